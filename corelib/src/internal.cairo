@@ -11,6 +11,24 @@ extern type index_enum_type<const NUM_VARIANTS: felt252>;
 /// Useful for debugging and panic tracking.
 extern fn trace<const FLAG: felt252>() nopanic;
 
+/// Returns the index of the arm taken by the innermost match enclosing the call.
+/// Can only be used when the `bind_match_arm_index` compilation flag is set.
+/// Note: This extern function is not mapped to a Sierra function, and all usages of it are replaced
+/// by constants during compilation.
+pub extern fn match_arm_index() -> felt252 nopanic;
+
+/// Emits a `'CatchAllReached'` event with the id of a match whose catch-all arm was taken.
+/// Called before the body of such arms when the `emit_catch_all_events` compilation flag is set.
+/// The id of a match is the offset of the match expression in its file.
+pub fn catch_all_reached(match_id: felt252) nopanic {
+    // Spans are constructed directly, as `span` is not `nopanic`.
+    let keys = array!['CatchAllReached'];
+    let data = array![match_id];
+    let _ = starknet::syscalls::emit_event_syscall(
+        crate::array::Span { snapshot: @keys }, crate::array::Span { snapshot: @data },
+    );
+}
+
 pub(crate) mod bounded_int;
 
 /// Same as `Option`, except that the order of the variants is reversed.
@@ -41,6 +41,7 @@ mod language_features {
     mod early_return_test;
     mod for_test;
     mod glob_use_test;
+    mod match_test;
     mod panics_test;
     mod trait_test;
     mod while_test;
@@ -0,0 +1,29 @@
+fn overlapping_ranges(x: u8) -> felt252 {
+    match x {
+        0..=100 => 0,
+        50..=150 => 1,
+        _ => 2,
+    }
+}
+
+#[test]
+fn test_match_overlapping_ranges() {
+    assert_eq!(overlapping_ranges(75), 0);
+    assert_eq!(overlapping_ranges(100), 0);
+    assert_eq!(overlapping_ranges(101), 1);
+    assert_eq!(overlapping_ranges(150), 1);
+    assert_eq!(overlapping_ranges(151), 2);
+}
+
+fn overlapping_ranges_covering_all_values(x: u8) -> felt252 {
+    match x {
+        0..=200 => 0,
+        100..=255 => 1,
+    }
+}
+
+#[test]
+fn test_match_overlapping_ranges_covering_all_values() {
+    assert_eq!(overlapping_ranges_covering_all_values(150), 0);
+    assert_eq!(overlapping_ranges_covering_all_values(201), 1);
+}
@@ -253,7 +253,7 @@ fn main() -> anyhow::Result<()> {
                 .generated_lowerings
                 .keys()
                 .sorted_by_key(|key| match key {
-                    GeneratedFunctionKey::Loop(id) => {
+                    GeneratedFunctionKey::Loop(id) | GeneratedFunctionKey::MatchArm(id) => {
                         (id.0.lookup(db).span_without_trivia(db.upcast()), "".into())
                     }
                     GeneratedFunctionKey::TraitFunc(trait_function, id) => (
@@ -6,9 +6,10 @@ use cairo_lang_filesystem::db::FilesGroup;
 use cairo_lang_filesystem::ids::{CrateId, Directory, FileId, FileKind, FileLongId, VirtualFile};
 use cairo_lang_parser::db::ParserGroup;
 use cairo_lang_syntax::attribute::consts::{
-    ALLOW_ATTR, ALLOW_ATTR_ATTR, DEPRECATED_ATTR, FEATURE_ATTR, FMT_SKIP_ATTR,
-    IMPLICIT_PRECEDENCE_ATTR, INLINE_ATTR, INTERNAL_ATTR, MUST_USE_ATTR, PHANTOM_ATTR,
-    STARKNET_INTERFACE_ATTR, UNSTABLE_ATTR,
+    ALLOW_ATTR, ALLOW_ATTR_ATTR, CONSISTENT_MATCH_ATTR, DEPRECATED_ATTR, FEATURE_ATTR,
+    FMT_SKIP_ATTR, IMPLICIT_PRECEDENCE_ATTR, INLINE_ATTR, INTERNAL_ATTR, MATCH_JUMP_TABLE_ATTR,
+    MATCH_STRATEGY_ATTR, MUST_USE_ATTR, NO_CATCH_ALL_ATTR, PHANTOM_ATTR, STARKNET_INTERFACE_ATTR,
+    UNSTABLE_ATTR,
 };
 use cairo_lang_syntax::attribute::structured::AttributeStructurize;
 use cairo_lang_syntax::node::ast::MaybeModuleBody;
@@ -329,6 +330,10 @@ fn allowed_attributes(db: &dyn DefsGroup, crate_id: CrateId) -> Arc<OrderedHashS
         FEATURE_ATTR,
         PHANTOM_ATTR,
         IMPLICIT_PRECEDENCE_ATTR,
+        MATCH_STRATEGY_ATTR,
+        MATCH_JUMP_TABLE_ATTR,
+        NO_CATCH_ALL_ATTR,
+        CONSISTENT_MATCH_ATTR,
         FMT_SKIP_ATTR,
         // TODO(orizi): Remove this once `starknet` is removed from corelib.
         STARKNET_INTERFACE_ATTR,
@@ -345,7 +350,7 @@ fn allowed_attributes(db: &dyn DefsGroup, crate_id: CrateId) -> Arc<OrderedHashS
 }
 
 fn allowed_statement_attributes(_db: &dyn DefsGroup) -> Arc<OrderedHashSet<String>> {
-    let all_attributes = [FMT_SKIP_ATTR, ALLOW_ATTR, FEATURE_ATTR];
+    let all_attributes = [FMT_SKIP_ATTR, ALLOW_ATTR, FEATURE_ATTR, MUST_USE_ATTR];
     Arc::new(OrderedHashSet::from_iter(all_attributes.map(|attr| attr.into())))
 }
 
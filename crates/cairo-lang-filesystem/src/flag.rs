@@ -7,8 +7,63 @@ pub enum Flag {
     /// Additionally controls addition of `redeposit_gas` which happens on default.
     AddWithdrawGas(bool),
     NumericMatchOptimizationMinArmsThreshold(usize),
+    /// The level non-exhaustive matches are reported at.
+    /// Default is `DiagnosticLevel::Error`.
+    ///
+    /// When not reported as errors, values that are not covered by any arm panic at runtime.
+    NonExhaustiveMatchSeverity(DiagnosticLevel),
+    /// Whether to report all the unreachable arms of a match in a single diagnostic.
+    ///
+    /// Default is false - report each unreachable arm separately.
+    AggregateUnreachableMatchArms(bool),
+    /// Whether the arms of a match that follow an arm that failed to lower are still lowered, to
+    /// report their diagnostics as well.
+    ///
+    /// Default is false - stop lowering the match at the first failed arm.
+    CollectAllMatchArmDiagnostics(bool),
+    /// Whether `core::internal::match_arm_index()` may be called in match arms, to get the index of
+    /// the arm taken.
+    ///
+    /// Default is false - calling it is an error.
+    BindMatchArmIndex(bool),
+    /// Whether the constants subtracted from the input of felt252 matches are defined once per
+    /// function, at its start, and shared by all of its matches.
+    ///
+    /// Default is false - define each constant where it is used, so it may be an immediate value.
+    PoolMatchConsts(bool),
+    /// Whether to check the internal invariants of match lowering, panicking when one is violated.
+    /// The checks are only compiled in debug builds of the compiler.
+    ///
+    /// Default is false - only check that the arms of each match select distinct branches.
+    ValidateMatchLowering(bool),
+    /// Whether to lower the body of each match arm into a generated function called from the arm,
+    /// to reduce the size of functions with large matches.
+    ///
+    /// Default is false - lower the arm bodies inline.
+    OutlineMatchArms(bool),
+    /// Whether to lower a match on a `bool` selecting between two `felt252` literals or variables
+    /// into arithmetic on the matched value, instead of branching.
+    ///
+    /// Default is false - lower such matches into a branch.
+    BranchlessSelect(bool),
+    /// Whether the catch-all arm of each match calls `core::internal::catch_all_reached` with the
+    /// id of the match before its body, emitting an event that the arm was taken.
+    ///
+    /// Default is false - do not emit such events.
+    EmitCatchAllEvents(bool),
     /// Whether to add panic backtrace handling to the generated code.
     ///
     /// Default is false - do not add, as it won't be used in production.
     PanicBacktrace(bool),
 }
+
+/// The level a diagnostic controlled by a flag is reported at.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DiagnosticLevel {
+    /// Report as an error, failing the compilation.
+    Error,
+    /// Report as a warning.
+    Warning,
+    /// Do not report.
+    Allow,
+}
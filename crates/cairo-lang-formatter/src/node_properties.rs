@@ -42,6 +42,11 @@ impl SyntaxNodeFormat for SyntaxNode {
             {
                 true
             }
+            SyntaxKind::TokenDotDot | SyntaxKind::TokenDotDotEq
+                if self.grandparent_kind(db) == Some(SyntaxKind::PatternRange) =>
+            {
+                true
+            }
             SyntaxKind::TokenOr => {
                 matches!(self.grandparent_kind(db), Some(SyntaxKind::ExprClosure))
             }
@@ -94,16 +99,21 @@ impl SyntaxNodeFormat for SyntaxNode {
 
     fn force_no_space_after(&self, db: &dyn SyntaxGroup) -> bool {
         match self.kind(db) {
+            SyntaxKind::TokenAt => {
+                !matches!(self.grandparent_kind(db), Some(SyntaxKind::PatternBinding))
+            }
             SyntaxKind::TokenDot
             | SyntaxKind::TokenNot
             | SyntaxKind::TokenBitNot
-            | SyntaxKind::TokenAt
             | SyntaxKind::TokenColonColon
             | SyntaxKind::TokenLParen
             | SyntaxKind::TokenLBrack
             | SyntaxKind::TokenImplicits => true,
             SyntaxKind::TerminalDotDot | SyntaxKind::TerminalDotDotEq
-                if matches!(self.parent_kind(db), Some(SyntaxKind::ExprBinary)) =>
+                if matches!(
+                    self.parent_kind(db),
+                    Some(SyntaxKind::ExprBinary | SyntaxKind::PatternRange)
+                ) =>
             {
                 true
             }
@@ -57,6 +57,29 @@ fn fib(a: Box<felt252>, b: Box<felt252>, n: Box<felt252>) -> Box<felt252> {
     // A trailing comment.
 }
 
+fn range_patterns(x: u8) -> u8 {
+    match x {
+        0..=9 => 0,
+        10..100 | 200 => 1,
+        _ => 2,
+    }
+}
+
+fn binding_patterns(x: Option<u8>) -> u8 {
+    match x {
+        y @ Option::Some(z @ 0) => 0,
+        _ => 1,
+    }
+}
+
+fn span_patterns(x: Span<u8>) -> u8 {
+    match x {
+        [] => 0,
+        [a, .. tail] => 1,
+        [.., b] | [..] => 2,
+    }
+}
+
 fn if_let_test() {
     if let (x, y) =
         (
@@ -10,9 +10,9 @@ use cairo_lang_debug::DebugWithDb;
 use cairo_lang_defs::diagnostic_utils::StableLocation;
 use cairo_lang_defs::ids::{
     EnumLongId, ExternFunctionLongId, ExternTypeLongId, FileIndex, FreeFunctionLongId,
-    FunctionWithBodyId, GenericParamId, GenericParamLongId, ImplDefId, ImplDefLongId,
-    ImplFunctionLongId, LanguageElementId, LocalVarId, LocalVarLongId, MemberLongId, ModuleFileId,
-    ModuleId, ParamLongId, PluginGeneratedFileId, PluginGeneratedFileLongId, StatementConstLongId,
+    GenericParamId, GenericParamLongId, ImplDefId, ImplDefLongId, ImplFunctionLongId,
+    LanguageElementId, LocalVarId, LocalVarLongId, MemberLongId, ModuleFileId, ModuleId,
+    ParamLongId, PluginGeneratedFileId, PluginGeneratedFileLongId, StatementConstLongId,
     StatementItemId, StatementUseLongId, StructLongId, SubmoduleId, SubmoduleLongId,
     TraitConstantId, TraitConstantLongId, TraitFunctionLongId, TraitTypeId, TraitTypeLongId,
     VariantLongId,
@@ -61,7 +61,7 @@ use crate::db::LoweringGroup;
 use crate::ids::{
     FunctionId, FunctionLongId, GeneratedFunction, GeneratedFunctionKey, LocationId, Signature,
 };
-use crate::lower::MultiLowering;
+use crate::lower::{MultiLowering, crate_function_with_body_ids};
 use crate::objects::{
     BlockId, MatchExternInfo, Statement, StatementCall, StatementConst, StatementStructDestructure,
     VariableId,
@@ -108,18 +108,7 @@ pub fn generate_crate_cache(
     db: &dyn LoweringGroup,
     crate_id: cairo_lang_filesystem::ids::CrateId,
 ) -> Maybe<Arc<[u8]>> {
-    let modules = db.crate_modules(crate_id);
-    let mut function_ids = Vec::new();
-    for module_id in modules.iter() {
-        for free_func in db.module_free_functions_ids(*module_id)?.iter() {
-            function_ids.push(FunctionWithBodyId::Free(*free_func));
-        }
-        for impl_id in db.module_impls_ids(*module_id)?.iter() {
-            for impl_func in db.impl_functions(*impl_id)?.values() {
-                function_ids.push(FunctionWithBodyId::Impl(*impl_func));
-            }
-        }
-    }
+    let function_ids = crate_function_with_body_ids(db, crate_id)?;
 
     let mut ctx = CacheSavingContext::new(db, crate_id);
     let cached = function_ids
@@ -1665,6 +1654,7 @@ impl ImplFunctionBodyCached {
 enum GeneratedFunctionKeyCached {
     Loop(SyntaxStablePtrIdCached),
     TraitFunc(LanguageElementCached, SyntaxStablePtrIdCached),
+    MatchArm(SyntaxStablePtrIdCached),
 }
 
 impl GeneratedFunctionKeyCached {
@@ -1682,6 +1672,9 @@ impl GeneratedFunctionKeyCached {
                     ),
                 )
             }
+            GeneratedFunctionKey::MatchArm(id) => GeneratedFunctionKeyCached::MatchArm(
+                SyntaxStablePtrIdCached::new(id.untyped(), &mut ctx.semantic_ctx),
+            ),
         }
     }
     fn embed(self, ctx: &mut CacheLoadingContext<'_>) -> GeneratedFunctionKey {
@@ -1697,6 +1690,9 @@ impl GeneratedFunctionKeyCached {
                     StableLocation::new(stable_location.embed(&mut ctx.semantic_ctx)),
                 )
             }
+            GeneratedFunctionKeyCached::MatchArm(id) => {
+                GeneratedFunctionKey::MatchArm(ExprPtr(id.embed(&mut ctx.semantic_ctx)))
+            }
         }
     }
 }
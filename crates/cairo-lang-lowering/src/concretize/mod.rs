@@ -1,10 +1,12 @@
 use cairo_lang_diagnostics::Maybe;
+use cairo_lang_semantic::items::constant::ConstValue;
 use cairo_lang_semantic::substitution::GenericSubstitution;
+use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
 use cairo_lang_utils::{Intern, LookupIntern};
 
 use crate::db::LoweringGroup;
 use crate::ids::{FunctionId, FunctionLongId, GeneratedFunction, SemanticFunctionIdEx};
-use crate::{FlatBlockEnd, FlatLowered, MatchArm, Statement};
+use crate::{FlatBlockEnd, FlatLowered, MatchArm, MatchInfo, Statement, StatementConst};
 
 /// Rewrites a [FunctionId] with a [GenericSubstitution].
 fn concretize_function(
@@ -43,6 +45,8 @@ pub fn concretize_lowered(
             *impl_id = substitution.substitute(db.upcast(), *impl_id)?;
         }
     }
+    // The enum values of the const generic parameters, which select the arm of the matches on them.
+    let mut generic_enum_values = UnorderedHashMap::<_, ConstValue>::default();
     // Substitute all statements.
     for block in lowered.blocks.iter_mut() {
         for stmt in block.statements.iter_mut() {
@@ -54,7 +58,11 @@ pub fn concretize_lowered(
                     stmt.variant = substitution.substitute(db.upcast(), stmt.variant.clone())?;
                 }
                 Statement::Const(stmt) => {
+                    let is_generic = matches!(stmt.value, ConstValue::Generic(_));
                     stmt.value = substitution.substitute(db.upcast(), stmt.value.clone())?;
+                    if is_generic && matches!(stmt.value, ConstValue::Enum(..)) {
+                        generic_enum_values.insert(stmt.output, stmt.value.clone());
+                    }
                 }
                 Statement::Snapshot(_)
                 | Statement::Desnap(_)
@@ -75,6 +83,23 @@ pub fn concretize_lowered(
             }
         }
     }
+    // Dispatch the matches on const generic enum parameters to the arm of their value. The other
+    // arms are left unreachable, and are removed by the optimizations.
+    for block in lowered.blocks.iter_mut() {
+        let FlatBlockEnd::Match { info: MatchInfo::Enum(info) } = &block.end else {
+            continue;
+        };
+        let Some(ConstValue::Enum(variant, value)) = generic_enum_values.get(&info.input.var_id)
+        else {
+            continue;
+        };
+        let arm = &info.arms[variant.idx];
+        let (output, block_id) = (arm.var_ids[0], arm.block_id);
+        block
+            .statements
+            .push(Statement::Const(StatementConst { value: (**value).clone(), output }));
+        block.end = FlatBlockEnd::Goto(block_id, Default::default());
+    }
     lowered.signature = substitution.substitute(db.upcast(), lowered.signature.clone())?;
 
     Ok(())
@@ -8,6 +8,9 @@ use cairo_lang_semantic::corelib::LiteralError;
 use cairo_lang_semantic::db::SemanticGroup;
 use cairo_lang_semantic::expr::inference::InferenceError;
 use cairo_lang_syntax::node::ids::SyntaxStablePtrId;
+use itertools::Itertools;
+use num_bigint::BigInt;
+use smol_str::SmolStr;
 
 use crate::Location;
 
@@ -53,7 +56,8 @@ impl DiagnosticEntry for LoweringDiagnostic {
             LoweringDiagnosticKind::DesnappingANonCopyableType { .. } => {
                 "Cannot desnap a non copyable type.".into()
             }
-            LoweringDiagnosticKind::MatchError(match_err) => match_err.format(),
+            LoweringDiagnosticKind::MatchError(match_err)
+            | LoweringDiagnosticKind::MatchWarning(match_err) => match_err.format(),
             LoweringDiagnosticKind::CannotInlineFunctionThatMightCallItself => {
                 "Cannot inline a function that might call itself.".into()
             }
@@ -82,12 +86,18 @@ impl DiagnosticEntry for LoweringDiagnostic {
             LoweringDiagnosticKind::EmptyRepeatedElementFixedSizeArray => {
                 "Fixed size array repeated element size must be greater than 0.".into()
             }
+            LoweringDiagnosticKind::MatchArmIndexUnavailable => {
+                "`match_arm_index()` can only be called inside a match arm, when the \
+                 `bind_match_arm_index` flag is set."
+                    .into()
+            }
         }
     }
 
     fn severity(&self) -> Severity {
         match self.kind {
-            LoweringDiagnosticKind::Unreachable { .. } => Severity::Warning,
+            LoweringDiagnosticKind::Unreachable { .. }
+            | LoweringDiagnosticKind::MatchWarning(_) => Severity::Warning,
             _ => Severity::Error,
         }
     }
@@ -123,6 +133,12 @@ impl MatchError {
             (MatchDiagnostic::UnsupportedMatchedType(matched_type), MatchKind::WhileLet(_, _)) => {
                 format!("Unsupported type in while-let. Type: `{}`.", matched_type)
             }
+            (MatchDiagnostic::UnsupportedMatchedNonNominalType(matched_type), _) => {
+                format!(
+                    "Unsupported matched type. Type: `{matched_type}`. Only values of a named \
+                     enum type can be matched, consider wrapping the value in a named enum."
+                )
+            }
             (MatchDiagnostic::UnsupportedMatchedValueTuple, MatchKind::Match) => {
                 "Unsupported matched value. Currently, match on tuples only supports enums as \
                  tuple members."
@@ -141,31 +157,92 @@ impl MatchError {
             (MatchDiagnostic::UnsupportedMatchArmNotAVariant, _) => {
                 "Unsupported pattern - not a variant.".into()
             }
+            (MatchDiagnostic::UnsupportedMatchArmGuard, _) => {
+                "Match arm guards are only supported in matches on enums.".into()
+            }
+            (MatchDiagnostic::UnsupportedInnerOrPattern, _) => "Unsupported or-pattern - \
+                                                                alternatives must be variants of \
+                                                                an enum or numeric literals."
+                .into(),
+            (MatchDiagnostic::UnknownVariantInMatch { variant, enum_name, valid_variants }, _) => {
+                format!(
+                    "Variant `{variant}` does not belong to the matched enum `{enum_name}`. Valid \
+                     variants are: {}.",
+                    valid_variants.iter().map(|name| format!("`{name}`")).join(", ")
+                )
+            }
             (MatchDiagnostic::UnsupportedMatchArmNotATuple, _) => {
                 "Unsupported pattern - not a tuple.".into()
             }
+            (MatchDiagnostic::UnsupportedMatchArmNotAStruct, _) => {
+                "Unsupported pattern - only struct patterns and `_` are supported in a match on a \
+                 struct."
+                    .into()
+            }
+
+            (MatchDiagnostic::UnsupportedMatchArmNotAnArray, _) => {
+                "Unsupported pattern - only array patterns and `_` are supported in a match on an \
+                 array or a span."
+                    .into()
+            }
 
             (MatchDiagnostic::UnsupportedMatchArmNotALiteral, MatchKind::Match) => {
                 "Unsupported match arm - not a literal.".into()
             }
-            (MatchDiagnostic::UnsupportedMatchArmNonSequential, MatchKind::Match) => {
-                "Unsupported match - numbers must be sequential starting from 0.".into()
+            (MatchDiagnostic::UnsupportedMatchArmNonSequential { missing }, MatchKind::Match) => {
+                match missing {
+                    Some(missing) => format!(
+                        "Unsupported match - numbers must be sequential starting from 0, but \
+                         `{missing}` is not covered."
+                    ),
+                    None => {
+                        "Unsupported match - numbers must be sequential starting from 0.".into()
+                    }
+                }
             }
-            (MatchDiagnostic::NonExhaustiveMatchFelt252, MatchKind::Match) => {
-                "Match is non exhaustive - match over a numerical value must have a wildcard card \
-                 pattern (`_`)."
-                    .into()
+            (MatchDiagnostic::LiteralOutOfRangeForMatch { min, max }, MatchKind::Match) => {
+                format!(
+                    "Literal is out of the range of the matched value - expected a value between \
+                     {min} and {max}."
+                )
+            }
+            (MatchDiagnostic::NonExhaustiveMatchFelt252(suggestion), MatchKind::Match) => {
+                format!(
+                    "Match is non exhaustive - match over a numerical value must have a wildcard \
+                     card pattern (`_`). Consider adding `{} => ...` as the last arm.",
+                    suggestion.pattern
+                )
             }
 
             (
                 MatchDiagnostic::UnsupportedMatchArmNotALiteral
-                | MatchDiagnostic::UnsupportedMatchArmNonSequential
-                | MatchDiagnostic::NonExhaustiveMatchFelt252,
+                | MatchDiagnostic::UnsupportedMatchArmNonSequential { .. }
+                | MatchDiagnostic::LiteralOutOfRangeForMatch { .. }
+                | MatchDiagnostic::NonExhaustiveMatchFelt252(_),
                 MatchKind::IfLet | MatchKind::WhileLet(_, _),
             ) => unreachable!("Numeric values are not supported in if/while-let conditions."),
 
-            (MatchDiagnostic::MissingMatchArm(variant), MatchKind::Match) => {
-                format!("Missing match arm: `{}` not covered.", variant)
+            (MatchDiagnostic::MissingMatchArm(variants), MatchKind::Match) => {
+                match variants.as_slice() {
+                    [variant] => format!("Missing match arm: `{variant}` not covered."),
+                    [init @ .., last] => format!(
+                        "Missing match arms: {} and `{last}` not covered.",
+                        init.iter().map(|variant| format!("`{variant}`")).join(", ")
+                    ),
+                    [] => unreachable!("A missing match arm is reported for some variant."),
+                }
+            }
+            (MatchDiagnostic::NonExhaustiveMatchSpanLength(lengths), _) => {
+                match lengths.as_slice() {
+                    [length] => {
+                        format!("Match on a span is non exhaustive - `{length}` not covered.")
+                    }
+                    [init @ .., last] => format!(
+                        "Match on a span is non exhaustive - {} and `{last}` not covered.",
+                        init.iter().map(|length| format!("`{length}`")).join(", ")
+                    ),
+                    [] => unreachable!("A non exhaustive span match misses some length."),
+                }
             }
             (MatchDiagnostic::MissingMatchArm(_), MatchKind::IfLet) => {
                 unreachable!("If-let is not required to be exhaustive.")
@@ -183,6 +260,9 @@ impl MatchError {
             (MatchDiagnostic::UnreachableMatchArm, MatchKind::WhileLet(_, _)) => {
                 unreachable!("While-let is does not have two arms.")
             }
+            (MatchDiagnostic::UnreachableMatchArms(n_unreachable), _) => {
+                format!("Unreachable pattern arms: {n_unreachable} patterns are never matched.")
+            }
             (MatchDiagnostic::UnsupportedNumericInLetCondition, MatchKind::Match) => {
                 unreachable!("Numeric values are supported in match conditions.")
             }
@@ -192,6 +272,70 @@ impl MatchError {
             (MatchDiagnostic::UnsupportedNumericInLetCondition, MatchKind::WhileLet(_, _)) => {
                 "Numeric values are not supported in while-let conditions.".into()
             }
+            (MatchDiagnostic::UnsupportedMatchStrategyArguments, _) => {
+                "Unsupported `match_strategy` arguments - expected `jump_table` or `if_chain`."
+                    .into()
+            }
+            (MatchDiagnostic::UnsupportedMatchJumpTableArguments, _) => {
+                "Unsupported `match_jump_table` arguments - expected `min_arms = N`, for a \
+                 non-negative integer literal `N`."
+                    .into()
+            }
+            (MatchDiagnostic::ConflictingMatchJumpTableThresholds, _) => {
+                "Conflicting numeric match thresholds - the `match_jump_table` and \
+                 `match_strategy` attributes of the function must agree."
+                    .into()
+            }
+            (MatchDiagnostic::CatchAllForbidden(enum_name), _) => {
+                format!(
+                    "Catch-all patterns are forbidden in matches on `{enum_name}`, as it is \
+                     marked with `#[no_catch_all]`."
+                )
+            }
+            (MatchDiagnostic::UnsupportedConsistentMatchArguments, _) => {
+                "Unsupported `consistent_match` arguments - expected the name of a free function \
+                 in the same module."
+                    .into()
+            }
+            (MatchDiagnostic::MatchCoverageMismatch { function, variants }, _) => {
+                format!(
+                    "Match does not handle the same variants as the linked match in `{function}`. \
+                     Differing variants: {}.",
+                    variants.iter().map(|name| format!("`{name}`")).join(", ")
+                )
+            }
+            (MatchDiagnostic::EmptyMatchArmPatterns, _) => "Match arm has no patterns.".into(),
+            (MatchDiagnostic::RedundantSnapshotInMatchedExpr, _) => {
+                "Redundant snapshot of the matched expression - no arm binds a payload. Consider \
+                 removing the `@`."
+                    .into()
+            }
+            (MatchDiagnostic::MatchCoverageRegression(variants), _) => {
+                format!(
+                    "Match no longer handles variants handled explicitly in the coverage \
+                     baseline: {}.",
+                    variants.iter().map(|name| format!("`{name}`")).join(", ")
+                )
+            }
+            (MatchDiagnostic::NumericMatchOnEnumConversion(enum_name), _) => {
+                format!(
+                    "Match on a numeric conversion of a value of the enum `{enum_name}`. Consider \
+                     matching on the enum directly."
+                )
+            }
+            (MatchDiagnostic::SingleVariantMatchChain, _) => {
+                "Chained matches each handling a single variant. Consider using an `if let ... \
+                 else if let ...` chain instead."
+                    .into()
+            }
+            (MatchDiagnostic::MatchArmGuardAlwaysTrue, _) => {
+                "Match arm guard is always true. Consider removing it.".into()
+            }
+            (MatchDiagnostic::DuplicateMatchArmBody, _) => {
+                "Match arm body is identical to the body of a previous arm binding the same \
+                 variables. Consider merging their patterns with `|`."
+                    .into()
+            }
         }
     }
 }
@@ -202,6 +346,7 @@ pub enum LoweringDiagnosticKind {
     VariableMoved { inference_error: InferenceError },
     VariableNotDropped { drop_err: InferenceError, destruct_err: InferenceError },
     MatchError(MatchError),
+    MatchWarning(MatchError),
     DesnappingANonCopyableType { inference_error: InferenceError },
     UnexpectedError,
     CannotInlineFunctionThatMightCallItself,
@@ -212,6 +357,7 @@ pub enum LoweringDiagnosticKind {
     EmptyRepeatedElementFixedSizeArray,
     UnsupportedPattern,
     Unsupported,
+    MatchArmIndexUnavailable,
 }
 
 /// Error in a match-like construct.
@@ -230,19 +376,93 @@ pub enum MatchKind {
     WhileLet(semantic::ExprId, SyntaxStablePtrId),
 }
 
+/// An arm suggested to be added to a match, structured so that a tool may insert it.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MatchArmSuggestion {
+    /// The pattern of the suggested arm, e.g. `_`.
+    pub pattern: SmolStr,
+    /// The closing brace of the match, before which the suggested arm is inserted.
+    pub insert_before: SyntaxStablePtrId,
+}
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum MatchDiagnostic {
     /// TODO(TomerStarkware): Get rid of the string and pass the type information directly.
     UnsupportedMatchedType(String),
+    /// A matched type which may be an enum only once its generic arguments are known.
+    UnsupportedMatchedNonNominalType(String),
     UnsupportedMatchedValueTuple,
     UnsupportedMatchArmNotAVariant,
+    /// A guard on an arm of a match on a value which is not an enum.
+    UnsupportedMatchArmGuard,
+    /// An or-pattern in the pattern of a variant, with an alternative which is neither a variant
+    /// nor a numeric literal.
+    UnsupportedInnerOrPattern,
+    /// A pattern of a variant of an enum other than the matched one.
+    UnknownVariantInMatch {
+        variant: String,
+        enum_name: String,
+        valid_variants: Vec<SmolStr>,
+    },
     UnsupportedMatchArmNotATuple,
+    /// A pattern other than a struct pattern or `_` in a match on a struct.
+    UnsupportedMatchArmNotAStruct,
+    /// A pattern other than an array pattern or `_` in a match on a fixed size array or a span.
+    UnsupportedMatchArmNotAnArray,
 
     UnreachableMatchArm,
-    MissingMatchArm(String),
+    /// Several unreachable arms, reported together.
+    UnreachableMatchArms(usize),
+    /// The variants, or combinations of variants of a tuple, not covered by any arm of a match, in
+    /// declaration order.
+    MissingMatchArm(Vec<String>),
+    /// The lengths of a span not covered by any arm of a match on it, formatted as span patterns,
+    /// e.g. `[_, _]` or `[_, ..]`.
+    NonExhaustiveMatchSpanLength(Vec<String>),
+    /// A catch-all pattern in a match on an enum marked with `#[no_catch_all]`.
+    CatchAllForbidden(String),
 
     UnsupportedMatchArmNotALiteral,
-    UnsupportedMatchArmNonSequential,
-    NonExhaustiveMatchFelt252,
+    /// Numeric literals of a match which are not sequential from 0, with the smallest value not
+    /// covered by them, if it is below some of them.
+    UnsupportedMatchArmNonSequential {
+        missing: Option<usize>,
+    },
+    LiteralOutOfRangeForMatch {
+        min: BigInt,
+        max: BigInt,
+    },
+    /// A numeric match without a wildcard arm, with the suggested wildcard arm to add.
+    NonExhaustiveMatchFelt252(MatchArmSuggestion),
     UnsupportedNumericInLetCondition,
+    UnsupportedMatchStrategyArguments,
+    UnsupportedMatchJumpTableArguments,
+    /// A `match_jump_table` attribute setting a threshold different from the one set by another
+    /// `match_jump_table` or `match_strategy` attribute of the same function.
+    ConflictingMatchJumpTableThresholds,
+    UnsupportedConsistentMatchArguments,
+    /// A match handling explicitly different variants than a match linked to it by the
+    /// `consistent_match` attribute.
+    MatchCoverageMismatch {
+        function: SmolStr,
+        variants: Vec<SmolStr>,
+    },
+    /// A match no longer handling explicitly the given variants, which it handled explicitly in a
+    /// coverage baseline.
+    MatchCoverageRegression(Vec<SmolStr>),
+    /// A match arm without any pattern, which may only come from malformed semantic input.
+    EmptyMatchArmPatterns,
+
+    RedundantSnapshotInMatchedExpr,
+    /// A match with an arm of a single variant and a catch-all arm whose body is another such
+    /// match, which is clearer as an `if let` chain.
+    SingleVariantMatchChain,
+    /// A match on a numeric value converted from an enum value, which is clearer as a match on the
+    /// enum.
+    NumericMatchOnEnumConversion(String),
+    /// A reachable match arm whose body is identical to the body of a previous reachable arm, which
+    /// binds the same variables. Allowed by `#[allow(duplicate_match_arm_body)]`.
+    DuplicateMatchArmBody,
+    /// A guard of a match arm which always holds.
+    MatchArmGuardAlwaysTrue,
 }
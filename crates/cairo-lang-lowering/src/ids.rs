@@ -215,7 +215,10 @@ impl ConcreteFunctionWithBodyId {
         Ok(match self.lookup_intern(db) {
             ConcreteFunctionWithBodyLongId::Semantic(id) => id.stable_location(semantic_db),
             ConcreteFunctionWithBodyLongId::Generated(generated) => match generated.key {
-                GeneratedFunctionKey::Loop(stable_ptr) => StableLocation::new(stable_ptr.untyped()),
+                GeneratedFunctionKey::Loop(stable_ptr)
+                | GeneratedFunctionKey::MatchArm(stable_ptr) => {
+                    StableLocation::new(stable_ptr.untyped())
+                }
                 GeneratedFunctionKey::TraitFunc(_, stable_location) => stable_location,
             },
         })
@@ -375,6 +378,8 @@ pub enum GeneratedFunctionKey {
     /// Generated loop functions are identified by the loop expr_id.
     Loop(ExprPtr),
     TraitFunc(TraitFunctionId, StableLocation),
+    /// Generated functions of outlined match arm bodies are identified by the arm body expr_id.
+    MatchArm(ExprPtr),
 }
 
 /// Generated function.
@@ -399,7 +404,7 @@ impl<'a> DebugWithDb<dyn LoweringGroup + 'a> for GeneratedFunction {
         db: &(dyn LoweringGroup + 'a),
     ) -> std::fmt::Result {
         match self.key {
-            GeneratedFunctionKey::Loop(expr_ptr) => {
+            GeneratedFunctionKey::Loop(expr_ptr) | GeneratedFunctionKey::MatchArm(expr_ptr) => {
                 let mut func_ptr = expr_ptr.untyped();
                 while !matches!(
                     func_ptr.kind(db.upcast()),
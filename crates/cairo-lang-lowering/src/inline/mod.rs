@@ -22,7 +22,8 @@ use crate::diagnostic::{
     LoweringDiagnostic, LoweringDiagnosticKind, LoweringDiagnostics, LoweringDiagnosticsBuilder,
 };
 use crate::ids::{
-    ConcreteFunctionWithBodyId, FunctionWithBodyId, FunctionWithBodyLongId, LocationId,
+    ConcreteFunctionWithBodyId, ConcreteFunctionWithBodyLongId, FunctionWithBodyId,
+    FunctionWithBodyLongId, GeneratedFunction, GeneratedFunctionKey, LocationId,
 };
 use crate::lower::context::{VarRequest, VariableAllocator};
 use crate::utils::{InliningStrategy, Rebuilder, RebuilderEx};
@@ -64,6 +65,16 @@ pub fn priv_should_inline(
         return Ok(false);
     }
 
+    // Outlined match arms are kept as functions of their own, as inlining them would undo the
+    // outlining.
+    if let ConcreteFunctionWithBodyLongId::Generated(GeneratedFunction {
+        key: GeneratedFunctionKey::MatchArm(_),
+        ..
+    }) = function_id.lookup_intern(db)
+    {
+        return Ok(false);
+    }
+
     let config = db.function_declaration_inline_config(
         function_id.function_with_body_id(db).base_semantic_function(db),
     )?;
@@ -18,6 +18,8 @@ use crate::db::LoweringGroup;
 use crate::diagnostic::{LoweringDiagnosticKind, LoweringDiagnosticsBuilder};
 use crate::ids::LocationId;
 use crate::lower::refs::ClosureInfo;
+#[cfg(debug_assertions)]
+use crate::optimizations::validate::{validate_match_arms_layout, validate_match_info};
 use crate::{
     BlockId, FlatBlock, FlatBlockEnd, MatchInfo, Statement, VarRemapping, VarUsage, VariableId,
 };
@@ -234,6 +236,24 @@ impl BlockBuilder {
 
     /// Ends a block with known ending information. Used by [SealedBlockBuilder].
     pub fn finalize(self, ctx: &mut LoweringContext<'_, '_>, end: FlatBlockEnd) {
+        #[cfg(debug_assertions)]
+        if let FlatBlockEnd::Match { info } = &end {
+            validate_match_info(info)
+                .unwrap_or_else(|err| panic!("Invalid match: {}", err.to_message()));
+        }
+        #[cfg(debug_assertions)]
+        if ctx.validate_match_lowering {
+            assert!(
+                !ctx.blocks.0[self.block_id.0].is_set(),
+                "Block {} is finalized more than once.",
+                self.block_id.0
+            );
+            if let FlatBlockEnd::Match { info } = &end {
+                validate_match_arms_layout(info).unwrap_or_else(|err| {
+                    panic!("Invalid match in block {}: {}", self.block_id.0, err.to_message())
+                });
+            }
+        }
         let block = FlatBlock { statements: self.statements.statements, end };
         ctx.blocks.set_block(self.block_id, block);
     }
@@ -3,15 +3,22 @@ use std::sync::Arc;
 
 use cairo_lang_defs::ids::{LanguageElementId, ModuleFileId};
 use cairo_lang_diagnostics::{DiagnosticAdded, Maybe};
+#[cfg(debug_assertions)]
+use cairo_lang_filesystem::flag::Flag;
+#[cfg(debug_assertions)]
+use cairo_lang_filesystem::ids::FlagId;
 use cairo_lang_semantic::ConcreteVariant;
 use cairo_lang_semantic::expr::fmt::ExprFormatter;
+use cairo_lang_semantic::items::constant::ConstValue;
 use cairo_lang_semantic::items::enm::SemanticEnumEx;
 use cairo_lang_semantic::items::imp::ImplLookupContext;
 use cairo_lang_semantic::usage::Usages;
+use cairo_lang_syntax::node::ast;
 use cairo_lang_syntax::node::ids::SyntaxStablePtrId;
 use cairo_lang_utils::Intern;
 use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
 use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
+use cairo_lang_utils::unordered_hash_set::UnorderedHashSet;
 use defs::diagnostic_utils::StableLocation;
 use id_arena::Arena;
 use itertools::{Itertools, zip_eq};
@@ -22,6 +29,7 @@ use {cairo_lang_defs as defs, cairo_lang_semantic as semantic};
 
 use super::block_builder::{BlockBuilder, SealedBlockBuilder};
 use super::generators;
+use super::lower_match::{MatchCoverageBaseline, MatchStrategyHistogram};
 use crate::blocks::FlatBlocksBuilder;
 use crate::db::LoweringGroup;
 use crate::diagnostic::LoweringDiagnostics;
@@ -84,6 +92,14 @@ impl Index<VariableId> for VariableAllocator<'_> {
     }
 }
 
+/// A transform of the body of a match arm, applied right before the arm is lowered.
+///
+/// Given the arenas of the function body and the expression of the arm, returns the expression to
+/// lower instead. New expressions should be allocated in the given arenas and reuse the stable
+/// pointer of the original expression, so that diagnostics still point to the arm.
+pub type MatchArmTransform<'db> =
+    Box<dyn FnMut(&mut semantic::Arenas, semantic::ExprId) -> semantic::ExprId + 'db>;
+
 /// Lowering context for the encapsulating semantic function.
 ///
 /// Each semantic function may generate multiple lowered functions. This context is common to all
@@ -104,6 +120,33 @@ pub struct EncapsulatingLoweringContext<'db> {
     pub usages: Usages,
     /// Lowerings of generated functions.
     pub lowerings: OrderedHashMap<GeneratedFunctionKey, FlatLowered>,
+    /// A transform applied to the body of each match arm before it is lowered, if any.
+    pub match_arm_transform: Option<MatchArmTransform<'db>>,
+    /// The indices of the arms of the matches enclosing the expression currently being lowered,
+    /// innermost last.
+    pub match_arm_indices: Vec<usize>,
+    /// Whether identical diagnostics reported on the patterns of a match arm by several of its
+    /// alternatives are reported only once.
+    pub dedup_match_arm_diagnostics: bool,
+    /// The depth of the decision tree of each lowered match on a tuple, by the location of the
+    /// match. Deep trees are a common cause of slow compilation.
+    pub tuple_match_tree_depths: OrderedHashMap<LocationId, usize>,
+    /// The matches which are part of a chain of single variant matches that was already reported,
+    /// so that the chain is reported only once.
+    pub single_variant_match_chains: UnorderedHashSet<ast::ExprPtr>,
+    /// The number of match expressions lowered with each strategy.
+    pub match_strategies: MatchStrategyHistogram,
+    /// Whether each lowered match on an enum was lowered by matching directly on the extern
+    /// function call producing the enum, rather than on the materialized enum, by the match.
+    pub optimized_extern_matches: OrderedHashMap<ast::ExprPtr, bool>,
+    /// The baseline to compare the variants handled explicitly by each match on an enum against,
+    /// if any.
+    pub match_coverage_baseline: Option<Arc<MatchCoverageBaseline>>,
+    /// The variants handled explicitly by each match on an enum, recorded if a baseline is given.
+    pub match_coverage: MatchCoverageBaseline,
+    /// The threshold for lowering numeric matches into a jump table set by the attributes of the
+    /// function, if any. Read once when the function is lowered.
+    pub function_match_threshold: Option<usize>,
 }
 impl<'db> EncapsulatingLoweringContext<'db> {
     pub fn new(
@@ -120,6 +163,16 @@ impl<'db> EncapsulatingLoweringContext<'db> {
             expr_formatter: ExprFormatter { db: db.upcast(), function_id: semantic_function_id },
             usages,
             lowerings: Default::default(),
+            match_arm_transform: None,
+            match_arm_indices: vec![],
+            dedup_match_arm_diagnostics: true,
+            tuple_match_tree_depths: Default::default(),
+            single_variant_match_chains: Default::default(),
+            match_strategies: Default::default(),
+            optimized_extern_matches: Default::default(),
+            match_coverage_baseline: None,
+            match_coverage: Default::default(),
+            function_match_threshold: None,
         })
     }
 }
@@ -158,6 +211,15 @@ pub struct LoweringContext<'a, 'db> {
     pub blocks: FlatBlocksBuilder,
     // The return type in the current context, for loops this differs from signature.return_type.
     pub return_type: semantic::TypeId,
+    /// Constants shared by the matches of the function, defined once at the start of its root
+    /// block.
+    pub const_pool: OrderedHashMap<ConstValue, VariableId>,
+    /// The variants of the enums matched in the function, fetched once per enum.
+    concrete_enum_variants_cache:
+        UnorderedHashMap<semantic::ConcreteEnumId, Arc<[ConcreteVariant]>>,
+    /// Whether the internal invariants of match lowering are checked when finalizing blocks.
+    #[cfg(debug_assertions)]
+    pub validate_match_lowering: bool,
 }
 impl<'a, 'db> LoweringContext<'a, 'db> {
     pub fn new(
@@ -182,9 +244,24 @@ impl<'a, 'db> LoweringContext<'a, 'db> {
             diagnostics: LoweringDiagnostics::default(),
             blocks: Default::default(),
             return_type,
+            const_pool: Default::default(),
+            concrete_enum_variants_cache: Default::default(),
+            #[cfg(debug_assertions)]
+            validate_match_lowering: validate_match_lowering(db),
         })
     }
 }
+
+/// Returns whether the internal invariants of match lowering should be checked.
+#[cfg(debug_assertions)]
+fn validate_match_lowering(db: &dyn LoweringGroup) -> bool {
+    db.get_flag(FlagId::new(db.upcast(), "validate_match_lowering"))
+        .map(|flag| match *flag {
+            Flag::ValidateMatchLowering(validate) => validate,
+            _ => panic!("Wrong type flag `{flag:?}`."),
+        })
+        .unwrap_or(false)
+}
 impl<'db> Deref for LoweringContext<'_, 'db> {
     type Target = EncapsulatingLoweringContext<'db>;
 
@@ -198,6 +275,20 @@ impl DerefMut for LoweringContext<'_, '_> {
     }
 }
 impl LoweringContext<'_, '_> {
+    /// Returns the variants of the given enum, fetched from the database once per function.
+    pub fn concrete_enum_variants(
+        &mut self,
+        concrete_enum_id: semantic::ConcreteEnumId,
+    ) -> Maybe<Arc<[ConcreteVariant]>> {
+        if let Some(variants) = self.concrete_enum_variants_cache.get(&concrete_enum_id) {
+            return Ok(variants.clone());
+        }
+        let variants: Arc<[ConcreteVariant]> =
+            self.db.concrete_enum_variants(concrete_enum_id)?.into();
+        self.concrete_enum_variants_cache.insert(concrete_enum_id, variants.clone());
+        Ok(variants)
+    }
+
     /// Allocates a new variable in the context's variable arena according to the context.
     pub fn new_var(&mut self, req: VarRequest) -> VariableId {
         self.variables.new_var(req)
@@ -215,6 +306,25 @@ impl LoweringContext<'_, '_> {
     pub fn get_location(&self, stable_ptr: SyntaxStablePtrId) -> LocationId {
         self.variables.get_location(stable_ptr)
     }
+
+    /// Returns a usage of the constant `value` from the constant pool of the function, adding it
+    /// to the pool if it is not there yet.
+    pub fn pooled_const(
+        &mut self,
+        value: ConstValue,
+        ty: semantic::TypeId,
+        location: LocationId,
+    ) -> VarUsage {
+        let var_id = match self.const_pool.get(&value) {
+            Some(var_id) => *var_id,
+            None => {
+                let var_id = self.new_var(VarRequest { ty, location });
+                self.const_pool.insert(value, var_id);
+                var_id
+            }
+        };
+        VarUsage { var_id, location }
+    }
 }
 
 /// Request for a lowered variable allocation.
@@ -337,15 +447,13 @@ impl LoweredExprExternEnum {
         ctx: &mut LoweringContext<'_, '_>,
         builder: &mut BlockBuilder,
     ) -> LoweringResult<VarUsage> {
-        let concrete_variants = ctx
-            .db
-            .concrete_enum_variants(self.concrete_enum_id)
-            .map_err(LoweringFlowError::Failed)?;
+        let concrete_variants =
+            ctx.concrete_enum_variants(self.concrete_enum_id).map_err(LoweringFlowError::Failed)?;
 
         let mut arm_var_ids = vec![];
         let (sealed_blocks, block_ids): (Vec<_>, Vec<_>) = concrete_variants
-            .clone()
-            .into_iter()
+            .iter()
+            .cloned()
             .map(|concrete_variant| {
                 let mut subscope = builder.child_block_builder(ctx.blocks.alloc_empty());
                 let block_id = subscope.block_id;
@@ -393,7 +501,7 @@ impl LoweredExprExternEnum {
         let match_info = MatchInfo::Extern(MatchExternInfo {
             function: self.function.lowered(ctx.db),
             inputs: self.inputs,
-            arms: zip_eq(zip_eq(concrete_variants, block_ids), arm_var_ids)
+            arms: zip_eq(zip_eq(concrete_variants.iter().cloned(), block_ids), arm_var_ids)
                 .map(|((variant_id, block_id), var_ids)| MatchArm {
                     arm_selector: MatchArmSelector::VariantId(variant_id),
                     block_id,
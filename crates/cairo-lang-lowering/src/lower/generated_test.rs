@@ -73,6 +73,7 @@ fn test_generated_function(
             let func_description = match key {
                 crate::ids::GeneratedFunctionKey::Loop(_) => "loop".into(),
                 crate::ids::GeneratedFunctionKey::TraitFunc(func, _) => func.full_path(db),
+                crate::ids::GeneratedFunctionKey::MatchArm(_) => "match arm".into(),
             };
 
             writeln!(
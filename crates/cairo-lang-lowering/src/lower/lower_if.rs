@@ -16,7 +16,7 @@ use crate::ids::LocationId;
 use crate::lower::context::VarRequest;
 use crate::lower::lower_match::{
     MatchArmWrapper, TupleInfo, lower_concrete_enum_match, lower_expr_match_tuple,
-    lower_optimized_extern_match,
+    lower_optimized_extern_match, try_extract_extern_enum,
 };
 use crate::lower::{create_subscope, lower_block, lower_expr, lower_expr_to_var_usage};
 use crate::{MatchArm, MatchEnumInfo, MatchInfo};
@@ -121,8 +121,8 @@ pub fn lower_expr_if_let(
     let (n_snapshots, long_type_id) = peel_snapshots(ctx.db.upcast(), ty);
 
     let arms = vec![
-        MatchArmWrapper { patterns: patterns.into(), expr: Some(expr.if_block) },
-        MatchArmWrapper { patterns: vec![], expr: expr.else_block },
+        MatchArmWrapper { patterns: patterns.into(), guard: None, expr: Some(expr.if_block) },
+        MatchArmWrapper { patterns: vec![], guard: None, expr: expr.else_block },
     ];
 
     if let Some(types) = try_extract_matches!(long_type_id, TypeLongId::Tuple) {
@@ -139,9 +139,19 @@ pub fn lower_expr_if_let(
 
     // TODO(spapini): Use diagnostics.
     // TODO(spapini): Handle more than just enums.
-    if let LoweredExpr::ExternEnum(extern_enum) = lowered_expr {
-        return lower_optimized_extern_match(ctx, builder, extern_enum, &arms, MatchKind::IfLet);
-    }
+    let lowered_expr = match try_extract_extern_enum(lowered_expr) {
+        Ok((extern_enum, n_snapshots)) => {
+            return lower_optimized_extern_match(
+                ctx,
+                builder,
+                extern_enum,
+                n_snapshots,
+                &arms,
+                MatchKind::IfLet,
+            );
+        }
+        Err(lowered_expr) => lowered_expr,
+    };
     lower_concrete_enum_match(
         ctx,
         builder,
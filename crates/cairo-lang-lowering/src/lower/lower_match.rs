@@ -1,50 +1,190 @@
+use std::sync::Arc;
+
 use cairo_lang_debug::DebugWithDb;
-use cairo_lang_defs::ids::NamedLanguageElementId;
-use cairo_lang_filesystem::flag::Flag;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::diagnostic_utils::StableLocation;
+use cairo_lang_defs::ids::{
+    FunctionWithBodyId, LanguageElementId, ModuleItemId, NamedLanguageElementId,
+    TopLevelLanguageElementId,
+};
+use cairo_lang_diagnostics::{DiagnosticAdded, DiagnosticEntry, DiagnosticNote, Maybe, Severity};
+use cairo_lang_filesystem::flag::{DiagnosticLevel, Flag};
 use cairo_lang_filesystem::ids::FlagId;
 use cairo_lang_semantic as semantic;
 use cairo_lang_semantic::corelib;
-use cairo_lang_syntax::node::TypedStablePtr;
+use cairo_lang_semantic::items::attribute::SemanticQueryAttrs;
+use cairo_lang_semantic::usage::Usages;
+use cairo_lang_syntax::attribute::consts::{
+    ALLOW_ATTR, CONSISTENT_MATCH_ATTR, MATCH_JUMP_TABLE_ATTR, MATCH_STRATEGY_ATTR,
+    NO_CATCH_ALL_ATTR,
+};
+use cairo_lang_syntax::attribute::structured::{AttributeArg, AttributeArgVariant};
 use cairo_lang_syntax::node::ids::SyntaxStablePtrId;
+use cairo_lang_syntax::node::{TypedStablePtr, TypedSyntaxNode, ast};
+use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
 use cairo_lang_utils::unordered_hash_map::{Entry, UnorderedHashMap};
-use cairo_lang_utils::{LookupIntern, try_extract_matches};
-use itertools::{Itertools, zip_eq};
-use num_traits::ToPrimitive;
+use cairo_lang_utils::unordered_hash_set::UnorderedHashSet;
+use cairo_lang_utils::{Intern, LookupIntern, extract_matches, require, try_extract_matches};
+use itertools::{Itertools, chain, zip_eq};
+use num_bigint::{BigInt, Sign};
+use num_traits::{ToPrimitive, Zero};
 use semantic::corelib::unit_ty;
-use semantic::items::enm::SemanticEnumEx;
+use semantic::items::constant::{ConstValue, try_evaluate_const_expr};
+use semantic::items::functions::{GenericFunctionId, ImplGenericFunctionId};
+use semantic::items::imp::ImplLongId;
 use semantic::types::{peel_snapshots, wrap_in_snapshots};
 use semantic::{
-    ConcreteTypeId, MatchArmSelector, Pattern, PatternEnumVariant, PatternId, TypeLongId,
-    ValueSelectorArm,
+    ConcreteTypeId, GenericArgumentId, MatchArmSelector, Pattern, PatternEnumVariant, PatternId,
+    TypeLongId, ValueSelectorArm,
 };
+use smol_str::SmolStr;
 
 use super::block_builder::{BlockBuilder, SealedBlockBuilder};
 use super::context::{
-    LoweredExpr, LoweredExprExternEnum, LoweringContext, LoweringFlowError, LoweringResult,
-    lowering_flow_error_to_sealed_block,
+    EncapsulatingLoweringContext, LoweredExpr, LoweredExprExternEnum, LoweringContext,
+    LoweringFlowError, LoweringResult, lowering_flow_error_to_sealed_block,
 };
 use super::{
     alloc_empty_block, call_loop_func, generators, lower_expr_block, lower_expr_literal,
-    lower_tail_expr, lowered_expr_to_block_scope_end,
+    lower_expr_to_var_usage, lower_outlined_expr, lower_tail_expr, lowered_expr_to_block_scope_end,
 };
 use crate::diagnostic::LoweringDiagnosticKind::*;
-use crate::diagnostic::{LoweringDiagnosticsBuilder, MatchDiagnostic, MatchError, MatchKind};
+use crate::diagnostic::{
+    LoweringDiagnosticsBuilder, MatchArmSuggestion, MatchDiagnostic, MatchError, MatchKind,
+};
 use crate::ids::{LocationId, SemanticFunctionIdEx};
 use crate::lower::context::VarRequest;
 use crate::lower::external::extern_facade_expr;
 use crate::lower::{
-    create_subscope, lower_expr, lower_single_pattern, match_extern_arm_ref_args_bind,
-    match_extern_variant_arm_input_types,
+    create_subscope, lower_expr, lower_expr_function_call, lower_single_pattern,
+    match_extern_arm_ref_args_bind, match_extern_variant_arm_input_types,
 };
 use crate::{
-    FlatBlockEnd, MatchArm, MatchEnumInfo, MatchEnumValue, MatchExternInfo, MatchInfo, VarUsage,
-    VariableId,
+    BlockId, FlatBlockEnd, Location, MatchArm, MatchEnumInfo, MatchEnumValue, MatchExternInfo,
+    MatchInfo, VarUsage, VariableId,
 };
 
+/// A tracing span around the lowering of a match, entered for as long as it is alive.
+/// Compiles to nothing unless the `tracing` feature is enabled.
+struct MatchLoweringSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+}
+impl MatchLoweringSpan {
+    /// Records a field declared (as `tracing::field::Empty`) when the span was created.
+    fn record(&self, field: &'static str, value: impl std::fmt::Display) {
+        #[cfg(feature = "tracing")]
+        self.span.record(field, tracing::field::display(value));
+        #[cfg(not(feature = "tracing"))]
+        let _ = (field, value);
+    }
+}
+
+/// Creates and enters a [MatchLoweringSpan] with the given name and fields.
+macro_rules! match_lowering_span {
+    ($name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        MatchLoweringSpan {
+            #[cfg(feature = "tracing")]
+            span: tracing::trace_span!($name $(, $field = $value)*).entered(),
+        }
+    };
+}
+
+/// The strategy a match expression was lowered with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MatchStrategy {
+    /// A `match_enum` on a concrete enum.
+    Enum,
+    /// A match on the result of an extern function returning an enum.
+    Extern,
+    /// A numeric match lowered into a chain of comparisons.
+    Felt252IfChain,
+    /// A numeric match lowered into a jump table on its value.
+    Felt252JumpTable,
+    /// A match on a tuple, lowered into a decision tree over its elements.
+    TupleTree,
+    /// A match on a struct, lowered into a decision tree over its members.
+    StructTree,
+    /// A match on a fixed size array or a span, lowered into a decision tree over its length.
+    ArrayTree,
+}
+
+/// The number of match expressions lowered with each [MatchStrategy].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MatchStrategyHistogram {
+    counts: UnorderedHashMap<MatchStrategy, usize>,
+}
+impl MatchStrategyHistogram {
+    /// Records a match lowered with the given strategy.
+    pub fn record(&mut self, strategy: MatchStrategy) {
+        *self.counts.entry(strategy).or_default() += 1;
+    }
+
+    /// Adds the counts of `other` to this histogram.
+    pub fn merge(&mut self, other: &MatchStrategyHistogram) {
+        self.counts.merge(&other.counts, |mut entry, count| *entry.get_mut() += count);
+    }
+
+    /// Returns the number of matches lowered with the given strategy.
+    pub fn count(&self, strategy: MatchStrategy) -> usize {
+        self.counts.get(&strategy).copied().unwrap_or_default()
+    }
+
+    /// Returns the total number of recorded matches.
+    pub fn total(&self) -> usize {
+        self.counts.iter_sorted().map(|(_, count)| count).sum()
+    }
+}
+
+/// The variants handled by explicit arms of each match on an enum, by match, stored as a baseline
+/// to detect matches that stopped handling a variant explicitly, e.g. when a refactor dropped an
+/// arm and the variant fell into a catch-all arm.
+///
+/// A match is identified by the full path of its function and its index among the matches of the
+/// function, e.g. `test::foo#0`. The textual form, of [MatchCoverageBaseline::parse] and
+/// [std::fmt::Display], has a line per match, e.g. `test::foo#0: A, B`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MatchCoverageBaseline {
+    coverage: OrderedHashMap<String, Vec<SmolStr>>,
+}
+impl MatchCoverageBaseline {
+    /// Parses a baseline from its textual form, ignoring empty lines.
+    pub fn parse(content: &str) -> Self {
+        let coverage = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let (key, variants) = line.rsplit_once(':').unwrap_or((line, ""));
+                let variants = variants
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|variant| !variant.is_empty())
+                    .map(SmolStr::from)
+                    .collect();
+                (key.trim().to_string(), variants)
+            })
+            .collect();
+        Self { coverage }
+    }
+
+    /// Returns the variants handled by explicit arms of the given match, if it is in the baseline.
+    pub fn get(&self, key: &str) -> Option<&[SmolStr]> {
+        self.coverage.get(key).map(Vec::as_slice)
+    }
+}
+impl std::fmt::Display for MatchCoverageBaseline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (key, variants) in self.coverage.iter() {
+            writeln!(f, "{key}: {}", variants.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
 /// Information about the enum of a match statement. See [extract_concrete_enum].
 struct ExtractedEnumDetails {
     concrete_enum_id: semantic::ConcreteEnumId,
-    concrete_variants: Vec<semantic::ConcreteVariant>,
+    concrete_variants: Arc<[semantic::ConcreteVariant]>,
     n_snapshots: usize,
 }
 
@@ -52,12 +192,13 @@ struct ExtractedEnumDetails {
 /// Used in the case of if-let with missing else clause.
 pub struct MatchArmWrapper {
     pub patterns: Vec<PatternId>,
+    pub guard: Option<semantic::ExprId>,
     pub expr: Option<semantic::ExprId>,
 }
 
 impl From<&semantic::MatchArm> for MatchArmWrapper {
     fn from(arm: &semantic::MatchArm) -> Self {
-        Self { patterns: arm.patterns.clone(), expr: Some(arm.expression) }
+        Self { patterns: arm.patterns.clone(), guard: arm.guard, expr: Some(arm.expression) }
     }
 }
 
@@ -73,47 +214,115 @@ fn extract_concrete_enum(
 
     // Semantic model should have made sure the type is an enum.
     let TypeLongId::Concrete(ConcreteTypeId::Enum(concrete_enum_id)) = long_ty else {
-        return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
-            stable_ptr,
-            MatchError(MatchError {
-                kind: match_type,
-                error: MatchDiagnostic::UnsupportedMatchedType(long_ty.format(ctx.db.upcast())),
-            }),
-        )));
+        let matched_type = long_ty.format(ctx.db.upcast());
+        let error = match long_ty {
+            // Types which may be substituted by an enum, but are not one in the generic function.
+            TypeLongId::GenericParameter(_) | TypeLongId::ImplType(_) => {
+                MatchDiagnostic::UnsupportedMatchedNonNominalType(matched_type)
+            }
+            _ => MatchDiagnostic::UnsupportedMatchedType(matched_type),
+        };
+        return Err(LoweringFlowError::Failed(
+            ctx.diagnostics.report(stable_ptr, MatchError(MatchError { kind: match_type, error })),
+        ));
     };
     let concrete_variants =
-        ctx.db.concrete_enum_variants(concrete_enum_id).map_err(LoweringFlowError::Failed)?;
+        ctx.concrete_enum_variants(concrete_enum_id).map_err(LoweringFlowError::Failed)?;
 
     Ok(ExtractedEnumDetails { concrete_enum_id, concrete_variants, n_snapshots })
 }
 
-/// Extracts concrete enums and variants from a match expression on a tuple of enums.
+/// The shape of a member of a matched tuple of enums. The enums of the tuple and of its nested
+/// tuples are matched as the positions of a single flattened tuple, in order.
+#[derive(Debug, Clone)]
+enum TupleShape {
+    Enum,
+    Tuple(Vec<TupleShape>),
+}
+
+/// Returns the number of positions of the flattened tuple with the given members.
+fn n_tuple_positions(members: &[TupleShape]) -> usize {
+    members
+        .iter()
+        .map(|member| match member {
+            TupleShape::Enum => 1,
+            TupleShape::Tuple(members) => n_tuple_positions(members),
+        })
+        .sum()
+}
+
+/// Extracts concrete enums and variants from a match expression on a tuple of enums, or of nested
+/// tuples of enums, along with the shape of its members.
 fn extract_concrete_enum_tuple(
     ctx: &mut LoweringContext<'_, '_>,
     stable_ptr: SyntaxStablePtrId,
     types: &[semantic::TypeId],
     match_type: MatchKind,
-) -> Result<Vec<ExtractedEnumDetails>, LoweringFlowError> {
-    types
-        .iter()
-        .map(|ty| {
-            let (n_snapshots, long_ty) = peel_snapshots(ctx.db.upcast(), *ty);
-            let TypeLongId::Concrete(ConcreteTypeId::Enum(concrete_enum_id)) = long_ty else {
-                return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+) -> Result<(Vec<ExtractedEnumDetails>, Vec<TupleShape>), LoweringFlowError> {
+    let mut extracted_enums_details = vec![];
+    let shape = extract_concrete_enum_tuple_members(
+        ctx,
+        stable_ptr,
+        types,
+        0,
+        match_type,
+        &mut extracted_enums_details,
+    )?;
+    Ok((extracted_enums_details, shape))
+}
+
+/// A helper function for [extract_concrete_enum_tuple]. Extracts the enums of the members of a
+/// tuple nested in `n_snapshots` snapshots within the matched tuple.
+fn extract_concrete_enum_tuple_members(
+    ctx: &mut LoweringContext<'_, '_>,
+    stable_ptr: SyntaxStablePtrId,
+    types: &[semantic::TypeId],
+    n_snapshots: usize,
+    match_type: MatchKind,
+    extracted_enums_details: &mut Vec<ExtractedEnumDetails>,
+) -> Result<Vec<TupleShape>, LoweringFlowError> {
+    let unsupported = |ctx: &mut LoweringContext<'_, '_>| {
+        LoweringFlowError::Failed(ctx.diagnostics.report(
+            stable_ptr,
+            MatchError(MatchError {
+                kind: match_type,
+                error: MatchDiagnostic::UnsupportedMatchedValueTuple,
+            }),
+        ))
+    };
+    if types.is_empty() {
+        return Err(unsupported(ctx));
+    }
+    let mut shape = vec![];
+    for ty in types {
+        let (member_snapshots, long_ty) = peel_snapshots(ctx.db.upcast(), *ty);
+        let n_snapshots = n_snapshots + member_snapshots;
+        match long_ty {
+            TypeLongId::Concrete(ConcreteTypeId::Enum(concrete_enum_id)) => {
+                let concrete_variants = ctx
+                    .concrete_enum_variants(concrete_enum_id)
+                    .map_err(LoweringFlowError::Failed)?;
+                extracted_enums_details.push(ExtractedEnumDetails {
+                    concrete_enum_id,
+                    concrete_variants,
+                    n_snapshots,
+                });
+                shape.push(TupleShape::Enum);
+            }
+            TypeLongId::Tuple(types) => {
+                shape.push(TupleShape::Tuple(extract_concrete_enum_tuple_members(
+                    ctx,
                     stable_ptr,
-                    MatchError(MatchError {
-                        kind: match_type,
-                        error: MatchDiagnostic::UnsupportedMatchedValueTuple,
-                    }),
-                )));
-            };
-            let concrete_variants = ctx
-                .db
-                .concrete_enum_variants(concrete_enum_id)
-                .map_err(LoweringFlowError::Failed)?;
-            Ok(ExtractedEnumDetails { concrete_enum_id, concrete_variants, n_snapshots })
-        })
-        .collect()
+                    &types,
+                    n_snapshots,
+                    match_type,
+                    extracted_enums_details,
+                )?));
+            }
+            _ => return Err(unsupported(ctx)),
+        }
+    }
+    Ok(shape)
 }
 
 /// The arm and pattern indices of a pattern in a match arm with an or list.
@@ -123,51 +332,113 @@ struct PatternPath {
     pattern_index: Option<usize>,
 }
 
+/// Returns the variables bound to the whole value matched by `pattern_id` through `name @ pattern`
+/// bindings, outermost first, along with the pattern they wrap.
+fn peel_pattern_bindings(
+    ctx: &LoweringContext<'_, '_>,
+    mut pattern_id: PatternId,
+) -> (Vec<semantic::PatternVariable>, PatternId) {
+    let mut bindings = vec![];
+    while let Pattern::Binding(binding) = &ctx.function_body.arenas.patterns[pattern_id] {
+        bindings.push(binding.binding.clone());
+        pattern_id = binding.inner_pattern;
+    }
+    (bindings, pattern_id)
+}
+
+/// Binds each of `bindings` to the value in `value`.
+fn lower_pattern_bindings(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    bindings: Vec<semantic::PatternVariable>,
+    value: VarUsage,
+) -> LoweringResult<()> {
+    for binding in bindings {
+        lower_single_pattern(
+            ctx,
+            builder,
+            Pattern::Variable(binding),
+            LoweredExpr::AtVariable(value),
+        )?;
+    }
+    Ok(())
+}
+
+/// Binds each of `bindings` to the matched value of an arm taken for `concrete_variant`, whose
+/// payload is in `var_id`. The value is reconstructed from the variant and its payload, unless it
+/// is a snapshot, in which case it is the matched snapshot in `match_input`.
+fn bind_matched_variant(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    bindings: Vec<semantic::PatternVariable>,
+    concrete_variant: &semantic::ConcreteVariant,
+    var_id: VariableId,
+    match_input: VarUsage,
+) -> LoweringResult<()> {
+    let Some(binding) = bindings.first() else {
+        return Ok(());
+    };
+    let location = ctx.get_location(binding.stable_ptr.untyped());
+    let value = if ctx.variables[var_id].ty == concrete_variant.ty {
+        generators::EnumConstruct {
+            input: VarUsage { var_id, location },
+            variant: concrete_variant.clone(),
+            location,
+        }
+        .add(ctx, &mut builder.statements)
+    } else {
+        VarUsage { var_id: match_input.var_id, location }
+    };
+    lower_pattern_bindings(ctx, builder, bindings, value)
+}
+
 /// Returns an option containing the PatternPath of the underscore pattern, if it exists.
+/// If `allow_variable_catch_all` is set, a variable pattern is also considered a catch-all.
+/// Arms with a guard may not be taken, so they are never the catch-all arm.
 fn get_underscore_pattern_path(
     ctx: &mut LoweringContext<'_, '_>,
     arms: &[MatchArmWrapper],
     match_type: MatchKind,
+    allow_variable_catch_all: bool,
 ) -> Option<PatternPath> {
     let otherwise_variant = arms
         .iter()
         .enumerate()
         .map(|(arm_index, arm)| {
+            if arm.guard.is_some() {
+                return None;
+            }
             if arm.patterns.is_empty() {
                 return Some(PatternPath { arm_index, pattern_index: None });
             }
             arm.patterns
                 .iter()
-                .position(|pattern| {
-                    matches!(
-                        ctx.function_body.arenas.patterns[*pattern],
-                        semantic::Pattern::Otherwise(_)
-                    )
+                .position(|pattern| match ctx.function_body.arenas.patterns[*pattern] {
+                    semantic::Pattern::Otherwise(_) => true,
+                    semantic::Pattern::Variable(_) => allow_variable_catch_all,
+                    // A binding of `_` is a variable pattern.
+                    semantic::Pattern::Binding(_) => {
+                        allow_variable_catch_all
+                            && matches!(
+                                ctx.function_body.arenas.patterns
+                                    [peel_pattern_bindings(ctx, *pattern).1],
+                                semantic::Pattern::Otherwise(_)
+                            )
+                    }
+                    _ => false,
                 })
                 .map(|pattern_index| PatternPath { arm_index, pattern_index: Some(pattern_index) })
         })
         .find(|option| option.is_some())??;
 
+    let mut unreachable = vec![];
     for arm in arms.iter().skip(otherwise_variant.arm_index + 1) {
-        if arm.patterns.is_empty() && arm.expr.is_some() {
-            let expr = ctx.function_body.arenas.exprs[arm.expr.unwrap()].clone();
-            ctx.diagnostics.report(
-                &expr,
-                MatchError(MatchError {
-                    kind: match_type,
-                    error: MatchDiagnostic::UnreachableMatchArm,
-                }),
-            );
+        if let (true, Some(expr)) = (arm.patterns.is_empty(), arm.expr) {
+            let expr = &ctx.function_body.arenas.exprs[expr];
+            unreachable.push(expr.stable_ptr().untyped());
         }
         for pattern in arm.patterns.iter() {
-            let pattern = ctx.function_body.arenas.patterns[*pattern].clone();
-            ctx.diagnostics.report(
-                &pattern,
-                MatchError(MatchError {
-                    kind: match_type,
-                    error: MatchDiagnostic::UnreachableMatchArm,
-                }),
-            );
+            unreachable.push(ctx.function_body.arenas.patterns[*pattern].stable_ptr().untyped());
         }
     }
     for pattern in arms[otherwise_variant.arm_index]
@@ -175,67 +446,127 @@ fn get_underscore_pattern_path(
         .iter()
         .skip(otherwise_variant.pattern_index.unwrap_or(0) + 1)
     {
-        let pattern = ctx.function_body.arenas.patterns[*pattern].clone();
-        ctx.diagnostics.report(
-            &pattern,
-            MatchError(MatchError {
-                kind: match_type,
-                error: MatchDiagnostic::UnreachableMatchArm,
-            }),
-        );
+        unreachable.push(ctx.function_body.arenas.patterns[*pattern].stable_ptr().untyped());
     }
+    report_unreachable_arms(ctx, unreachable, match_type);
 
     Some(otherwise_variant)
 }
 
+/// Reports the catch-all pattern at `otherwise_variant` as unreachable in a match, if the arms
+/// before it already cover all the `n_variants` variants of the matched enum, as given by
+/// `variant_map`. A match on an enum without variants, or whose only arm is the catch-all, is not
+/// reported, as the catch-all covers no variant in it either way.
+fn report_unreachable_otherwise_arm(
+    ctx: &mut LoweringContext<'_, '_>,
+    arms: &[MatchArmWrapper],
+    otherwise_variant: Option<&PatternPath>,
+    variant_map: &UnorderedHashMap<semantic::ConcreteVariant, PatternPath>,
+    n_variants: usize,
+    match_type: MatchKind,
+) {
+    let Some(PatternPath { arm_index, pattern_index: Some(pattern_index) }) = otherwise_variant
+    else {
+        return;
+    };
+    if match_type != MatchKind::Match || n_variants == 0 || variant_map.len() < n_variants {
+        return;
+    }
+    let pattern = arms[*arm_index].patterns[*pattern_index];
+    let stable_ptr = ctx.function_body.arenas.patterns[pattern].stable_ptr().untyped();
+    report_unreachable_match_arm(ctx, stable_ptr, match_type);
+}
+
+/// Reports a pattern of `variant`, which does not belong to the matched enum, listing the variants
+/// that are valid for it.
+fn report_unknown_variant(
+    ctx: &mut LoweringContext<'_, '_>,
+    stable_ptr: impl Into<SyntaxStablePtrId>,
+    variant: &semantic::ConcreteVariant,
+    concrete_enum_id: semantic::ConcreteEnumId,
+    match_type: MatchKind,
+) -> LoweringFlowError {
+    let semantic_db = ctx.db.upcast();
+    let enum_id = concrete_enum_id.enum_id(semantic_db);
+    let valid_variants = match ctx.db.enum_variants(enum_id) {
+        Ok(variants) => variants.keys().cloned().collect(),
+        Err(diag_added) => return LoweringFlowError::Failed(diag_added),
+    };
+    let variant = format!(
+        "{}::{}",
+        variant.concrete_enum_id.enum_id(semantic_db).full_path(semantic_db),
+        variant.id.name(semantic_db)
+    );
+    LoweringFlowError::Failed(ctx.diagnostics.report(
+        stable_ptr,
+        MatchError(MatchError {
+            kind: match_type,
+            error: MatchDiagnostic::UnknownVariantInMatch {
+                variant,
+                enum_name: enum_id.full_path(semantic_db),
+                valid_variants,
+            },
+        }),
+    ))
+}
+
 /// Returns a map from variants to their corresponding pattern path in a match statement.
+/// `n_variants` is the number of variants of the enum, used to pre-size the map.
 fn get_variant_to_arm_map<'a>(
     ctx: &mut LoweringContext<'_, '_>,
     arms: impl Iterator<Item = &'a MatchArmWrapper>,
     concrete_enum_id: semantic::ConcreteEnumId,
+    n_variants: usize,
     match_type: MatchKind,
 ) -> LoweringResult<UnorderedHashMap<semantic::ConcreteVariant, PatternPath>> {
-    let mut map = UnorderedHashMap::default();
+    let mut map = UnorderedHashMap::with_capacity(n_variants);
     for (arm_index, arm) in arms.enumerate() {
-        for (pattern_index, pattern) in arm.patterns.iter().enumerate() {
-            let pattern = ctx.function_body.arenas.patterns[*pattern].clone();
+        for (pattern_index, pattern_id) in arm.patterns.iter().enumerate() {
+            let pattern = ctx.function_body.arenas.patterns[*pattern_id].clone();
 
             if let semantic::Pattern::Otherwise(_) = pattern {
                 break;
             }
+            // A guarded variable pattern is a catch-all. See [get_conditional_arm_paths].
+            if arm.guard.is_some() && matches!(pattern, semantic::Pattern::Variable(_)) {
+                break;
+            }
 
-            let enum_pattern = try_extract_matches!(&pattern, semantic::Pattern::EnumVariant)
-                .ok_or_else(|| {
-                    LoweringFlowError::Failed(ctx.diagnostics.report(
-                        &pattern,
-                        MatchError(MatchError {
-                            kind: match_type,
-                            error: MatchDiagnostic::UnsupportedMatchArmNotAVariant,
-                        }),
-                    ))
-                })?
-                .clone();
-
-            if enum_pattern.variant.concrete_enum_id != concrete_enum_id {
-                return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+            let (_, inner_pattern) = peel_pattern_bindings(ctx, *pattern_id);
+            let enum_pattern = try_extract_matches!(
+                &ctx.function_body.arenas.patterns[inner_pattern],
+                semantic::Pattern::EnumVariant
+            )
+            .cloned()
+            .ok_or_else(|| {
+                LoweringFlowError::Failed(ctx.diagnostics.report(
                     &pattern,
                     MatchError(MatchError {
                         kind: match_type,
                         error: MatchDiagnostic::UnsupportedMatchArmNotAVariant,
                     }),
-                )));
+                ))
+            })?;
+
+            if enum_pattern.variant.concrete_enum_id != concrete_enum_id {
+                return Err(report_unknown_variant(
+                    ctx,
+                    &pattern,
+                    &enum_pattern.variant,
+                    concrete_enum_id,
+                    match_type,
+                ));
             }
 
             match map.entry(enum_pattern.variant.clone()) {
                 Entry::Occupied(_) => {
-                    ctx.diagnostics.report(
-                        &pattern,
-                        MatchError(MatchError {
-                            kind: match_type,
-                            error: MatchDiagnostic::UnreachableMatchArm,
-                        }),
-                    );
+                    report_unreachable_match_arm(ctx, &pattern, match_type);
                 }
+                // An arm with a guard, or with an or-pattern of the payload, may not be taken, so
+                // the variant falls through to the next arm matching it, which is the arm of the
+                // variant.
+                Entry::Vacant(_)
+                    if arm.guard.is_some() || has_inner_or_pattern(ctx, &enum_pattern) => {}
                 Entry::Vacant(entry) => {
                     entry.insert(PatternPath { arm_index, pattern_index: Some(pattern_index) });
                 }
@@ -247,153 +578,327 @@ fn get_variant_to_arm_map<'a>(
 
 /// Represents a path in a match tree.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
-struct MatchingPath {
-    /// The variants per member of the tuple matched until this point.
-    variants: Vec<semantic::ConcreteVariant>,
+pub struct MatchingPath {
+    /// The variants per position of the flattened tuple matched until this point. The positions of
+    /// a nested tuple follow each other, so a path is the preorder of the tree of variants matched
+    /// by the tuple and its nested tuples.
+    pub variants: Vec<semantic::ConcreteVariant>,
+}
+
+/// A variable bound to the matched tuple or to one of its nested tuples, with the first position
+/// and the members of the bound tuple.
+#[derive(Debug, Clone)]
+struct TupleBinding {
+    variable: semantic::PatternVariable,
+    position: usize,
+    members: Vec<TupleShape>,
 }
 
-/// A helper function for [get_variants_to_arm_map_tuple] Inserts the pattern path to the map for
-/// each variants list it can match.
-fn insert_tuple_path_patterns(
+/// A pattern of a match on a tuple of enums, flattened into the patterns of the positions of the
+/// tuple and its nested tuples.
+#[derive(Debug, Clone, Default)]
+struct FlatTuplePattern {
+    /// The pattern of each position, or `None` for positions of a nested tuple matched by a
+    /// catch-all.
+    positions: Vec<Option<PatternId>>,
+    /// The variables bound to the whole tuple or to its nested tuples.
+    tuple_bindings: Vec<TupleBinding>,
+}
+
+/// A helper function for [get_tuple_variants_rows]. Flattens the patterns of the members of a
+/// (possibly nested) tuple into `flat`.
+fn flatten_tuple_patterns(
     ctx: &mut LoweringContext<'_, '_>,
     patterns: &[PatternId],
-    pattern_path: &PatternPath,
-    extracted_enums_details: &[ExtractedEnumDetails],
-    mut path: MatchingPath,
-    map: &mut UnorderedHashMap<MatchingPath, PatternPath>,
+    members: &[TupleShape],
     match_type: MatchKind,
+    flat: &mut FlatTuplePattern,
 ) -> LoweringResult<()> {
-    let index = path.variants.len();
-
-    // if the path is the same length as the tuple's patterns, we have reached the end of the path
-    if index == patterns.len() {
-        match map.entry(path) {
-            Entry::Occupied(_) => {}
-            Entry::Vacant(entry) => {
-                entry.insert(pattern_path.clone());
-            }
+    for (pattern_id, member) in zip_eq(patterns, members) {
+        let TupleShape::Tuple(members) = member else {
+            flat.positions.push(Some(*pattern_id));
+            continue;
         };
-        return Ok(());
-    }
-
-    let pattern = ctx.function_body.arenas.patterns[patterns[index]].clone();
-
-    match pattern {
-        Pattern::EnumVariant(enum_pattern) => {
-            if enum_pattern.variant.concrete_enum_id
-                != extracted_enums_details[index].concrete_enum_id
-            {
+        let position = flat.positions.len();
+        let (bindings, inner_pattern) = peel_pattern_bindings(ctx, *pattern_id);
+        let mut variables = bindings;
+        match ctx.function_body.arenas.patterns[inner_pattern].clone() {
+            Pattern::Tuple(tuple) => {
+                flatten_tuple_patterns(ctx, &tuple.field_patterns, members, match_type, flat)?
+            }
+            Pattern::Variable(variable) => {
+                variables.push(variable);
+                flat.positions.resize(position + n_tuple_positions(members), None);
+            }
+            Pattern::Otherwise(_) => {
+                flat.positions.resize(position + n_tuple_positions(members), None)
+            }
+            pattern => {
                 return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
-                    enum_pattern.stable_ptr.untyped(),
+                    &pattern,
                     MatchError(MatchError {
                         kind: match_type,
-                        error: MatchDiagnostic::UnsupportedMatchArmNotAVariant,
+                        error: MatchDiagnostic::UnsupportedMatchArmNotATuple,
                     }),
                 )));
             }
-            path.variants.push(enum_pattern.variant);
-            insert_tuple_path_patterns(
-                ctx,
-                patterns,
-                pattern_path,
-                extracted_enums_details,
-                path,
-                map,
-                match_type,
-            )
         }
-        Pattern::Otherwise(_) => {
-            extracted_enums_details[index].concrete_variants.iter().try_for_each(|variant| {
-                // TODO(TomerStarkware): Remove the match on the variant options in this case if
-                // there's no other conflicting arm.
-                let mut path = path.clone();
-                path.variants.push(variant.clone());
-                insert_tuple_path_patterns(
-                    ctx,
-                    patterns,
-                    pattern_path,
-                    extracted_enums_details,
-                    path,
-                    map,
-                    match_type,
-                )
-            })
+        flat.tuple_bindings.extend(variables.into_iter().map(|variable| TupleBinding {
+            variable,
+            position,
+            members: members.clone(),
+        }));
+    }
+    Ok(())
+}
+
+/// A tuple pattern of a match on a tuple of enums, with the variant matched by each position of the
+/// flattened tuple, or `None` for positions matching any variant.
+struct TupleVariantsRow {
+    pattern_path: PatternPath,
+    variants: Vec<Option<semantic::ConcreteVariant>>,
+    pattern: FlatTuplePattern,
+}
+
+impl TupleVariantsRow {
+    /// Returns whether the row matches the variants of `path`, which may be a prefix of the
+    /// positions.
+    fn matches(&self, path: &MatchingPath) -> bool {
+        self.variants
+            .iter()
+            .zip(&path.variants)
+            .all(|(row_variant, variant)| row_variant.as_ref().is_none_or(|v| v == variant))
+    }
+}
+
+/// Returns the row of the catch-all pattern of a match on a tuple expression, matching any variant
+/// in every position.
+fn catch_all_tuple_row(
+    ctx: &LoweringContext<'_, '_>,
+    arms: &[MatchArmWrapper],
+    pattern_path: PatternPath,
+    shape: &[TupleShape],
+) -> TupleVariantsRow {
+    let n_positions = n_tuple_positions(shape);
+    let mut tuple_bindings = vec![];
+    if let Some(pattern_index) = pattern_path.pattern_index {
+        let (mut variables, pattern) =
+            peel_pattern_bindings(ctx, arms[pattern_path.arm_index].patterns[pattern_index]);
+        if let Pattern::Variable(variable) = &ctx.function_body.arenas.patterns[pattern] {
+            variables.push(variable.clone());
         }
-        _ => Err(LoweringFlowError::Failed(ctx.diagnostics.report(
-            &pattern,
-            MatchError(MatchError {
-                kind: match_type,
-                error: MatchDiagnostic::UnsupportedMatchArmNotAVariant,
-            }),
-        ))),
+        tuple_bindings = variables
+            .into_iter()
+            .map(|variable| TupleBinding { variable, position: 0, members: shape.to_vec() })
+            .collect();
+    }
+    TupleVariantsRow {
+        pattern_path,
+        variants: vec![None; n_positions],
+        pattern: FlatTuplePattern { positions: vec![None; n_positions], tuple_bindings },
     }
 }
 
-/// Returns a map from a matching paths to their corresponding pattern path in a match statement.
-fn get_variants_to_arm_map_tuple<'a>(
+/// Returns the variants matched by each of the tuple patterns of a match statement, by order of
+/// precedence. Reports the patterns not matching any combination of variants which is not matched
+/// by a previous pattern as unreachable.
+fn get_tuple_variants_rows<'a>(
     ctx: &mut LoweringContext<'_, '_>,
     arms: impl Iterator<Item = &'a MatchArmWrapper>,
     extracted_enums_details: &[ExtractedEnumDetails],
+    shape: &[TupleShape],
     match_type: MatchKind,
-) -> LoweringResult<UnorderedHashMap<MatchingPath, PatternPath>> {
-    let mut map = UnorderedHashMap::default();
+) -> LoweringResult<Vec<TupleVariantsRow>> {
+    let mut rows: Vec<TupleVariantsRow> = vec![];
     for (arm_index, arm) in arms.enumerate() {
-        for (pattern_index, pattern) in arm.patterns.iter().enumerate() {
-            let pattern = ctx.function_body.arenas.patterns[*pattern].clone();
-            if let semantic::Pattern::Otherwise(_) = pattern {
+        for (pattern_index, pattern_id) in arm.patterns.iter().enumerate() {
+            let pattern = ctx.function_body.arenas.patterns[*pattern_id].clone();
+            if let semantic::Pattern::Otherwise(_) | semantic::Pattern::Variable(_) = pattern {
                 break;
             }
-            let patterns =
-                try_extract_matches!(&pattern, semantic::Pattern::Tuple).ok_or_else(|| {
-                    LoweringFlowError::Failed(ctx.diagnostics.report(
-                        &pattern,
-                        MatchError(MatchError {
-                            kind: match_type,
-                            error: MatchDiagnostic::UnsupportedMatchArmNotAVariant,
-                        }),
-                    ))
-                })?;
-
-            let map_size = map.len();
-            insert_tuple_path_patterns(
-                ctx,
-                &patterns.field_patterns,
-                &PatternPath { arm_index, pattern_index: Some(pattern_index) },
-                extracted_enums_details,
-                MatchingPath::default(),
-                &mut map,
-                match_type,
-            )?;
-            if map.len() == map_size {
-                ctx.diagnostics.report(
+            let (bindings, inner_pattern) = peel_pattern_bindings(ctx, *pattern_id);
+            let patterns = try_extract_matches!(
+                &ctx.function_body.arenas.patterns[inner_pattern],
+                semantic::Pattern::Tuple
+            )
+            .cloned()
+            .ok_or_else(|| {
+                LoweringFlowError::Failed(ctx.diagnostics.report(
                     &pattern,
                     MatchError(MatchError {
                         kind: match_type,
-                        error: MatchDiagnostic::UnreachableMatchArm,
+                        error: MatchDiagnostic::UnsupportedMatchArmNotAVariant,
                     }),
-                );
+                ))
+            })?;
+
+            let mut flat = FlatTuplePattern {
+                positions: vec![],
+                tuple_bindings: bindings
+                    .into_iter()
+                    .map(|variable| TupleBinding { variable, position: 0, members: shape.to_vec() })
+                    .collect(),
+            };
+            flatten_tuple_patterns(ctx, &patterns.field_patterns, shape, match_type, &mut flat)?;
+            let variants = zip_eq(&flat.positions, extracted_enums_details)
+                .map(|(pattern, details)| {
+                    let Some(pattern) = pattern else {
+                        return Ok(None);
+                    };
+                    let (_, pattern) = peel_pattern_bindings(ctx, *pattern);
+                    match ctx.function_body.arenas.patterns[pattern].clone() {
+                        Pattern::EnumVariant(enum_pattern) => {
+                            if enum_pattern.variant.concrete_enum_id != details.concrete_enum_id {
+                                return Err(report_unknown_variant(
+                                    ctx,
+                                    enum_pattern.stable_ptr.untyped(),
+                                    &enum_pattern.variant,
+                                    details.concrete_enum_id,
+                                    match_type,
+                                ));
+                            }
+                            Ok(Some(enum_pattern.variant))
+                        }
+                        Pattern::Otherwise(_) => Ok(None),
+                        pattern => Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+                            &pattern,
+                            MatchError(MatchError {
+                                kind: match_type,
+                                error: MatchDiagnostic::UnsupportedMatchArmNotAVariant,
+                            }),
+                        ))),
+                    }
+                })
+                .collect::<LoweringResult<Vec<_>>>()?;
+            let row = TupleVariantsRow {
+                pattern_path: PatternPath { arm_index, pattern_index: Some(pattern_index) },
+                variants,
+                pattern: flat,
+            };
+            if !is_tuple_row_reachable(&rows.iter().collect_vec(), &row, 0, extracted_enums_details)
+            {
+                report_unreachable_match_arm(ctx, &pattern, match_type);
             }
+            rows.push(row);
         }
     }
-    Ok(map)
-}
-
-/// Information needed to lower a match on tuple expression.
-struct LoweringMatchTupleContext {
-    /// The location of the match expression.
-    match_location: LocationId,
-    /// The index of the underscore pattern, if it exists.
-    otherwise_variant: Option<PatternPath>,
-    /// A map from variants vector to their corresponding pattern path.
-    variants_map: UnorderedHashMap<MatchingPath, PatternPath>,
-    /// The tuple's destructured inputs.
-    match_inputs: Vec<VarUsage>,
-    /// The number of snapshots of the tuple.
-    n_snapshots_outer: usize,
-    /// The current variants path.
-    current_path: MatchingPath,
-    /// The current variants' variable ids.
-    current_var_ids: Vec<VariableId>,
+    Ok(rows)
+}
+
+/// Returns whether some combination of the variants matched by `row` from `position` onwards is
+/// not matched by any of `previous_rows`. A position matching any variant is only split into its
+/// variants if some of the previous rows tell them apart, so the combinations are not enumerated
+/// when the previous rows do not depend on them.
+fn is_tuple_row_reachable(
+    previous_rows: &[&TupleVariantsRow],
+    row: &TupleVariantsRow,
+    position: usize,
+    extracted_enums_details: &[ExtractedEnumDetails],
+) -> bool {
+    if previous_rows.is_empty() {
+        return true;
+    }
+    if position == row.variants.len() {
+        return false;
+    }
+    let rows_matching = |variant: &semantic::ConcreteVariant| {
+        previous_rows
+            .iter()
+            .filter(|previous| previous.variants[position].as_ref().is_none_or(|v| v == variant))
+            .copied()
+            .collect_vec()
+    };
+    match &row.variants[position] {
+        Some(variant) => is_tuple_row_reachable(
+            &rows_matching(variant),
+            row,
+            position + 1,
+            extracted_enums_details,
+        ),
+        None if previous_rows.iter().all(|previous| previous.variants[position].is_none()) => {
+            is_tuple_row_reachable(previous_rows, row, position + 1, extracted_enums_details)
+        }
+        None => extracted_enums_details[position].concrete_variants.iter().any(|variant| {
+            is_tuple_row_reachable(
+                &rows_matching(variant),
+                row,
+                position + 1,
+                extracted_enums_details,
+            )
+        }),
+    }
+}
+
+/// Reports the given unreachable arms - each one separately, or all of them in a single
+/// diagnostic if configured by the `aggregate_unreachable_match_arms` flag.
+fn report_unreachable_arms(
+    ctx: &mut LoweringContext<'_, '_>,
+    unreachable: Vec<SyntaxStablePtrId>,
+    match_type: MatchKind,
+) {
+    if unreachable.len() > 1 && aggregate_unreachable_match_arms(ctx) {
+        let n_unreachable = unreachable.len();
+        let first_location = StableLocation::new(unreachable[0]);
+        let location = unreachable.iter().skip(1).fold(
+            Location::new(first_location)
+                .maybe_with_note(macro_expansion_note(ctx, first_location)),
+            |location, stable_ptr| {
+                location.with_note(DiagnosticNote::with_location(
+                    "this arm is unreachable as well".into(),
+                    StableLocation::new(*stable_ptr).diagnostic_location(ctx.db.upcast()),
+                ))
+            },
+        );
+        ctx.diagnostics.report_by_location(
+            location,
+            MatchError(MatchError {
+                kind: match_type,
+                error: MatchDiagnostic::UnreachableMatchArms(n_unreachable),
+            }),
+        );
+        return;
+    }
+    for stable_ptr in unreachable {
+        report_unreachable_match_arm(ctx, stable_ptr, match_type);
+    }
+}
+
+/// Returns whether to report all the unreachable arms of a match in a single diagnostic.
+fn aggregate_unreachable_match_arms(ctx: &LoweringContext<'_, '_>) -> bool {
+    ctx.db
+        .get_flag(FlagId::new(ctx.db.upcast(), "aggregate_unreachable_match_arms"))
+        .map(|flag| match *flag {
+            Flag::AggregateUnreachableMatchArms(aggregate) => aggregate,
+            _ => panic!("Wrong type flag `{flag:?}`."),
+        })
+        .unwrap_or(false)
+}
+
+/// Information needed to lower a match on tuple expression.
+struct LoweringMatchTupleContext {
+    /// The location of the match expression.
+    match_location: LocationId,
+    /// The row of the underscore pattern, if it exists.
+    otherwise_row: Option<TupleVariantsRow>,
+    /// The variants matched by each of the tuple patterns, by order of precedence.
+    rows: Vec<TupleVariantsRow>,
+    /// The shape of the members of the tuple.
+    shape: Vec<TupleShape>,
+    /// The destructured inputs of the positions of the flattened tuple.
+    match_inputs: Vec<VarUsage>,
+    /// The number of snapshots of the tuple.
+    n_snapshots_outer: usize,
+    /// The current variants path.
+    current_path: MatchingPath,
+    /// The current variants' variable ids.
+    current_var_ids: Vec<VariableId>,
+    /// The maximum length of the current variants path reached so far, which is the depth of the
+    /// decision tree of the match.
+    max_depth: usize,
+    /// The combinations of variants not covered by any arm, in declaration order.
+    missing_paths: Vec<MatchingPath>,
+    /// The location to report the missing combinations at, explaining the first of them.
+    missing_arm_location: Option<LocationId>,
 }
 
 /// Lowers the arm of a match on a tuple expression.
@@ -405,84 +910,275 @@ fn lower_tuple_match_arm(
     leaves_builders: &mut Vec<MatchLeafBuilder>,
     match_type: MatchKind,
 ) -> LoweringResult<()> {
-    let pattern_path = match_tuple_ctx
-        .variants_map
-        .get(&match_tuple_ctx.current_path)
-        .or(match_tuple_ctx.otherwise_variant.as_ref())
-        .ok_or_else(|| {
-            LoweringFlowError::Failed(ctx.diagnostics.report_by_location(
-                match_tuple_ctx.match_location.lookup_intern(ctx.db),
-                MatchError(MatchError {
-                    kind: match_type,
-                    error: MatchDiagnostic::MissingMatchArm(format!(
-                        "({})",
-                        match_tuple_ctx.current_path.variants
-                            .iter()
-                            .map(|variant| variant.id.name(ctx.db.upcast()))
-                            .join(", ")
-                    )),
-                }),
-            ))
-        })?;
-    let pattern = pattern_path.pattern_index.map(|pattern_index| {
-        ctx.function_body.arenas.patterns[arms[pattern_path.arm_index].patterns[pattern_index]]
-            .clone()
-    });
-
-    let lowering_inner_pattern_result = match pattern {
-        Some(semantic::Pattern::Tuple(patterns)) => patterns
-            .field_patterns
-            .iter()
-            .enumerate()
-            .map(|(index, pattern)| {
-                let pattern = &ctx.function_body.arenas.patterns[*pattern];
-                match pattern {
-                    Pattern::EnumVariant(PatternEnumVariant {
-                        inner_pattern: Some(inner_pattern),
-                        ..
-                    }) => {
-                        let inner_pattern =
-                            ctx.function_body.arenas.patterns[*inner_pattern].clone();
-                        let pattern_location =
-                            ctx.get_location(inner_pattern.stable_ptr().untyped());
-
-                        let variant_expr = LoweredExpr::AtVariable(VarUsage {
-                            var_id: match_tuple_ctx.current_var_ids[index],
-                            location: pattern_location,
-                        });
-
-                        lower_single_pattern(ctx, &mut builder, inner_pattern, variant_expr)
-                    }
-                    Pattern::EnumVariant(PatternEnumVariant { inner_pattern: None, .. })
-                    | Pattern::Otherwise(_) => Ok(()),
-                    _ => unreachable!(
-                        "function `get_variant_to_arm_map` should have reported every other \
-                         pattern type"
-                    ),
-                }
-            })
-            .collect::<LoweringResult<Vec<_>>>()
-            .map(|_| ()),
-        Some(semantic::Pattern::Otherwise(_)) | None => Ok(()),
-        _ => {
-            return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
-                &pattern.unwrap(),
-                MatchError(MatchError {
-                    kind: match_type,
-                    error: MatchDiagnostic::UnsupportedMatchArmNotATuple,
-                }),
-            )));
+    let Some(row) = match_tuple_ctx
+        .rows
+        .iter()
+        .find(|row| row.matches(&match_tuple_ctx.current_path))
+        .or(match_tuple_ctx.otherwise_row.as_ref())
+    else {
+        let location = match_tuple_ctx.match_location;
+        if match_tuple_ctx.missing_arm_location.is_none() {
+            match_tuple_ctx.missing_arm_location =
+                Some(missing_tuple_arm_location(ctx, match_tuple_ctx));
         }
+        match_tuple_ctx.missing_paths.push(match_tuple_ctx.current_path.clone());
+        let arm_index = arms.len() + leaves_builders.len();
+        leaves_builders.push(lower_uncovered_leaf(ctx, builder, location, arm_index));
+        return Ok(());
     };
+    let lowering_result =
+        lower_tuple_match_arm_pattern(ctx, &mut builder, match_tuple_ctx, &row.pattern, match_type);
     leaves_builders.push(MatchLeafBuilder {
         builder,
-        arm_index: pattern_path.arm_index,
-        lowering_result: lowering_inner_pattern_result,
+        arm_index: row.pattern_path.arm_index,
+        lowering_result,
     });
     Ok(())
 }
 
-/// Lowers a full decision tree for a match on a tuple expression.
+/// Binds the variables of the flattened pattern of the arm taken for the current path of a match
+/// on a tuple expression, and lowers the payload patterns of its positions.
+fn lower_tuple_match_arm_pattern(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    match_tuple_ctx: &LoweringMatchTupleContext,
+    pattern: &FlatTuplePattern,
+    match_type: MatchKind,
+) -> LoweringResult<()> {
+    for tuple_binding in &pattern.tuple_bindings {
+        bind_reconstructed_tuple(ctx, builder, match_tuple_ctx, tuple_binding, match_type)?;
+    }
+    // The positions not matched yet are not bound. See [is_uniform_subtree].
+    let depth = match_tuple_ctx.current_path.variants.len();
+    for (index, pattern) in pattern.positions.iter().enumerate().take(depth) {
+        let Some(pattern) = pattern else {
+            continue;
+        };
+        let (bindings, pattern) = peel_pattern_bindings(ctx, *pattern);
+        bind_matched_variant(
+            ctx,
+            builder,
+            bindings,
+            &match_tuple_ctx.current_path.variants[index],
+            match_tuple_ctx.current_var_ids[index],
+            match_tuple_ctx.match_inputs[index],
+        )?;
+        match &ctx.function_body.arenas.patterns[pattern] {
+            Pattern::EnumVariant(PatternEnumVariant {
+                inner_pattern: Some(inner_pattern), ..
+            }) => {
+                let inner_pattern = ctx.function_body.arenas.patterns[*inner_pattern].clone();
+                let pattern_location = ctx.get_location(inner_pattern.stable_ptr().untyped());
+
+                let variant_expr = LoweredExpr::AtVariable(VarUsage {
+                    var_id: match_tuple_ctx.current_var_ids[index],
+                    location: pattern_location,
+                });
+
+                lower_single_pattern(ctx, builder, inner_pattern, variant_expr)?;
+            }
+            Pattern::EnumVariant(PatternEnumVariant { inner_pattern: None, .. })
+            | Pattern::Otherwise(_) => {}
+            _ => unreachable!(
+                "function `get_tuple_variants_rows` should have reported every other pattern type"
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Returns the location to report a missing combination of variants of a match on a tuple at, with
+/// a note for each position of the tuple, up to the first position where no arm matches the
+/// combination, listing the arms matching the combination up to that position.
+fn missing_tuple_arm_location(
+    ctx: &LoweringContext<'_, '_>,
+    match_tuple_ctx: &LoweringMatchTupleContext,
+) -> LocationId {
+    let variants = &match_tuple_ctx.current_path.variants;
+    let mut location = match_tuple_ctx.match_location;
+    let mut matching_rows = match_tuple_ctx.rows.iter().collect_vec();
+    for (position, variant) in variants.iter().enumerate() {
+        matching_rows.retain(|row| row.variants[position].as_ref().is_none_or(|v| v == variant));
+        let prefix = format_tuple_positions(
+            &match_tuple_ctx.shape,
+            &mut variants.iter().enumerate().map(|(index, variant)| {
+                if index <= position { variant.id.name(ctx.db.upcast()) } else { "_".into() }
+            }),
+        );
+        let note = if matching_rows.is_empty() {
+            format!("`{prefix}` is not matched by any arm.")
+        } else {
+            let arms =
+                matching_rows.iter().map(|row| row.pattern_path.arm_index + 1).dedup().join(", ");
+            format!("`{prefix}` is matched by arms: {arms}.")
+        };
+        location = location.with_note(ctx.db, DiagnosticNote::text_only(note));
+        if matching_rows.is_empty() {
+            break;
+        }
+    }
+    location
+}
+
+/// Formats the given texts of the positions of a flattened tuple as a tuple with the given members,
+/// with its nested tuples.
+fn format_tuple_positions(
+    members: &[TupleShape],
+    positions: &mut impl Iterator<Item = SmolStr>,
+) -> String {
+    let members = members
+        .iter()
+        .map(|member| match member {
+            TupleShape::Enum => positions.next().unwrap_or_default().to_string(),
+            TupleShape::Tuple(members) => format_tuple_positions(members, positions),
+        })
+        .join(", ");
+    format!("({members})")
+}
+
+/// Formats the combinations of variants of a match on a tuple which are not covered by any arm.
+/// Combinations which differ only in a single position, and together cover all the variants of
+/// that position, are formatted as one, with a `_` in that position.
+fn format_missing_tuple_paths(
+    ctx: &LoweringContext<'_, '_>,
+    missing_paths: &[MatchingPath],
+    extracted_enums_details: &[ExtractedEnumDetails],
+    shape: &[TupleShape],
+) -> Vec<String> {
+    let mut rows = missing_paths
+        .iter()
+        .map(|path| path.variants.iter().cloned().map(Some).collect_vec())
+        .collect_vec();
+    for (position, details) in extracted_enums_details.iter().enumerate().rev() {
+        // The number of rows matching each row with this position replaced by `_`.
+        let without_position = |row: &Vec<Option<semantic::ConcreteVariant>>| {
+            let mut key = row.clone();
+            key[position] = None;
+            key
+        };
+        let mut n_variants = UnorderedHashMap::<_, usize>::default();
+        for row in rows.iter().filter(|row| row[position].is_some()) {
+            *n_variants.entry(without_position(row)).or_default() += 1;
+        }
+        // Collapses each full group into its first row, keeping the declaration order.
+        let mut collapsed = UnorderedHashSet::<_>::default();
+        rows = rows
+            .into_iter()
+            .filter_map(|row| {
+                let key = without_position(&row);
+                if row[position].is_none()
+                    || n_variants.get(&key) != Some(&details.concrete_variants.len())
+                {
+                    return Some(row);
+                }
+                if collapsed.insert(key.clone()) { Some(key) } else { None }
+            })
+            .collect();
+    }
+    rows.into_iter()
+        .map(|row| {
+            format_tuple_positions(
+                shape,
+                &mut row.iter().map(|variant| match variant {
+                    Some(variant) => variant.id.name(ctx.db.upcast()),
+                    None => "_".into(),
+                }),
+            )
+        })
+        .collect()
+}
+
+/// Binds a variable pattern of a match on a tuple expression, either a catch-all or the binding of
+/// a `name @ pattern` pattern, to the matched tuple or to one of its nested tuples, reconstructed
+/// from the variants of the current path and their payloads.
+fn bind_reconstructed_tuple(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    match_tuple_ctx: &LoweringMatchTupleContext,
+    tuple_binding: &TupleBinding,
+    match_type: MatchKind,
+) -> LoweringResult<()> {
+    let pattern = tuple_binding.variable.clone();
+    let location = ctx.get_location(pattern.stable_ptr.untyped());
+    let variants = &match_tuple_ctx.current_path.variants[tuple_binding.position..];
+    // The positions can only be reconstructed into the bound tuple if it is not a snapshot.
+    if reconstructed_tuple_ty(ctx, &tuple_binding.members, &mut variants.iter()) != pattern.var.ty {
+        return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+            pattern.stable_ptr,
+            MatchError(MatchError {
+                kind: match_type,
+                error: MatchDiagnostic::UnsupportedMatchArmNotATuple,
+            }),
+        )));
+    }
+    let mut position = tuple_binding.position;
+    let tuple = reconstruct_tuple(
+        ctx,
+        builder,
+        match_tuple_ctx,
+        &tuple_binding.members,
+        &mut position,
+        location,
+    );
+    lower_single_pattern(ctx, builder, semantic::Pattern::Variable(pattern), tuple)
+}
+
+/// Returns the type of the tuple with the given members, reconstructed from the given variants of
+/// its positions.
+fn reconstructed_tuple_ty<'a>(
+    ctx: &LoweringContext<'_, '_>,
+    members: &[TupleShape],
+    variants: &mut impl Iterator<Item = &'a semantic::ConcreteVariant>,
+) -> semantic::TypeId {
+    let tys = members
+        .iter()
+        .map(|member| match member {
+            TupleShape::Enum => {
+                let concrete_enum_id = variants.next().unwrap().concrete_enum_id;
+                TypeLongId::Concrete(ConcreteTypeId::Enum(concrete_enum_id)).intern(ctx.db)
+            }
+            TupleShape::Tuple(members) => reconstructed_tuple_ty(ctx, members, variants),
+        })
+        .collect();
+    TypeLongId::Tuple(tys).intern(ctx.db)
+}
+
+/// Reconstructs the tuple with the given members from the variants of the current path from
+/// `position` onwards, and their payloads. Advances `position` past the positions of the tuple.
+fn reconstruct_tuple(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    match_tuple_ctx: &LoweringMatchTupleContext,
+    members: &[TupleShape],
+    position: &mut usize,
+    location: LocationId,
+) -> LoweredExpr {
+    let exprs = members
+        .iter()
+        .map(|member| match member {
+            TupleShape::Enum => {
+                let variant = match_tuple_ctx.current_path.variants[*position].clone();
+                let var_id = match_tuple_ctx.current_var_ids[*position];
+                *position += 1;
+                LoweredExpr::AtVariable(
+                    generators::EnumConstruct {
+                        input: VarUsage { var_id, location },
+                        variant,
+                        location,
+                    }
+                    .add(ctx, &mut builder.statements),
+                )
+            }
+            TupleShape::Tuple(members) => {
+                reconstruct_tuple(ctx, builder, match_tuple_ctx, members, position, location)
+            }
+        })
+        .collect();
+    LoweredExpr::Tuple { exprs, location }
+}
+
+/// Lowers a decision tree for a match on a tuple expression. A subtree whose paths all lead to the
+/// same arm is lowered as a single leaf. See [is_uniform_subtree].
 fn lower_full_match_tree(
     ctx: &mut LoweringContext<'_, '_>,
     builder: &mut BlockBuilder,
@@ -512,7 +1208,11 @@ fn lower_full_match_tree(
 
             match_tuple_ctx.current_path.variants.push(concrete_variant.clone());
             match_tuple_ctx.current_var_ids.push(var_id);
-            let result = if index + 1 == extracted_enums_details.len() {
+            match_tuple_ctx.max_depth =
+                match_tuple_ctx.max_depth.max(match_tuple_ctx.current_path.variants.len());
+            let result = if index + 1 == extracted_enums_details.len()
+                || is_uniform_subtree(ctx, match_tuple_ctx)
+            {
                 lower_tuple_match_arm(
                     ctx,
                     subscope,
@@ -547,7 +1247,7 @@ fn lower_full_match_tree(
         concrete_enum_id: extracted_enums_details[index].concrete_enum_id,
         input: match_tuple_ctx.match_inputs[index],
         arms: zip_eq(
-            zip_eq(&extracted_enums_details[index].concrete_variants, block_ids),
+            zip_eq(extracted_enums_details[index].concrete_variants.iter().cloned(), block_ids),
             arm_var_ids,
         )
         .map(|((variant_id, block_id), var_ids)| MatchArm {
@@ -561,23 +1261,47 @@ fn lower_full_match_tree(
     Ok(match_info)
 }
 
+/// Returns whether all the paths extending the current path of a match on a tuple expression lead
+/// to the same arm, which does not bind the values of the positions not matched yet. The subtree of
+/// the current path is then lowered as a single leaf, instead of matching the remaining positions.
+fn is_uniform_subtree(
+    ctx: &LoweringContext<'_, '_>,
+    match_tuple_ctx: &LoweringMatchTupleContext,
+) -> bool {
+    let depth = match_tuple_ctx.current_path.variants.len();
+    // The rows before the first row matching the current path match none of its extensions.
+    let Some(row) = match_tuple_ctx
+        .rows
+        .iter()
+        .find(|row| row.matches(&match_tuple_ctx.current_path))
+        .or(match_tuple_ctx.otherwise_row.as_ref())
+    else {
+        return false;
+    };
+    row.variants[depth..].iter().all(Option::is_none)
+        && row.pattern.positions[depth..]
+            .iter()
+            .flatten()
+            .all(|pattern| peel_pattern_bindings(ctx, *pattern).0.is_empty())
+        && row.pattern.tuple_bindings.iter().all(|tuple_binding| {
+            tuple_binding.position + n_tuple_positions(&tuple_binding.members) <= depth
+        })
+}
+
 /// The types and number of snapshots of a tuple expression in a match statement.
 pub struct TupleInfo {
     pub n_snapshots: usize,
     pub types: Vec<semantic::TypeId>,
 }
 
-/// Lowers an expression of type [semantic::ExprMatch] where the matched expression is a tuple of
-/// enums.
-pub(crate) fn lower_expr_match_tuple(
+/// Destructures the lowered matched tuple of a match on a tuple expression into the variables of
+/// the positions of the flattened tuple, destructuring its nested tuples as well.
+fn destructure_matched_tuple(
     ctx: &mut LoweringContext<'_, '_>,
     builder: &mut BlockBuilder,
     expr: LoweredExpr,
-    matched_expr: &semantic::Expr,
     tuple_info: &TupleInfo,
-    arms: &[MatchArmWrapper],
-    match_type: MatchKind,
-) -> LoweringResult<LoweredExpr> {
+) -> LoweringResult<Vec<VarUsage>> {
     let location = expr.location();
     let match_inputs_exprs = if let LoweredExpr::Tuple { exprs, .. } = expr {
         exprs
@@ -603,21 +1327,50 @@ pub(crate) fn lower_expr_match_tuple(
             })
             .collect()
     };
+    let mut match_inputs = vec![];
+    for (expr, ty) in zip_eq(match_inputs_exprs, &tuple_info.types) {
+        let (n_snapshots, long_ty) = peel_snapshots(ctx.db.upcast(), *ty);
+        match long_ty {
+            TypeLongId::Tuple(types) => match_inputs.extend(destructure_matched_tuple(
+                ctx,
+                builder,
+                expr,
+                &TupleInfo { n_snapshots: tuple_info.n_snapshots + n_snapshots, types },
+            )?),
+            _ => match_inputs.push(expr.as_var_usage(ctx, builder)?),
+        }
+    }
+    Ok(match_inputs)
+}
 
-    let match_inputs = match_inputs_exprs
-        .into_iter()
-        .map(|expr| expr.as_var_usage(ctx, builder))
-        .collect::<LoweringResult<Vec<_>>>()?;
-    let extracted_enums_details = extract_concrete_enum_tuple(
+/// Lowers an expression of type [semantic::ExprMatch] where the matched expression is a tuple of
+/// enums.
+pub(crate) fn lower_expr_match_tuple(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    expr: LoweredExpr,
+    matched_expr: &semantic::Expr,
+    tuple_info: &TupleInfo,
+    arms: &[MatchArmWrapper],
+    match_type: MatchKind,
+) -> LoweringResult<LoweredExpr> {
+    let _span = match_lowering_span!(
+        "lower_expr_match_tuple",
+        n_arms = arms.len(),
+        n_positions = tuple_info.types.len(),
+    );
+    let location = expr.location();
+    let match_inputs = destructure_matched_tuple(ctx, builder, expr, tuple_info)?;
+    let (extracted_enums_details, shape) = extract_concrete_enum_tuple(
         ctx,
         matched_expr.stable_ptr().untyped(),
         &tuple_info.types,
         match_type,
     )?;
 
-    let otherwise_variant = get_underscore_pattern_path(ctx, arms, match_type);
+    let otherwise_variant = get_underscore_pattern_path(ctx, arms, match_type, true);
 
-    let variants_map = get_variants_to_arm_map_tuple(
+    let rows = get_tuple_variants_rows(
         ctx,
         arms.iter().take(
             otherwise_variant
@@ -626,18 +1379,44 @@ pub(crate) fn lower_expr_match_tuple(
                 .unwrap_or(arms.len()),
         ),
         extracted_enums_details.as_slice(),
+        &shape,
         match_type,
     )?;
+    let otherwise_row =
+        otherwise_variant.map(|pattern_path| catch_all_tuple_row(ctx, arms, pattern_path, &shape));
+    if let Some(index_arms) = try_as_bool_tuple_match(
+        ctx,
+        tuple_info,
+        &extracted_enums_details,
+        &rows,
+        otherwise_row.as_ref(),
+        arms,
+        match_type,
+    ) {
+        return lower_bool_tuple_match(
+            ctx,
+            builder,
+            &match_inputs,
+            &extracted_enums_details,
+            arms,
+            &index_arms,
+            location,
+        );
+    }
 
     let mut arms_vec = vec![];
     let mut match_tuple_ctx = LoweringMatchTupleContext {
         match_location: location,
-        otherwise_variant,
-        variants_map,
+        otherwise_row,
+        rows,
+        shape,
         match_inputs,
         n_snapshots_outer: tuple_info.n_snapshots,
         current_path: MatchingPath::default(),
         current_var_ids: vec![],
+        max_depth: 0,
+        missing_paths: vec![],
+        missing_arm_location: None,
     };
     let match_info = lower_full_match_tree(
         ctx,
@@ -648,6 +1427,21 @@ pub(crate) fn lower_expr_match_tuple(
         &mut arms_vec,
         match_type,
     )?;
+    if let Some(missing_arm_location) = match_tuple_ctx.missing_arm_location {
+        let missing_paths = format_missing_tuple_paths(
+            ctx,
+            &match_tuple_ctx.missing_paths,
+            &extracted_enums_details,
+            &match_tuple_ctx.shape,
+        );
+        report_non_exhaustive_match(
+            ctx,
+            missing_arm_location,
+            MatchDiagnostic::MissingMatchArm(missing_paths),
+            match_type,
+        )?;
+    }
+    ctx.tuple_match_tree_depths.insert(location, match_tuple_ctx.max_depth);
     let empty_match_info = MatchInfo::Enum(MatchEnumInfo {
         concrete_enum_id: extracted_enums_details[0].concrete_enum_id,
         input: match_tuple_ctx.match_inputs[0],
@@ -660,218 +1454,3251 @@ pub(crate) fn lower_expr_match_tuple(
     builder.merge_and_end_with_match(ctx, match_info, sealed_blocks, location)
 }
 
-/// Lowers an expression of type [semantic::ExprMatch].
-pub(crate) fn lower_expr_match(
+/// If `arms` match a pair of `bool`s as a truth table, i.e. every combination of their values is
+/// selected by some arm, and no arm binds a value, returns the arm selected by each combination, by
+/// its index `2 * a + b`, to be lowered by [lower_bool_tuple_match].
+fn try_as_bool_tuple_match(
+    ctx: &LoweringContext<'_, '_>,
+    tuple_info: &TupleInfo,
+    extracted_enums_details: &[ExtractedEnumDetails],
+    rows: &[TupleVariantsRow],
+    otherwise_row: Option<&TupleVariantsRow>,
+    arms: &[MatchArmWrapper],
+    match_type: MatchKind,
+) -> Option<Vec<usize>> {
+    require(match_type == MatchKind::Match && tuple_info.n_snapshots == 0)?;
+    let bool_ty = corelib::core_bool_ty(ctx.db.upcast());
+    require(tuple_info.types == [bool_ty, bool_ty])?;
+    let [first, second] = extracted_enums_details else {
+        return None;
+    };
+    let arenas = &ctx.function_body.arenas;
+    let is_binding_free = |pattern: PatternId| match &arenas.patterns[pattern] {
+        Pattern::Tuple(tuple) => tuple.field_patterns.iter().all(|pattern| {
+            matches!(
+                arenas.patterns[*pattern],
+                Pattern::EnumVariant(PatternEnumVariant { inner_pattern: None, .. })
+                    | Pattern::Otherwise(_)
+            )
+        }),
+        Pattern::Otherwise(_) => true,
+        _ => false,
+    };
+    // The variants of `bool` are ordered `False`, `True`, so the combinations are ordered by their
+    // index.
+    first
+        .concrete_variants
+        .iter()
+        .cartesian_product(second.concrete_variants.iter())
+        .map(|(first_variant, second_variant)| {
+            let path =
+                MatchingPath { variants: vec![first_variant.clone(), second_variant.clone()] };
+            let PatternPath { arm_index, pattern_index } =
+                &rows.iter().find(|row| row.matches(&path)).or(otherwise_row)?.pattern_path;
+            if let Some(pattern_index) = pattern_index {
+                require(is_binding_free(arms[*arm_index].patterns[*pattern_index]))?;
+            }
+            Some(*arm_index)
+        })
+        .collect()
+}
+
+/// Lowers a truth table match on a pair of `bool`s into a match on the first `bool`, whose arms
+/// match the second one and lead directly to the arm selected by the combination, instead of
+/// lowering the tuple patterns. See [try_as_bool_tuple_match].
+fn lower_bool_tuple_match(
     ctx: &mut LoweringContext<'_, '_>,
-    expr: &semantic::ExprMatch,
     builder: &mut BlockBuilder,
+    match_inputs: &[VarUsage],
+    extracted_enums_details: &[ExtractedEnumDetails],
+    arms: &[MatchArmWrapper],
+    index_arms: &[usize],
+    location: LocationId,
 ) -> LoweringResult<LoweredExpr> {
-    log::trace!("Lowering a match expression: {:?}", expr.debug(&ctx.expr_formatter));
-    let location = ctx.get_location(expr.stable_ptr.untyped());
-    let lowered_expr = lower_expr(ctx, builder, expr.matched_expr)?;
+    let [first, second] = match_inputs else {
+        unreachable!("A match on a pair of `bool`s has two inputs.");
+    };
+    let concrete_enum_id = extracted_enums_details[0].concrete_enum_id;
+    let variants = &extracted_enums_details[0].concrete_variants;
+    let unit_type = unit_ty(ctx.db.upcast());
+    let mut arms_vec = vec![];
+    let mut first_arms = vec![];
+    for (first_index, first_variant) in variants.iter().enumerate() {
+        let subscope = create_subscope(ctx, builder);
+        let mut second_arms = vec![];
+        for (second_index, second_variant) in variants.iter().enumerate() {
+            let leaf = create_subscope(ctx, &subscope);
+            second_arms.push(MatchArm {
+                arm_selector: MatchArmSelector::VariantId(second_variant.clone()),
+                block_id: leaf.block_id,
+                var_ids: vec![ctx.new_var(VarRequest { ty: unit_type, location })],
+            });
+            arms_vec.push(MatchLeafBuilder {
+                arm_index: index_arms[2 * first_index + second_index],
+                lowering_result: Ok(()),
+                builder: leaf,
+            });
+        }
+        first_arms.push(MatchArm {
+            arm_selector: MatchArmSelector::VariantId(first_variant.clone()),
+            block_id: subscope.block_id,
+            var_ids: vec![ctx.new_var(VarRequest { ty: unit_type, location })],
+        });
+        let second_match_info = MatchInfo::Enum(MatchEnumInfo {
+            concrete_enum_id,
+            input: *second,
+            arms: second_arms,
+            location,
+        });
+        subscope.finalize(ctx, FlatBlockEnd::Match { info: second_match_info });
+    }
+    let match_info = MatchInfo::Enum(MatchEnumInfo {
+        concrete_enum_id,
+        input: *first,
+        arms: first_arms,
+        location,
+    });
+    let empty_match_info =
+        MatchInfo::Enum(MatchEnumInfo { concrete_enum_id, input: *first, arms: vec![], location });
+    let sealed_blocks =
+        group_match_arms(ctx, empty_match_info, location, arms, arms_vec, MatchKind::Match)?;
+    builder.merge_and_end_with_match(ctx, match_info, sealed_blocks, location)
+}
 
-    let matched_expr = ctx.function_body.arenas.exprs[expr.matched_expr].clone();
-    let ty = matched_expr.ty();
+/// A pattern of a match on a tuple of numeric values, with the literal matched by each position of
+/// the tuple, or `None` for positions matching any value.
+struct TupleLiteralsRow {
+    pattern_path: PatternPath,
+    literals: Vec<Option<semantic::ExprLiteral>>,
+}
 
-    if ty == ctx.db.core_info().felt252 {
-        let match_input = lowered_expr.as_var_usage(ctx, builder)?;
-        return lower_expr_match_felt252(ctx, expr, match_input, builder, None);
-    }
-    if let Some(convert_function) =
-        corelib::get_convert_to_felt252_libfunc_name_by_type(ctx.db.upcast(), ty)
-    {
-        let match_input = lowered_expr.as_var_usage(ctx, builder)?;
-        return lower_expr_match_felt252(ctx, expr, match_input, builder, Some(convert_function));
-    }
+/// Information needed to lower a match on a tuple of numeric values.
+struct LoweringMatchTupleLiteralsContext {
+    /// The location of the match expression.
+    match_location: LocationId,
+    /// The tuple's destructured inputs, converted to felt252.
+    match_inputs: Vec<VarUsage>,
+    /// The patterns of the match, by order of precedence.
+    rows: Vec<TupleLiteralsRow>,
+    /// Whether each of the rows is selected by some leaf of the decision tree.
+    reached_rows: Vec<bool>,
+    /// The number of arms of the match.
+    n_arms: usize,
+}
 
-    let (n_snapshots, long_type_id) = peel_snapshots(ctx.db.upcast(), ty);
+/// Returns whether a match on a tuple with positions of the given types should be lowered by
+/// comparing each position to the literals of the arms.
+fn is_tuple_of_numeric_types(ctx: &LoweringContext<'_, '_>, types: &[semantic::TypeId]) -> bool {
+    !types.is_empty()
+        && types.iter().all(|ty| {
+            *ty == ctx.db.core_info().felt252
+                || corelib::get_convert_to_felt252_libfunc_name_by_type(ctx.db.upcast(), *ty)
+                    .is_some()
+        })
+}
 
-    let arms = expr.arms.iter().map(|arm| arm.into()).collect_vec();
-    if let Some(types) = try_extract_matches!(long_type_id, TypeLongId::Tuple) {
-        return lower_expr_match_tuple(
-            ctx,
-            builder,
-            lowered_expr,
-            &matched_expr,
-            &TupleInfo { n_snapshots, types },
-            &arms,
-            MatchKind::Match,
-        );
-    }
+/// The way a member of a struct is discriminated in the decision tree of a match on the struct.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StructMemberTest {
+    /// The member is not discriminated, as no arm restricts it.
+    None,
+    /// The member is a (snapshot of an) enum, discriminated by its variant.
+    Enum { concrete_enum_id: semantic::ConcreteEnumId, n_snapshots: usize },
+    /// The member is a (snapshot of a) number, compared to the literals of the arms.
+    Numeric { n_snapshots: usize },
+}
 
-    // TODO(spapini): Use diagnostics.
-    // TODO(spapini): Handle more than just enums.
-    if let LoweredExpr::ExternEnum(extern_enum) = lowered_expr {
-        return lower_optimized_extern_match(ctx, builder, extern_enum, &arms, MatchKind::Match);
-    }
-    lower_concrete_enum_match(
-        ctx,
-        builder,
-        &matched_expr,
-        lowered_expr,
-        &arms,
-        location,
-        MatchKind::Match,
-    )
+/// The decision taken on a member of a struct in a path of the decision tree of a match on it.
+#[derive(Clone)]
+enum StructMemberDecision {
+    /// The member is not discriminated on the path.
+    Any,
+    /// The member is the given variant, whose payload is in the given variable.
+    Variant(semantic::ConcreteVariant, VariableId),
+    /// The member equals the given value.
+    Value(BigInt),
+    /// The member differs from all the literals of the arms which may still match.
+    Other,
 }
 
-pub(crate) fn lower_concrete_enum_match(
+/// A pattern of an arm of a match on a struct, with the pattern of each member of the struct, or
+/// `None` for members it does not restrict.
+struct StructMatchRow {
+    arm_index: usize,
+    stable_ptr: SyntaxStablePtrId,
+    member_patterns: Vec<Option<PatternId>>,
+}
+
+/// Information needed to lower a match on a struct expression.
+struct LoweringMatchStructContext {
+    /// The location of the matched expression.
+    match_location: LocationId,
+    /// The name of the struct and of each of its members, to explain missing arms.
+    struct_name: SmolStr,
+    member_names: Vec<SmolStr>,
+    /// The way each member of the struct is discriminated.
+    tests: Vec<StructMemberTest>,
+    rows: Vec<StructMatchRow>,
+    /// The destructured members of the matched struct.
+    member_vars: Vec<VariableId>,
+    /// The decision taken on each member in the current path of the tree.
+    current_path: Vec<StructMemberDecision>,
+    /// The rows reached by some path of the tree.
+    reached_rows: UnorderedHashSet<usize>,
+    /// The paths of the tree not covered by any arm, formatted as patterns, in declaration order.
+    missing_paths: Vec<String>,
+}
+
+/// Lowers an expression of type [semantic::ExprMatch] where the matched expression is a struct.
+/// The struct is destructured into its members, and the arms are lowered into a decision tree
+/// over the members, in their declaration order. Members of an enum type are discriminated by
+/// their variant, and numeric members by the literals of the arms. A member is only discriminated
+/// on a path of the tree if some arm which may still be taken restricts it.
+fn lower_expr_match_struct(
     ctx: &mut LoweringContext<'_, '_>,
     builder: &mut BlockBuilder,
-    matched_expr: &semantic::Expr,
-    lowered_matched_expr: LoweredExpr,
+    expr: LoweredExpr,
+    concrete_struct_id: semantic::ConcreteStructId,
+    n_snapshots: usize,
     arms: &[MatchArmWrapper],
-    location: LocationId,
-    match_type: MatchKind,
 ) -> LoweringResult<LoweredExpr> {
-    let ExtractedEnumDetails { concrete_enum_id, concrete_variants, n_snapshots } =
-        extract_concrete_enum(ctx, matched_expr.into(), matched_expr.ty(), match_type)?;
-    let match_input = lowered_matched_expr.as_var_usage(ctx, builder)?;
+    let _span = match_lowering_span!("lower_expr_match_struct", n_arms = arms.len());
+    let location = expr.location();
+    let members =
+        ctx.db.concrete_struct_members(concrete_struct_id).map_err(LoweringFlowError::Failed)?;
+    let member_tys = members
+        .values()
+        .map(|member| wrap_in_snapshots(ctx.db.upcast(), member.ty, n_snapshots))
+        .collect_vec();
+    let rows = get_struct_match_rows(ctx, arms, &members)?;
+    let tests = member_tys
+        .iter()
+        .enumerate()
+        .map(|(member_index, ty)| get_struct_member_test(ctx, &rows, member_index, *ty))
+        .collect_vec();
+    let struct_name = concrete_struct_id.struct_id(ctx.db.upcast()).name(ctx.db.upcast());
 
-    // Merge arm blocks.
-    let otherwise_variant = get_underscore_pattern_path(ctx, arms, match_type);
+    let Some(first_row) = rows.first() else {
+        report_non_exhaustive_match(
+            ctx,
+            location,
+            MatchDiagnostic::MissingMatchArm(vec![format!("{struct_name} {{ .. }}")]),
+            MatchKind::Match,
+        )?;
+        return Err(lower_uncovered_value_panic(ctx, builder, location));
+    };
+    // If no arm discriminates the struct, the first arm is always taken.
+    if next_struct_member_test(ctx, &tests, &rows, &(0..rows.len()).collect_vec(), 0).is_none() {
+        let unreachable = rows.iter().skip(1).map(|row| row.stable_ptr).collect();
+        report_unreachable_arms(ctx, unreachable, MatchKind::Match);
+        let pattern = arms[first_row.arm_index].patterns[0];
+        return lower_single_leaf_match(ctx, builder, arms, first_row.arm_index, pattern, expr);
+    }
 
-    let variant_map = get_variant_to_arm_map(
+    let member_vars = generators::StructDestructure {
+        input: expr.as_var_usage(ctx, builder)?,
+        var_reqs: member_tys.iter().map(|ty| VarRequest { ty: *ty, location }).collect(),
+    }
+    .add(ctx, &mut builder.statements);
+    let mut match_struct_ctx = LoweringMatchStructContext {
+        match_location: location,
+        struct_name,
+        member_names: members.keys().cloned().collect(),
+        current_path: vec![StructMemberDecision::Any; member_vars.len()],
+        tests,
+        rows,
+        member_vars,
+        reached_rows: UnorderedHashSet::default(),
+        missing_paths: vec![],
+    };
+    let mut leaves = vec![];
+    let alive_rows = (0..match_struct_ctx.rows.len()).collect_vec();
+    let match_info = lower_struct_match_node(
         ctx,
-        arms.iter().take(
-            otherwise_variant
-                .as_ref()
-                .map(|PatternPath { arm_index, .. }| *arm_index)
-                .unwrap_or(arms.len()),
-        ),
-        concrete_enum_id,
-        match_type,
+        builder,
+        arms,
+        &mut match_struct_ctx,
+        alive_rows,
+        0,
+        &mut leaves,
     )?;
-    let mut arm_var_ids = vec![];
-    let mut block_ids = vec![];
-    let variants_block_builders = concrete_variants
+    let unreachable = match_struct_ctx
+        .rows
         .iter()
-        .map(|concrete_variant| {
-            let PatternPath { arm_index, pattern_index } = variant_map
-                .get(concrete_variant)
-                .or(otherwise_variant.as_ref())
-                .ok_or_else(|| {
-                    LoweringFlowError::Failed(ctx.diagnostics.report_by_location(
-                        location.lookup_intern(ctx.db),
+        .enumerate()
+        .filter(|(row_index, _)| !match_struct_ctx.reached_rows.contains(row_index))
+        .map(|(_, row)| row.stable_ptr)
+        .collect();
+    report_unreachable_arms(ctx, unreachable, MatchKind::Match);
+    if !match_struct_ctx.missing_paths.is_empty() {
+        report_non_exhaustive_match(
+            ctx,
+            location,
+            MatchDiagnostic::MissingMatchArm(match_struct_ctx.missing_paths),
+            MatchKind::Match,
+        )?;
+    }
+
+    let empty_match_info = match &match_info {
+        MatchInfo::Enum(info) => MatchInfo::Enum(MatchEnumInfo { arms: vec![], ..info.clone() }),
+        MatchInfo::Extern(info) => {
+            MatchInfo::Extern(MatchExternInfo { arms: vec![], ..info.clone() })
+        }
+        MatchInfo::Value(_) => unreachable!("A struct match node is an enum or extern match."),
+    };
+    let sealed_blocks =
+        group_match_arms(ctx, empty_match_info, location, arms, leaves, MatchKind::Match)?;
+    builder.merge_and_end_with_match(ctx, match_info, sealed_blocks, location)
+}
+
+/// Lowers a match whose patterns do not discriminate the matched value, so `pattern` of the arm at
+/// `arm_index` is always taken, as the single leaf of the match.
+///
+/// The leaf continues the block of `builder`, so no branch is added.
+fn lower_single_leaf_match(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    arms: &[MatchArmWrapper],
+    arm_index: usize,
+    pattern: PatternId,
+    expr: LoweredExpr,
+) -> LoweringResult<LoweredExpr> {
+    let location = expr.location();
+    // `builder` is replaced by an unreachable sibling, ending the block if the leaf does not flow
+    // to the code after the match.
+    let sibling = builder.sibling_block_builder(alloc_empty_block(ctx));
+    let mut leaf = std::mem::replace(builder, sibling);
+    let input = expr.clone().as_var_usage(ctx, builder)?;
+    let empty_match_info =
+        MatchInfo::Value(MatchEnumValue { num_of_arms: 0, arms: vec![], input, location });
+    let pattern = ctx.function_body.arenas.patterns[pattern].clone();
+    let lowering_result = lower_single_pattern(ctx, &mut leaf, pattern, expr);
+    let leaves = vec![MatchLeafBuilder { arm_index, lowering_result, builder: leaf }];
+    let sealed_blocks =
+        group_match_arms(ctx, empty_match_info.clone(), location, arms, leaves, MatchKind::Match)?;
+    // Otherwise, the leaf ends, so the code after the match is not reached.
+    let Ok([SealedBlockBuilder::GotoCallsite { builder: leaf, expr }]) =
+        <[_; 1]>::try_from(sealed_blocks)
+    else {
+        return Err(LoweringFlowError::Match(empty_match_info));
+    };
+    std::mem::replace(builder, leaf).finalize(ctx, FlatBlockEnd::Match { info: empty_match_info });
+    Ok(match expr {
+        Some(var_usage) => LoweredExpr::AtVariable(var_usage),
+        None => LoweredExpr::Tuple { exprs: vec![], location },
+    })
+}
+
+/// Returns whether some arm of a match has a struct pattern.
+fn has_struct_pattern_arm(ctx: &LoweringContext<'_, '_>, arms: &[MatchArmWrapper]) -> bool {
+    arms.iter()
+        .flat_map(|arm| &arm.patterns)
+        .any(|pattern| matches!(ctx.function_body.arenas.patterns[*pattern], Pattern::Struct(_)))
+}
+
+/// Returns the rows of the decision tree of a match on a struct with the given members - one for
+/// each pattern of each arm.
+fn get_struct_match_rows(
+    ctx: &mut LoweringContext<'_, '_>,
+    arms: &[MatchArmWrapper],
+    members: &OrderedHashMap<SmolStr, semantic::Member>,
+) -> LoweringResult<Vec<StructMatchRow>> {
+    let mut rows = vec![];
+    for (arm_index, arm) in arms.iter().enumerate() {
+        for pattern_id in &arm.patterns {
+            let pattern = &ctx.function_body.arenas.patterns[*pattern_id];
+            let mut member_patterns = vec![None; members.len()];
+            match pattern {
+                Pattern::Struct(struct_pattern) => {
+                    // Members omitted with `..` are not restricted.
+                    for (member, member_pattern) in &struct_pattern.field_patterns {
+                        let member_index = members
+                            .values()
+                            .position(|other| other.id == member.id)
+                            .expect("The members of the pattern are members of the struct.");
+                        member_patterns[member_index] = Some(*member_pattern);
+                    }
+                }
+                Pattern::Otherwise(_) => {}
+                _ => {
+                    return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+                        pattern.stable_ptr(),
                         MatchError(MatchError {
-                            kind: match_type,
-                            error: MatchDiagnostic::MissingMatchArm(format!(
-                                "{}",
-                                concrete_variant.id.name(ctx.db.upcast())
-                            )),
+                            kind: MatchKind::Match,
+                            error: MatchDiagnostic::UnsupportedMatchArmNotAStruct,
                         }),
-                    ))
-                })?;
-            let arm = &arms[*arm_index];
+                    )));
+                }
+            }
+            rows.push(StructMatchRow {
+                arm_index,
+                stable_ptr: pattern.stable_ptr().untyped(),
+                member_patterns,
+            });
+        }
+    }
+    Ok(rows)
+}
 
-            let mut subscope = create_subscope(ctx, builder);
+/// Returns the way the member at `member_index`, of type `ty`, is discriminated in the decision
+/// tree of a match on a struct.
+fn get_struct_member_test(
+    ctx: &LoweringContext<'_, '_>,
+    rows: &[StructMatchRow],
+    member_index: usize,
+    ty: semantic::TypeId,
+) -> StructMemberTest {
+    let (n_snapshots, long_ty) = peel_snapshots(ctx.db.upcast(), ty);
+    let mut member_patterns = rows
+        .iter()
+        .filter_map(|row| row.member_patterns[member_index])
+        .map(|pattern| &ctx.function_body.arenas.patterns[pattern]);
+    match long_ty {
+        TypeLongId::Concrete(ConcreteTypeId::Enum(concrete_enum_id))
+            if member_patterns.any(|pattern| matches!(pattern, Pattern::EnumVariant(_))) =>
+        {
+            StructMemberTest::Enum { concrete_enum_id, n_snapshots }
+        }
+        _ if member_patterns.any(|pattern| matches!(pattern, Pattern::Literal(_))) => {
+            StructMemberTest::Numeric { n_snapshots }
+        }
+        _ => StructMemberTest::None,
+    }
+}
 
-            let pattern = pattern_index.map(|pattern_index| {
-                &ctx.function_body.arenas.patterns[arm.patterns[pattern_index]]
-            });
-            let block_id = subscope.block_id;
-            block_ids.push(block_id);
+/// Returns whether `pattern` restricts the value of a member discriminated by the decision tree of
+/// a match on a struct.
+fn is_refutable_struct_member_pattern(pattern: &Pattern) -> bool {
+    matches!(pattern, Pattern::EnumVariant(_) | Pattern::Literal(_))
+}
 
-            let lowering_inner_pattern_result = match pattern {
-                Some(Pattern::EnumVariant(PatternEnumVariant {
-                    inner_pattern: Some(inner_pattern),
-                    ..
-                })) => {
-                    let inner_pattern = ctx.function_body.arenas.patterns[*inner_pattern].clone();
-                    let pattern_location = ctx.get_location(inner_pattern.stable_ptr().untyped());
-
-                    let var_id = ctx.new_var(VarRequest {
-                        ty: wrap_in_snapshots(ctx.db.upcast(), concrete_variant.ty, n_snapshots),
-                        location: pattern_location,
-                    });
-                    arm_var_ids.push(vec![var_id]);
-                    let variant_expr =
-                        LoweredExpr::AtVariable(VarUsage { var_id, location: pattern_location });
-
-                    lower_single_pattern(ctx, &mut subscope, inner_pattern, variant_expr)
-                }
-                Some(
-                    Pattern::EnumVariant(PatternEnumVariant { inner_pattern: None, .. })
-                    | Pattern::Otherwise(_),
-                ) => {
-                    let var_id = ctx.new_var(VarRequest {
-                        ty: wrap_in_snapshots(ctx.db.upcast(), concrete_variant.ty, n_snapshots),
-                        location: ctx.get_location(pattern.unwrap().into()),
-                    });
-                    arm_var_ids.push(vec![var_id]);
-                    Ok(())
+/// Returns the index of the first member from `member_index` on which is restricted by some of
+/// the `alive_rows`, and is therefore discriminated in the decision tree of a match on a struct.
+fn next_struct_member_test(
+    ctx: &LoweringContext<'_, '_>,
+    tests: &[StructMemberTest],
+    rows: &[StructMatchRow],
+    alive_rows: &[usize],
+    member_index: usize,
+) -> Option<usize> {
+    (member_index..tests.len()).find(|member_index| {
+        tests[*member_index] != StructMemberTest::None
+            && alive_rows.iter().any(|row_index| {
+                rows[*row_index].member_patterns[*member_index].is_some_and(|pattern| {
+                    is_refutable_struct_member_pattern(&ctx.function_body.arenas.patterns[pattern])
+                })
+            })
+    })
+}
+
+/// Returns the rows of `alive_rows` which match the decision taken on the member at
+/// `member_index`.
+fn filter_struct_match_rows(
+    ctx: &LoweringContext<'_, '_>,
+    match_struct_ctx: &LoweringMatchStructContext,
+    alive_rows: &[usize],
+    member_index: usize,
+) -> Vec<usize> {
+    let decision = &match_struct_ctx.current_path[member_index];
+    alive_rows
+        .iter()
+        .copied()
+        .filter(|row_index| {
+            let Some(pattern) = match_struct_ctx.rows[*row_index].member_patterns[member_index]
+            else {
+                return true;
+            };
+            match (&ctx.function_body.arenas.patterns[pattern], decision) {
+                (Pattern::EnumVariant(enum_pattern), StructMemberDecision::Variant(variant, _)) => {
+                    enum_pattern.variant == *variant
                 }
-                None => {
-                    let var_id = ctx.new_var(VarRequest {
-                        ty: wrap_in_snapshots(ctx.db.upcast(), concrete_variant.ty, n_snapshots),
-                        location,
-                    });
-                    arm_var_ids.push(vec![var_id]);
-                    Ok(())
+                (Pattern::Literal(literal_pattern), StructMemberDecision::Value(value)) => {
+                    literal_pattern.literal.value == *value
                 }
-                _ => unreachable!(
-                    "function `get_variant_to_arm_map` should have reported every other pattern \
-                     type"
-                ),
-            };
-            Ok(MatchLeafBuilder {
-                arm_index: *arm_index,
-                lowering_result: lowering_inner_pattern_result,
-                builder: subscope,
-            })
+                (Pattern::EnumVariant(_) | Pattern::Literal(_), _) => false,
+                _ => true,
+            }
         })
-        .collect::<Vec<_>>()
-        .into_iter()
-        .collect::<LoweringResult<Vec<_>>>()?;
+        .collect()
+}
 
-    let empty_match_info = MatchInfo::Enum(MatchEnumInfo {
-        concrete_enum_id,
-        input: match_input,
-        arms: vec![],
-        location,
-    });
+/// Lowers a node of the decision tree of a match on a struct, discriminating the first member
+/// from `member_index` on which is restricted by some of the `alive_rows`, at the end of `builder`.
+/// Such a member must exist. Returns the info of the match ending `builder`.
+fn lower_struct_match_node(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    arms: &[MatchArmWrapper],
+    match_struct_ctx: &mut LoweringMatchStructContext,
+    alive_rows: Vec<usize>,
+    member_index: usize,
+    leaves: &mut Vec<MatchLeafBuilder>,
+) -> LoweringResult<MatchInfo> {
+    let member_index = next_struct_member_test(
+        ctx,
+        &match_struct_ctx.tests,
+        &match_struct_ctx.rows,
+        &alive_rows,
+        member_index,
+    )
+    .expect("A node of the decision tree discriminates some member.");
+    let location = match_struct_ctx.match_location;
+    let input = VarUsage { var_id: match_struct_ctx.member_vars[member_index], location };
+    let match_info = match match_struct_ctx.tests[member_index] {
+        StructMemberTest::Enum { concrete_enum_id, n_snapshots } => {
+            let concrete_variants =
+                ctx.concrete_enum_variants(concrete_enum_id).map_err(LoweringFlowError::Failed)?;
+            let mut match_arms = vec![];
+            for concrete_variant in concrete_variants.iter().cloned() {
+                let subscope = create_subscope(ctx, builder);
+                let var_id = ctx.new_var(VarRequest {
+                    ty: wrap_in_snapshots(ctx.db.upcast(), concrete_variant.ty, n_snapshots),
+                    location,
+                });
+                match_arms.push(MatchArm {
+                    arm_selector: MatchArmSelector::VariantId(concrete_variant.clone()),
+                    block_id: subscope.block_id,
+                    var_ids: vec![var_id],
+                });
+                match_struct_ctx.current_path[member_index] =
+                    StructMemberDecision::Variant(concrete_variant, var_id);
+                lower_struct_match_subtree(
+                    ctx,
+                    subscope,
+                    arms,
+                    match_struct_ctx,
+                    &alive_rows,
+                    member_index,
+                    leaves,
+                )?;
+            }
+            MatchInfo::Enum(MatchEnumInfo { concrete_enum_id, input, arms: match_arms, location })
+        }
+        StructMemberTest::Numeric { n_snapshots } => {
+            let mut input = input;
+            for _ in 0..n_snapshots {
+                input = generators::Desnap { input, location }.add(ctx, &mut builder.statements);
+            }
+            let values = alive_rows
+                .iter()
+                .filter_map(|row_index| {
+                    let pattern = match_struct_ctx.rows[*row_index].member_patterns[member_index]?;
+                    try_extract_matches!(
+                        &ctx.function_body.arenas.patterns[pattern],
+                        Pattern::Literal
+                    )
+                    .map(|literal_pattern| literal_pattern.literal.value.clone())
+                })
+                .unique()
+                .collect_vec();
+            // A chain of comparisons to the values, the first of which ends `builder`.
+            let mut match_info = None;
+            let mut not_equal_builder: Option<BlockBuilder> = None;
+            for value in values {
+                let parent = not_equal_builder.as_ref().unwrap_or(builder);
+                let equal_subscope = create_subscope(ctx, parent);
+                let not_equal_subscope = create_subscope(ctx, parent);
+                let end = &value + 1;
+                let info = numeric_range_check_match_info(
+                    ctx,
+                    input,
+                    value.clone(),
+                    end,
+                    equal_subscope.block_id,
+                    not_equal_subscope.block_id,
+                    location,
+                );
+                match not_equal_builder.replace(not_equal_subscope) {
+                    Some(parent) => parent.finalize(ctx, FlatBlockEnd::Match { info }),
+                    None => match_info = Some(info),
+                }
+                match_struct_ctx.current_path[member_index] = StructMemberDecision::Value(value);
+                lower_struct_match_subtree(
+                    ctx,
+                    equal_subscope,
+                    arms,
+                    match_struct_ctx,
+                    &alive_rows,
+                    member_index,
+                    leaves,
+                )?;
+            }
+            match_struct_ctx.current_path[member_index] = StructMemberDecision::Other;
+            lower_struct_match_subtree(
+                ctx,
+                not_equal_builder.expect("A numeric member is compared to some literal."),
+                arms,
+                match_struct_ctx,
+                &alive_rows,
+                member_index,
+                leaves,
+            )?;
+            match_info.unwrap()
+        }
+        StructMemberTest::None => unreachable!("Only discriminated members are tested."),
+    };
+    match_struct_ctx.current_path[member_index] = StructMemberDecision::Any;
+    Ok(match_info)
+}
 
-    let sealed_blocks = group_match_arms(
+/// Lowers the subtree of the decision tree of a match on a struct below the decision taken on the
+/// member at `member_index`, into `builder` - either another node, or a leaf of the first arm
+/// matching the decisions of the path.
+fn lower_struct_match_subtree(
+    ctx: &mut LoweringContext<'_, '_>,
+    mut builder: BlockBuilder,
+    arms: &[MatchArmWrapper],
+    match_struct_ctx: &mut LoweringMatchStructContext,
+    alive_rows: &[usize],
+    member_index: usize,
+    leaves: &mut Vec<MatchLeafBuilder>,
+) -> LoweringResult<()> {
+    let alive_rows = filter_struct_match_rows(ctx, match_struct_ctx, alive_rows, member_index);
+    if next_struct_member_test(
         ctx,
-        empty_match_info,
-        location,
-        arms,
-        variants_block_builders,
-        match_type,
+        &match_struct_ctx.tests,
+        &match_struct_ctx.rows,
+        &alive_rows,
+        member_index + 1,
+    )
+    .is_some()
+    {
+        let match_info = lower_struct_match_node(
+            ctx,
+            &mut builder,
+            arms,
+            match_struct_ctx,
+            alive_rows,
+            member_index + 1,
+            leaves,
+        )?;
+        builder.finalize(ctx, FlatBlockEnd::Match { info: match_info });
+        return Ok(());
+    }
+    let Some(row_index) = alive_rows.first().copied() else {
+        let missing_path = format_missing_struct_path(ctx, match_struct_ctx);
+        match_struct_ctx.missing_paths.push(missing_path);
+        let arm_index = arms.len() + leaves.len();
+        leaves.push(lower_uncovered_leaf(ctx, builder, match_struct_ctx.match_location, arm_index));
+        return Ok(());
+    };
+    match_struct_ctx.reached_rows.insert(row_index);
+    let lowering_result = bind_struct_match_row(ctx, &mut builder, match_struct_ctx, row_index);
+    leaves.push(MatchLeafBuilder {
+        arm_index: match_struct_ctx.rows[row_index].arm_index,
+        lowering_result,
+        builder,
+    });
+    Ok(())
+}
+
+/// Binds the member patterns of the row at `row_index` to the members of the matched struct, at
+/// a leaf of the decision tree of a match on a struct.
+fn bind_struct_match_row(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    match_struct_ctx: &LoweringMatchStructContext,
+    row_index: usize,
+) -> LoweringResult<()> {
+    let row = &match_struct_ctx.rows[row_index];
+    for (member_index, pattern) in row.member_patterns.iter().enumerate() {
+        let Some(pattern) = pattern else {
+            continue;
+        };
+        let pattern = ctx.function_body.arenas.patterns[*pattern].clone();
+        let location = ctx.get_location(pattern.stable_ptr().untyped());
+        let member_var = match_struct_ctx.member_vars[member_index];
+        let member_expr = match (&pattern, &match_struct_ctx.current_path[member_index]) {
+            (Pattern::EnumVariant(PatternEnumVariant { inner_pattern, .. }), _) => {
+                let (Some(inner_pattern), StructMemberDecision::Variant(_, var_id)) =
+                    (inner_pattern, &match_struct_ctx.current_path[member_index])
+                else {
+                    continue;
+                };
+                let inner_pattern = ctx.function_body.arenas.patterns[*inner_pattern].clone();
+                lower_single_pattern(
+                    ctx,
+                    builder,
+                    inner_pattern,
+                    LoweredExpr::AtVariable(VarUsage { var_id: *var_id, location }),
+                )?;
+                continue;
+            }
+            (Pattern::Literal(_), _) => continue,
+            // The member was consumed by the match on its variant, so it is reconstructed from the
+            // variant and its payload, unless it is a snapshot, which may be used again.
+            (_, StructMemberDecision::Variant(variant, var_id))
+                if !matches!(
+                    ctx.variables[member_var].ty.lookup_intern(ctx.db),
+                    TypeLongId::Snapshot(_)
+                ) =>
+            {
+                generators::EnumConstruct {
+                    input: VarUsage { var_id: *var_id, location },
+                    variant: variant.clone(),
+                    location,
+                }
+                .add(ctx, &mut builder.statements)
+            }
+            _ => VarUsage { var_id: member_var, location },
+        };
+        lower_single_pattern(ctx, builder, pattern, LoweredExpr::AtVariable(member_expr))?;
+    }
+    Ok(())
+}
+
+/// Formats the current path of the decision tree of a match on a struct, which is not covered by
+/// any arm, as a struct pattern.
+fn format_missing_struct_path(
+    ctx: &LoweringContext<'_, '_>,
+    match_struct_ctx: &LoweringMatchStructContext,
+) -> String {
+    let mut has_omitted_members = false;
+    let mut members = vec![];
+    for (name, decision) in zip_eq(&match_struct_ctx.member_names, &match_struct_ctx.current_path) {
+        match decision {
+            StructMemberDecision::Any => has_omitted_members = true,
+            StructMemberDecision::Variant(variant, _) => {
+                members.push(format!("{name}: {}", variant.id.name(ctx.db.upcast())))
+            }
+            StructMemberDecision::Value(value) => members.push(format!("{name}: {value}")),
+            StructMemberDecision::Other => members.push(format!("{name}: _")),
+        }
+    }
+    if has_omitted_members {
+        members.push("..".into());
+    }
+    format!("{} {{ {} }}", match_struct_ctx.struct_name, members.join(", "))
+}
+
+/// The lengths of the matched value matched by a pattern of an arm of a match on a span.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SpanLength {
+    /// Exactly the given number of elements, e.g. `[a, b]`.
+    Exact(usize),
+    /// At least the given number of elements, e.g. `[a, .. tail]` or `_`.
+    AtLeast(usize),
+}
+
+/// A pattern of an arm of a match on a fixed size array or a span, with the lengths it matches.
+struct SpanMatchRow {
+    arm_index: usize,
+    pattern: PatternId,
+    length: SpanLength,
+}
+
+/// Information needed to lower a match on a span expression.
+struct LoweringMatchSpanContext {
+    /// The location of the matched expression.
+    match_location: LocationId,
+    /// The matched span.
+    span: VarUsage,
+    /// The type of the elements of the span.
+    element_ty: semantic::TypeId,
+    rows: Vec<SpanMatchRow>,
+    /// The snapshot of the array of the span before each pop in the current path of the tree,
+    /// starting with the whole array.
+    remaining_arrays: Vec<VariableId>,
+    /// The boxed snapshots of the elements popped in the current path of the tree.
+    boxed_elements: Vec<VariableId>,
+    /// The rows reached by some path of the tree.
+    reached_rows: UnorderedHashSet<usize>,
+    /// The lengths of the span not covered by any arm, formatted as patterns, in increasing order.
+    missing_lengths: Vec<String>,
+}
+
+/// The step of the decision tree of a match on a span, at the end of the current path.
+enum SpanMatchStep {
+    /// The row at the given index is taken.
+    Leaf(usize),
+    /// No row may be taken.
+    Missing,
+    /// Another element is popped from the span.
+    Pop,
+}
+
+/// Lowers an expression of type [semantic::ExprMatch] where the matched expression is a fixed
+/// size array or a span, of type `ty`.
+/// The length of a fixed size array is known, so its first arm is always taken. A span is lowered
+/// into a decision tree over its length: elements are popped from the front of the span, using
+/// `array_snapshot_pop_front`, as long as some arm which may still be taken requires more
+/// elements. The patterns of the elements, and the rest of the span, are bound at the leaves.
+fn lower_expr_match_array(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    expr: LoweredExpr,
+    ty: semantic::TypeId,
+    arms: &[MatchArmWrapper],
+) -> LoweringResult<LoweredExpr> {
+    let _span = match_lowering_span!("lower_expr_match_array", n_arms = arms.len());
+    let location = expr.location();
+    let rows = get_span_match_rows(ctx, arms)?;
+    let Some(element_ty) = corelib::try_extract_span_element_type(ctx.db.upcast(), ty) else {
+        // All the patterns of a fixed size array match its length.
+        let Some(first_row) = rows.first() else {
+            report_non_exhaustive_match(
+                ctx,
+                location,
+                MatchDiagnostic::MissingMatchArm(vec!["[..]".into()]),
+                MatchKind::Match,
+            )?;
+            return Err(lower_uncovered_value_panic(ctx, builder, location));
+        };
+        return lower_span_match_single_row(ctx, builder, arms, &rows, first_row, expr);
+    };
+
+    let span = expr.as_var_usage(ctx, builder)?;
+    let mut match_span_ctx = LoweringMatchSpanContext {
+        match_location: location,
+        span,
+        element_ty,
+        rows,
+        remaining_arrays: vec![],
+        boxed_elements: vec![],
+        reached_rows: UnorderedHashSet::default(),
+        missing_lengths: vec![],
+    };
+    match next_span_match_step(&match_span_ctx, false) {
+        SpanMatchStep::Leaf(row_index) => {
+            let row = &match_span_ctx.rows[row_index];
+            return lower_span_match_single_row(
+                ctx,
+                builder,
+                arms,
+                &match_span_ctx.rows,
+                row,
+                LoweredExpr::AtVariable(span),
+            );
+        }
+        SpanMatchStep::Missing => {
+            report_non_exhaustive_match(
+                ctx,
+                location,
+                MatchDiagnostic::NonExhaustiveMatchSpanLength(vec![format_missing_span_length(
+                    0, false,
+                )]),
+                MatchKind::Match,
+            )?;
+            return Err(lower_uncovered_value_panic(ctx, builder, location));
+        }
+        SpanMatchStep::Pop => {}
+    }
+
+    let array_ty =
+        TypeLongId::Snapshot(corelib::core_array_ty(ctx.db.upcast(), element_ty)).intern(ctx.db);
+    let array = generators::StructDestructure {
+        input: span,
+        var_reqs: vec![VarRequest { ty: array_ty, location }],
+    }
+    .add(ctx, &mut builder.statements)[0];
+    match_span_ctx.remaining_arrays.push(array);
+    let mut leaves = vec![];
+    let match_info = lower_span_match_node(ctx, builder, arms, &mut match_span_ctx, &mut leaves)?;
+    let unreachable = match_span_ctx
+        .rows
+        .iter()
+        .enumerate()
+        .filter(|(row_index, _)| !match_span_ctx.reached_rows.contains(row_index))
+        .map(|(_, row)| ctx.function_body.arenas.patterns[row.pattern].stable_ptr().untyped())
+        .collect();
+    report_unreachable_arms(ctx, unreachable, MatchKind::Match);
+    if !match_span_ctx.missing_lengths.is_empty() {
+        // The subtree of the longer spans is lowered first.
+        match_span_ctx.missing_lengths.reverse();
+        report_non_exhaustive_match(
+            ctx,
+            location,
+            MatchDiagnostic::NonExhaustiveMatchSpanLength(match_span_ctx.missing_lengths),
+            MatchKind::Match,
+        )?;
+    }
+
+    let empty_match_info = MatchInfo::Extern(MatchExternInfo {
+        arms: vec![],
+        ..extract_matches!(&match_info, MatchInfo::Extern).clone()
+    });
+    let sealed_blocks =
+        group_match_arms(ctx, empty_match_info, location, arms, leaves, MatchKind::Match)?;
+    builder.merge_and_end_with_match(ctx, match_info, sealed_blocks, location)
+}
+
+/// Returns the rows of the arms of a match on a fixed size array or a span.
+fn get_span_match_rows(
+    ctx: &mut LoweringContext<'_, '_>,
+    arms: &[MatchArmWrapper],
+) -> LoweringResult<Vec<SpanMatchRow>> {
+    let mut rows = vec![];
+    for (arm_index, arm) in arms.iter().enumerate() {
+        for pattern_id in &arm.patterns {
+            let pattern = &ctx.function_body.arenas.patterns[*pattern_id];
+            let length = match pattern {
+                Pattern::FixedSizeArray(array_pattern) => {
+                    let n_elements = array_pattern.elements_patterns.len();
+                    match array_pattern.elements_patterns.last() {
+                        Some(last)
+                            if matches!(
+                                ctx.function_body.arenas.patterns[*last],
+                                Pattern::Rest(_)
+                            ) =>
+                        {
+                            SpanLength::AtLeast(n_elements - 1)
+                        }
+                        _ => SpanLength::Exact(n_elements),
+                    }
+                }
+                Pattern::Otherwise(_) | Pattern::Variable(_) => SpanLength::AtLeast(0),
+                _ => {
+                    return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+                        pattern.stable_ptr(),
+                        MatchError(MatchError {
+                            kind: MatchKind::Match,
+                            error: MatchDiagnostic::UnsupportedMatchArmNotAnArray,
+                        }),
+                    )));
+                }
+            };
+            rows.push(SpanMatchRow { arm_index, pattern: *pattern_id, length });
+        }
+    }
+    Ok(rows)
+}
+
+/// Lowers a match on a fixed size array or a span whose `row` is always taken, binding its
+/// pattern to `expr`, and reports all the other rows as unreachable.
+fn lower_span_match_single_row(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    arms: &[MatchArmWrapper],
+    rows: &[SpanMatchRow],
+    row: &SpanMatchRow,
+    expr: LoweredExpr,
+) -> LoweringResult<LoweredExpr> {
+    let unreachable = rows
+        .iter()
+        .filter(|other| other.pattern != row.pattern)
+        .map(|other| ctx.function_body.arenas.patterns[other.pattern].stable_ptr().untyped())
+        .collect();
+    report_unreachable_arms(ctx, unreachable, MatchKind::Match);
+    lower_single_leaf_match(ctx, builder, arms, row.arm_index, row.pattern, expr)
+}
+
+/// Returns the step of the decision tree of a match on a span at the end of the current path, on
+/// which the elements in `boxed_elements` were popped. If `exhausted`, the span has no more
+/// elements.
+fn next_span_match_step(
+    match_span_ctx: &LoweringMatchSpanContext,
+    exhausted: bool,
+) -> SpanMatchStep {
+    let n_popped = match_span_ctx.boxed_elements.len();
+    // The first row matching some of the lengths the span may still have.
+    let first_row = match_span_ctx.rows.iter().position(|row| match row.length {
+        SpanLength::Exact(n_elements) if exhausted => n_elements == n_popped,
+        SpanLength::Exact(n_elements) => n_elements >= n_popped,
+        SpanLength::AtLeast(n_elements) => n_elements <= n_popped || !exhausted,
+    });
+    match first_row {
+        None => SpanMatchStep::Missing,
+        Some(row_index) => match match_span_ctx.rows[row_index].length {
+            SpanLength::AtLeast(n_elements) if n_elements <= n_popped => {
+                SpanMatchStep::Leaf(row_index)
+            }
+            _ if exhausted => SpanMatchStep::Leaf(row_index),
+            _ => SpanMatchStep::Pop,
+        },
+    }
+}
+
+/// Lowers a node of the decision tree of a match on a span at the end of `builder`, popping the
+/// next element of the span. Returns the info of the match ending `builder`.
+fn lower_span_match_node(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &BlockBuilder,
+    arms: &[MatchArmWrapper],
+    match_span_ctx: &mut LoweringMatchSpanContext,
+    leaves: &mut Vec<MatchLeafBuilder>,
+) -> LoweringResult<MatchInfo> {
+    let semantic_db = ctx.db.upcast();
+    let location = match_span_ctx.match_location;
+    let element_ty = match_span_ctx.element_ty;
+    let array = *match_span_ctx.remaining_arrays.last().unwrap();
+    let array_ty = ctx.variables[array].ty;
+    let boxed_ty =
+        corelib::core_box_ty(semantic_db, TypeLongId::Snapshot(element_ty).intern(ctx.db));
+
+    let some_subscope = create_subscope(ctx, builder);
+    let some_block_id = some_subscope.block_id;
+    let some_array = ctx.new_var(VarRequest { ty: array_ty, location });
+    let boxed_element = ctx.new_var(VarRequest { ty: boxed_ty, location });
+    match_span_ctx.remaining_arrays.push(some_array);
+    match_span_ctx.boxed_elements.push(boxed_element);
+    let some_result =
+        lower_span_match_subtree(ctx, some_subscope, arms, match_span_ctx, false, leaves);
+    match_span_ctx.remaining_arrays.pop();
+    match_span_ctx.boxed_elements.pop();
+    some_result?;
+
+    let none_subscope = create_subscope(ctx, builder);
+    let none_block_id = none_subscope.block_id;
+    let none_array = ctx.new_var(VarRequest { ty: array_ty, location });
+    lower_span_match_subtree(ctx, none_subscope, arms, match_span_ctx, true, leaves)?;
+
+    Ok(MatchInfo::Extern(MatchExternInfo {
+        function: corelib::core_array_snapshot_pop_front(semantic_db, element_ty).lowered(ctx.db),
+        inputs: vec![VarUsage { var_id: array, location }],
+        arms: vec![
+            MatchArm {
+                arm_selector: MatchArmSelector::VariantId(corelib::option_some_variant(
+                    semantic_db,
+                    boxed_ty,
+                )),
+                block_id: some_block_id,
+                var_ids: vec![some_array, boxed_element],
+            },
+            MatchArm {
+                arm_selector: MatchArmSelector::VariantId(corelib::option_none_variant(
+                    semantic_db,
+                    boxed_ty,
+                )),
+                block_id: none_block_id,
+                var_ids: vec![none_array],
+            },
+        ],
+        location,
+    }))
+}
+
+/// Lowers the subtree of the decision tree of a match on a span at the end of the current path,
+/// into `builder` - either another node, or a leaf of the first row matching the lengths the span
+/// may have on the path. If `exhausted`, the span has no more elements.
+fn lower_span_match_subtree(
+    ctx: &mut LoweringContext<'_, '_>,
+    mut builder: BlockBuilder,
+    arms: &[MatchArmWrapper],
+    match_span_ctx: &mut LoweringMatchSpanContext,
+    exhausted: bool,
+    leaves: &mut Vec<MatchLeafBuilder>,
+) -> LoweringResult<()> {
+    let row_index = match next_span_match_step(match_span_ctx, exhausted) {
+        SpanMatchStep::Leaf(row_index) => row_index,
+        SpanMatchStep::Missing => {
+            let missing_length =
+                format_missing_span_length(match_span_ctx.boxed_elements.len(), exhausted);
+            match_span_ctx.missing_lengths.push(missing_length);
+            let arm_index = arms.len() + leaves.len();
+            leaves.push(lower_uncovered_leaf(
+                ctx,
+                builder,
+                match_span_ctx.match_location,
+                arm_index,
+            ));
+            return Ok(());
+        }
+        SpanMatchStep::Pop => {
+            let match_info = lower_span_match_node(ctx, &builder, arms, match_span_ctx, leaves)?;
+            builder.finalize(ctx, FlatBlockEnd::Match { info: match_info });
+            return Ok(());
+        }
+    };
+    match_span_ctx.reached_rows.insert(row_index);
+    let lowering_result = bind_span_match_row(ctx, &mut builder, match_span_ctx, row_index);
+    leaves.push(MatchLeafBuilder {
+        arm_index: match_span_ctx.rows[row_index].arm_index,
+        lowering_result,
+        builder,
+    });
+    Ok(())
+}
+
+/// Binds the pattern of the row at `row_index` at a leaf of the decision tree of a match on a
+/// span: each element pattern to the corresponding popped element, and a rest pattern to the
+/// remaining elements of the span.
+fn bind_span_match_row(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    match_span_ctx: &LoweringMatchSpanContext,
+    row_index: usize,
+) -> LoweringResult<()> {
+    let pattern = ctx.function_body.arenas.patterns[match_span_ctx.rows[row_index].pattern].clone();
+    let Pattern::FixedSizeArray(array_pattern) = pattern else {
+        return lower_single_pattern(
+            ctx,
+            builder,
+            pattern,
+            LoweredExpr::AtVariable(match_span_ctx.span),
+        );
+    };
+    let element_ty = TypeLongId::Snapshot(match_span_ctx.element_ty).intern(ctx.db);
+    for (element_index, element_pattern) in array_pattern.elements_patterns.iter().enumerate() {
+        let element_pattern = ctx.function_body.arenas.patterns[*element_pattern].clone();
+        let location = ctx.get_location(element_pattern.stable_ptr().untyped());
+        let element = match &element_pattern {
+            Pattern::Otherwise(_) => continue,
+            Pattern::Rest(rest_pattern) => {
+                let Some(binding) = rest_pattern.binding.clone() else {
+                    continue;
+                };
+                let array = match_span_ctx.remaining_arrays[element_index];
+                let rest = generators::StructConstruct {
+                    inputs: vec![VarUsage { var_id: array, location }],
+                    ty: rest_pattern.ty,
+                    location,
+                }
+                .add(ctx, &mut builder.statements);
+                lower_single_pattern(
+                    ctx,
+                    builder,
+                    Pattern::Variable(binding),
+                    LoweredExpr::AtVariable(rest),
+                )?;
+                continue;
+            }
+            _ => {
+                generators::Call {
+                    function: corelib::core_unbox(ctx.db.upcast(), element_ty).lowered(ctx.db),
+                    inputs: vec![VarUsage {
+                        var_id: match_span_ctx.boxed_elements[element_index],
+                        location,
+                    }],
+                    coupon_input: None,
+                    extra_ret_tys: vec![],
+                    ret_tys: vec![element_ty],
+                    location,
+                }
+                .add(ctx, &mut builder.statements)
+                .returns[0]
+            }
+        };
+        lower_single_pattern(ctx, builder, element_pattern, LoweredExpr::AtVariable(element))?;
+    }
+    Ok(())
+}
+
+/// Formats the lengths of a span with `n_popped` elements popped on a path of the decision tree
+/// of a match on it, which is not covered by any arm, as a span pattern. If `exhausted`, the span
+/// has no more elements.
+fn format_missing_span_length(n_popped: usize, exhausted: bool) -> String {
+    let mut elements = vec!["_"; n_popped];
+    if !exhausted {
+        elements.push("..");
+    }
+    format!("[{}]", elements.join(", "))
+}
+
+/// Lowers an expression of type [semantic::ExprMatch] where the matched expression is a tuple of
+/// felt252s and integers, and the arms are tuples of literals.
+/// The patterns are lowered into a decision tree, comparing one position of the tuple at a time to
+/// the literals of the patterns which may still match. The match must have a wildcard arm.
+fn lower_expr_match_tuple_literals(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    expr: LoweredExpr,
+    match_expr: &semantic::ExprMatch,
+    tuple_info: &TupleInfo,
+) -> LoweringResult<LoweredExpr> {
+    let _span = match_lowering_span!(
+        "lower_expr_match_tuple_literals",
+        n_arms = match_expr.arms.len(),
+        n_positions = tuple_info.types.len(),
+    );
+    let location = ctx.get_location(match_expr.stable_ptr.untyped());
+    let felt252_ty = ctx.db.core_info().felt252;
+    let match_inputs = destructure_matched_tuple(ctx, builder, expr, tuple_info)?
+        .into_iter()
+        .zip(&tuple_info.types)
+        .map(|(input, ty)| {
+            let Some(convert_function) =
+                corelib::get_convert_to_felt252_libfunc_name_by_type(ctx.db.upcast(), *ty)
+            else {
+                return input;
+            };
+            let call_result = generators::Call {
+                function: convert_function.lowered(ctx.db),
+                inputs: vec![input],
+                coupon_input: None,
+                extra_ret_tys: vec![],
+                ret_tys: vec![felt252_ty],
+                location,
+            }
+            .add(ctx, &mut builder.statements);
+            call_result.returns.into_iter().next().unwrap()
+        })
+        .collect_vec();
+
+    let arms = match_expr.arms.iter().map(|arm| arm.into()).collect_vec();
+    let otherwise_variant = get_underscore_pattern_path(ctx, &arms, MatchKind::Match, false);
+    let mut rows = vec![];
+    for (arm_index, arm) in arms.iter().enumerate().take(
+        otherwise_variant
+            .as_ref()
+            .map(|PatternPath { arm_index, .. }| *arm_index)
+            .unwrap_or(arms.len()),
+    ) {
+        for (pattern_index, pattern) in arm.patterns.iter().enumerate() {
+            let pattern = ctx.function_body.arenas.patterns[*pattern].clone();
+            let Pattern::Tuple(tuple_pattern) = pattern else {
+                return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+                    pattern.stable_ptr().untyped(),
+                    MatchError(MatchError {
+                        kind: MatchKind::Match,
+                        error: MatchDiagnostic::UnsupportedMatchArmNotATuple,
+                    }),
+                )));
+            };
+            let literals = tuple_pattern
+                .field_patterns
+                .iter()
+                .map(|pattern| match &ctx.function_body.arenas.patterns[*pattern] {
+                    Pattern::Literal(semantic::PatternLiteral { literal, .. }) => {
+                        Ok(Some(literal.clone()))
+                    }
+                    Pattern::Otherwise(_) => Ok(None),
+                    pattern => Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+                        pattern.stable_ptr().untyped(),
+                        MatchError(MatchError {
+                            kind: MatchKind::Match,
+                            error: MatchDiagnostic::UnsupportedMatchArmNotALiteral,
+                        }),
+                    ))),
+                })
+                .collect::<LoweringResult<Vec<_>>>()?;
+            rows.push(TupleLiteralsRow {
+                pattern_path: PatternPath { arm_index, pattern_index: Some(pattern_index) },
+                literals,
+            });
+        }
+    }
+    if let Some(pattern_path) = otherwise_variant {
+        rows.push(TupleLiteralsRow { pattern_path, literals: vec![None; tuple_info.types.len()] });
+    }
+    if !rows.iter().any(|row| row.literals.iter().all(Option::is_none)) {
+        report_non_exhaustive_match(
+            ctx,
+            non_exhaustive_numeric_match_location(ctx, match_expr),
+            MatchDiagnostic::NonExhaustiveMatchFelt252(wildcard_arm_suggestion(ctx, match_expr)),
+            MatchKind::Match,
+        )?;
+    }
+
+    let mut match_tuple_ctx = LoweringMatchTupleLiteralsContext {
+        match_location: location,
+        match_inputs,
+        reached_rows: vec![false; rows.len()],
+        rows,
+        n_arms: arms.len(),
+    };
+    let mut candidates = (0..match_tuple_ctx.rows.len()).collect_vec();
+    let Some(index) = next_tuple_literals_position(&match_tuple_ctx, 0, &mut candidates) else {
+        // No position is compared to a literal, so the first pattern is always selected.
+        return match candidates.first() {
+            Some(row) => {
+                let arm_index = match_tuple_ctx.rows[*row].pattern_path.arm_index;
+                let expr = transform_arm_expr(ctx, match_expr.arms[arm_index].expression);
+                in_match_arm(ctx, arm_index, |ctx| lower_expr(ctx, builder, expr))
+            }
+            None => Err(lower_uncovered_value_panic(ctx, builder, location)),
+        };
+    };
+    let mut arms_vec = vec![];
+    let match_info = lower_tuple_literals_tree(
+        ctx,
+        builder,
+        &mut match_tuple_ctx,
+        index,
+        &candidates,
+        &mut arms_vec,
+    )?;
+    for (row, reached) in zip_eq(&match_tuple_ctx.rows, &match_tuple_ctx.reached_rows) {
+        if let (false, Some(pattern_index)) = (reached, row.pattern_path.pattern_index) {
+            let pattern = arms[row.pattern_path.arm_index].patterns[pattern_index];
+            report_unreachable_match_arm(
+                ctx,
+                ctx.function_body.arenas.patterns[pattern].stable_ptr().untyped(),
+                MatchKind::Match,
+            );
+        }
+    }
+
+    let empty_match_info = MatchInfo::Extern(MatchExternInfo {
+        function: corelib::core_felt252_is_zero(ctx.db.upcast()).lowered(ctx.db),
+        inputs: vec![match_tuple_ctx.match_inputs[0]],
+        arms: vec![],
+        location,
+    });
+    let sealed_blocks =
+        group_match_arms(ctx, empty_match_info, location, &arms, arms_vec, MatchKind::Match)?;
+    builder.merge_and_end_with_match(ctx, match_info, sealed_blocks, location)
+}
+
+/// Returns the first position, starting at `index`, which is compared to a literal by any of the
+/// candidate rows, or `None` if the first candidate row is selected regardless of the values of the
+/// remaining positions.
+/// Removes the candidate rows that can no longer be selected.
+fn next_tuple_literals_position(
+    match_tuple_ctx: &LoweringMatchTupleLiteralsContext,
+    index: usize,
+    candidates: &mut Vec<usize>,
+) -> Option<usize> {
+    let has_literal_from_index =
+        |row: usize| match_tuple_ctx.rows[row].literals[index..].iter().any(Option::is_some);
+    if let Some(position) = candidates.iter().position(|row| !has_literal_from_index(*row)) {
+        candidates.truncate(position + 1);
+    }
+    (index..match_tuple_ctx.match_inputs.len()).find(|position| {
+        candidates.iter().any(|row| match_tuple_ctx.rows[*row].literals[*position].is_some())
+    })
+}
+
+/// Lowers the decision tree of a match on a tuple of numeric values from position `index`, into a
+/// chain of comparisons of the position to the literals of the candidate rows.
+fn lower_tuple_literals_tree(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    match_tuple_ctx: &mut LoweringMatchTupleLiteralsContext,
+    index: usize,
+    candidates: &[usize],
+    leaves_builders: &mut Vec<MatchLeafBuilder>,
+) -> LoweringResult<MatchInfo> {
+    let literals = candidates
+        .iter()
+        .filter_map(|row| match_tuple_ctx.rows[*row].literals[index].clone())
+        .unique_by(|literal| literal.value.clone())
+        .collect_vec();
+    lower_tuple_literals_chain(
+        ctx,
+        builder,
+        match_tuple_ctx,
+        index,
+        candidates,
+        &literals,
+        leaves_builders,
+    )
+}
+
+/// Lowers a comparison of position `index` of the matched tuple to the first of the given literals,
+/// comparing it to the rest of them if it is not equal.
+fn lower_tuple_literals_chain(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    match_tuple_ctx: &mut LoweringMatchTupleLiteralsContext,
+    index: usize,
+    candidates: &[usize],
+    literals: &[semantic::ExprLiteral],
+    leaves_builders: &mut Vec<MatchLeafBuilder>,
+) -> LoweringResult<MatchInfo> {
+    let location = match_tuple_ctx.match_location;
+    let main_block = create_subscope(ctx, builder);
+    let main_block_id = main_block.block_id;
+    let mut else_block = create_subscope(ctx, builder);
+    let else_block_id = else_block.block_id;
+
+    let (literal, other_literals) = literals.split_first().unwrap();
+    let if_input = lower_felt252_diff(
+        ctx,
+        builder,
+        match_tuple_ctx.match_inputs[index],
+        None,
+        literal,
+        location,
+    )?;
+    let match_info =
+        felt252_is_zero_match_info(ctx, if_input, main_block_id, else_block_id, location);
+
+    let literal_candidates = candidates
+        .iter()
+        .copied()
+        .filter(|row| {
+            match_tuple_ctx.rows[*row].literals[index]
+                .as_ref()
+                .is_none_or(|row_literal| row_literal.value == literal.value)
+        })
+        .collect_vec();
+    lower_tuple_literals_subtree(
+        ctx,
+        main_block,
+        match_tuple_ctx,
+        index + 1,
+        literal_candidates,
+        leaves_builders,
+    )?;
+    if other_literals.is_empty() {
+        let wildcard_candidates = candidates
+            .iter()
+            .copied()
+            .filter(|row| match_tuple_ctx.rows[*row].literals[index].is_none())
+            .collect_vec();
+        lower_tuple_literals_subtree(
+            ctx,
+            else_block,
+            match_tuple_ctx,
+            index + 1,
+            wildcard_candidates,
+            leaves_builders,
+        )?;
+    } else {
+        let else_match_info = lower_tuple_literals_chain(
+            ctx,
+            &mut else_block,
+            match_tuple_ctx,
+            index,
+            candidates,
+            other_literals,
+            leaves_builders,
+        )?;
+        // The else block is an inner block of the match expression, with no sibling block it goes
+        // to, so it can be finalized.
+        else_block.finalize(ctx, FlatBlockEnd::Match { info: else_match_info });
+    }
+    Ok(match_info)
+}
+
+/// Lowers the decision tree of a match on a tuple of numeric values from position `index` into
+/// `builder` - either as a leaf of the selected arm, or as an inner block of the tree.
+fn lower_tuple_literals_subtree(
+    ctx: &mut LoweringContext<'_, '_>,
+    mut builder: BlockBuilder,
+    match_tuple_ctx: &mut LoweringMatchTupleLiteralsContext,
+    index: usize,
+    mut candidates: Vec<usize>,
+    leaves_builders: &mut Vec<MatchLeafBuilder>,
+) -> LoweringResult<()> {
+    if let Some(index) = next_tuple_literals_position(match_tuple_ctx, index, &mut candidates) {
+        let match_info = lower_tuple_literals_tree(
+            ctx,
+            &mut builder,
+            match_tuple_ctx,
+            index,
+            &candidates,
+            leaves_builders,
+        )?;
+        builder.finalize(ctx, FlatBlockEnd::Match { info: match_info });
+        return Ok(());
+    }
+    let Some(row) = candidates.first() else {
+        // Non-exhaustiveness was already reported, and must be a warning if lowering got here.
+        let arm_index = match_tuple_ctx.n_arms + leaves_builders.len();
+        leaves_builders.push(lower_uncovered_leaf(
+            ctx,
+            builder,
+            match_tuple_ctx.match_location,
+            arm_index,
+        ));
+        return Ok(());
+    };
+    match_tuple_ctx.reached_rows[*row] = true;
+    leaves_builders.push(MatchLeafBuilder {
+        arm_index: match_tuple_ctx.rows[*row].pattern_path.arm_index,
+        lowering_result: Ok(()),
+        builder,
+    });
+    Ok(())
+}
+
+/// Returns the equivalent call to `downcast` if `expr` is a `try_into` call using the
+/// `DowncastableIntTryInto` impl, so that matching on it doesn't construct an `Option`.
+fn try_as_downcast_call(
+    ctx: &LoweringContext<'_, '_>,
+    expr: &semantic::Expr,
+) -> Option<semantic::ExprFunctionCall> {
+    let semantic::Expr::FunctionCall(call) = expr else {
+        return None;
+    };
+    let semantic_db = ctx.db.upcast();
+    let GenericFunctionId::Impl(ImplGenericFunctionId { impl_id, .. }) =
+        call.function.get_concrete(semantic_db).generic_function
+    else {
+        return None;
+    };
+    let ImplLongId::Concrete(concrete_impl_id) = impl_id.lookup_intern(ctx.db) else {
+        return None;
+    };
+    if concrete_impl_id.impl_def_id(semantic_db)
+        != corelib::core_downcastable_int_try_into_impl(semantic_db)
+    {
+        return None;
+    }
+    let [GenericArgumentId::Type(from_ty), GenericArgumentId::Type(to_ty)] =
+        impl_id.concrete_trait(semantic_db).ok()?.generic_args(semantic_db)[..]
+    else {
+        return None;
+    };
+    Some(semantic::ExprFunctionCall {
+        function: corelib::core_downcast(semantic_db, from_ty, to_ty),
+        ..call.clone()
+    })
+}
+
+/// If `expr` is a match on an enum variable whose last arm is a catch-all matching the same
+/// variable again, either directly (`_ => match x {..}`) or through its binding
+/// (`other => match other {..}`), returns a single match with the arms of both, so the value is
+/// dispatched on only once.
+///
+/// The patterns of the inner match covering variants handled by the outer arms are dropped, as
+/// they are never reached.
+fn try_fuse_otherwise_rematch(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+) -> Option<semantic::ExprMatch> {
+    let function_body = ctx.function_body.clone();
+    let arenas = &function_body.arenas;
+    let semantic::Expr::Var(matched_var) = &arenas.exprs[expr.matched_expr] else {
+        return None;
+    };
+    let (otherwise_arm, outer_arms) = expr.arms.split_last()?;
+    let [otherwise_pattern] = otherwise_arm.patterns[..] else {
+        return None;
+    };
+    let binding = match &arenas.patterns[otherwise_pattern] {
+        Pattern::Otherwise(_) => None,
+        Pattern::Variable(pattern) => Some(semantic::VarId::Local(pattern.var.id)),
+        _ => return None,
+    };
+    let inner_expr = match &arenas.exprs[otherwise_arm.expression] {
+        semantic::Expr::Block(block) if block.statements.is_empty() => &arenas.exprs[block.tail?],
+        inner_expr => inner_expr,
+    };
+    let semantic::Expr::Match(inner_expr) = inner_expr else {
+        return None;
+    };
+    let semantic::Expr::Var(inner_var) = &arenas.exprs[inner_expr.matched_expr] else {
+        return None;
+    };
+    if inner_var.var != binding.unwrap_or(matched_var.var) {
+        return None;
+    }
+    if let Some(binding) = binding {
+        // The binding is not introduced by the fused match, so it may only be used as the
+        // matched expression of the inner match.
+        let uses_binding = |expr_id| {
+            let usage = Usages::scope_expr_usage(arenas, expr_id);
+            chain!(usage.usage.keys(), usage.snap_usage.keys(), usage.changes.keys())
+                .any(|path| path.base_var() == binding)
+        };
+        if inner_expr
+            .arms
+            .iter()
+            .any(|arm| uses_binding(arm.expression) || arm.guard.is_some_and(uses_binding))
+        {
+            return None;
+        }
+    } else {
+        // The matched variable is used by both matches, which is only valid if it is copyable.
+        let type_info =
+            ctx.db.type_info(ctx.variables.lookup_context.clone(), matched_var.ty).ok()?;
+        type_info.copyable.as_ref().ok()?;
+    }
+    let mut covered_variants = vec![];
+    for pattern in outer_arms.iter().flat_map(|arm| &arm.patterns) {
+        match &arenas.patterns[*pattern] {
+            Pattern::EnumVariant(PatternEnumVariant { variant, .. }) => {
+                covered_variants.push(variant.clone())
+            }
+            _ => return None,
+        }
+    }
+    let mut inner_arms = vec![];
+    for arm in &inner_expr.arms {
+        let mut patterns = vec![];
+        for pattern in &arm.patterns {
+            match &arenas.patterns[*pattern] {
+                Pattern::EnumVariant(PatternEnumVariant { variant, .. })
+                    if covered_variants.contains(variant) => {}
+                Pattern::EnumVariant(_) | Pattern::Otherwise(_) => patterns.push(*pattern),
+                _ => return None,
+            }
+        }
+        if !patterns.is_empty() {
+            inner_arms.push(semantic::MatchArm {
+                patterns,
+                guard: arm.guard,
+                expression: arm.expression,
+            });
+        }
+    }
+    Some(semantic::ExprMatch {
+        matched_expr: expr.matched_expr,
+        arms: outer_arms.iter().cloned().chain(inner_arms).collect(),
+        ty: expr.ty,
+        stable_ptr: expr.stable_ptr,
+    })
+}
+
+/// If `expr` is a match on a struct with a single member of an enum type, whose arms destructure
+/// the struct and match the inner enum, returns the equivalent match on the member - so its
+/// exhaustiveness is checked on the inner enum.
+fn try_unwrap_enum_wrapper_match(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+) -> Option<semantic::ExprMatch> {
+    let matched_ty = ctx.function_body.arenas.exprs[expr.matched_expr].ty();
+    let (n_snapshots, long_ty) = peel_snapshots(ctx.db.upcast(), matched_ty);
+    let TypeLongId::Concrete(ConcreteTypeId::Struct(concrete_struct_id)) = long_ty else {
+        return None;
+    };
+    let members = ctx.db.concrete_struct_members(concrete_struct_id).ok()?;
+    let [member] = members.values().collect_vec()[..] else {
+        return None;
+    };
+    if !matches!(
+        peel_snapshots(ctx.db.upcast(), member.ty).1,
+        TypeLongId::Concrete(ConcreteTypeId::Enum(_))
+    ) {
+        return None;
+    }
+    let arenas = &ctx.function_body.arenas;
+    let arms = expr
+        .arms
+        .iter()
+        .map(|arm| {
+            let patterns = arm
+                .patterns
+                .iter()
+                .map(|pattern| match &arenas.patterns[*pattern] {
+                    Pattern::Otherwise(_) => Some(*pattern),
+                    Pattern::Struct(pattern) => match pattern.field_patterns[..] {
+                        [(_, field_pattern)] => Some(field_pattern),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(semantic::MatchArm { patterns, guard: arm.guard, expression: arm.expression })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let member_access = semantic::Expr::MemberAccess(semantic::ExprMemberAccess {
+        expr: expr.matched_expr,
+        concrete_struct_id,
+        member: member.id,
+        ty: wrap_in_snapshots(ctx.db.upcast(), member.ty, n_snapshots),
+        member_path: None,
+        n_snapshots,
+        stable_ptr: ctx.function_body.arenas.exprs[expr.matched_expr].stable_ptr(),
+    });
+    let matched_expr = Arc::make_mut(&mut ctx.function_body).arenas.exprs.alloc(member_access);
+    Some(semantic::ExprMatch { matched_expr, arms, ty: expr.ty, stable_ptr: expr.stable_ptr })
+}
+
+/// If `expr` is a match on a `bool` selecting between two `felt252` values without side effects,
+/// i.e. literals or variables, returns the values of the `true` and `false` arms, to be lowered by
+/// [lower_branchless_select]. Only done if enabled by the `branchless_select` flag.
+fn try_as_branchless_select(
+    ctx: &LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+) -> Option<(semantic::ExprId, semantic::ExprId)> {
+    require(branchless_select(ctx) && ctx.match_arm_transform.is_none())?;
+    let semantic_db = ctx.db.upcast();
+    let arenas = &ctx.function_body.arenas;
+    require(arenas.exprs[expr.matched_expr].ty() == corelib::core_bool_ty(semantic_db))?;
+    let felt252_ty = ctx.db.core_info().felt252;
+    require(expr.ty == felt252_ty)?;
+    let [first_arm, second_arm] = &expr.arms[..] else {
+        return None;
+    };
+    // The variant of the arm (`None` for a catch-all) and the value it evaluates to.
+    let arm_info = |arm: &semantic::MatchArm| {
+        let [pattern] = arm.patterns[..] else {
+            return None;
+        };
+        let variant = match &arenas.patterns[pattern] {
+            Pattern::EnumVariant(PatternEnumVariant { variant, .. }) => Some(variant.clone()),
+            Pattern::Otherwise(_) => None,
+            _ => return None,
+        };
+        let value = &arenas.exprs[arm.expression];
+        require(matches!(value, semantic::Expr::Literal(_) | semantic::Expr::Var(_)))?;
+        require(value.ty() == felt252_ty)?;
+        Some((variant, arm.expression))
+    };
+    let (Some(first_variant), first_value) = arm_info(first_arm)? else {
+        return None;
+    };
+    let (second_variant, second_value) = arm_info(second_arm)?;
+    require(second_variant.is_none_or(|variant| variant != first_variant))?;
+    if first_variant == corelib::true_variant(semantic_db) {
+        Some((first_value, second_value))
+    } else {
+        Some((second_value, first_value))
+    }
+}
+
+/// Lowers a match selecting between `true_value` and `false_value` by the matched `bool`, into
+/// `false_value + bool_to_felt252(matched) * (true_value - false_value)`. See
+/// [try_as_branchless_select].
+fn lower_branchless_select(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    expr: &semantic::ExprMatch,
+    true_value: semantic::ExprId,
+    false_value: semantic::ExprId,
+) -> LoweringResult<LoweredExpr> {
+    let location = ctx.get_location(expr.stable_ptr.untyped());
+    let condition = lower_expr_to_var_usage(ctx, builder, expr.matched_expr)?;
+    let true_value = lower_expr_to_var_usage(ctx, builder, true_value)?;
+    let false_value = lower_expr_to_var_usage(ctx, builder, false_value)?;
+    let selector =
+        add_felt252_libfunc_call(ctx, builder, "bool_to_felt252", vec![condition], location);
+    let diff = add_felt252_libfunc_call(
+        ctx,
+        builder,
+        "felt252_sub",
+        vec![true_value, false_value],
+        location,
+    );
+    let offset =
+        add_felt252_libfunc_call(ctx, builder, "felt252_mul", vec![selector, diff], location);
+    let result =
+        add_felt252_libfunc_call(ctx, builder, "felt252_add", vec![false_value, offset], location);
+    Ok(LoweredExpr::AtVariable(result))
+}
+
+/// Adds a call to the corelib libfunc with the given name, returning a `felt252`, to the builder.
+fn add_felt252_libfunc_call(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    name: &str,
+    inputs: Vec<VarUsage>,
+    location: LocationId,
+) -> VarUsage {
+    generators::Call {
+        function: corelib::get_core_function_id(ctx.db.upcast(), name.into(), vec![])
+            .lowered(ctx.db),
+        inputs,
+        coupon_input: None,
+        extra_ret_tys: vec![],
+        ret_tys: vec![ctx.db.core_info().felt252],
+        location,
+    }
+    .add(ctx, &mut builder.statements)
+    .returns
+    .into_iter()
+    .next()
+    .unwrap()
+}
+
+/// Lowers an expression of type [semantic::ExprMatch].
+pub(crate) fn lower_expr_match(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+    builder: &mut BlockBuilder,
+) -> LoweringResult<LoweredExpr> {
+    log::trace!("Lowering a match expression: {:?}", expr.debug(&ctx.expr_formatter));
+    let _span = match_lowering_span!("lower_expr_match", n_arms = expr.arms.len());
+    if expr.arms.iter().any(|arm| arm.guard.is_some()) {
+        return lower_expr_match_with_guards(ctx, expr, builder);
+    }
+    if let Some(fused_expr) = try_fuse_otherwise_rematch(ctx, expr) {
+        return lower_expr_match(ctx, &fused_expr, builder);
+    }
+    if let Some(inner_expr) = try_unwrap_enum_wrapper_match(ctx, expr) {
+        return lower_expr_match(ctx, &inner_expr, builder);
+    }
+    report_single_variant_match_chain(ctx, expr);
+    if let Some((true_value, false_value)) = try_as_branchless_select(ctx, expr) {
+        return lower_branchless_select(ctx, builder, expr, true_value, false_value);
+    }
+    let location = ctx.get_location(expr.stable_ptr.untyped());
+    let matched_expr = ctx.function_body.arenas.exprs[expr.matched_expr].clone();
+    let lowered_expr = match try_as_downcast_call(ctx, &matched_expr) {
+        // Lowered as an extern enum, so that the downcast directly drives the match arms.
+        Some(downcast_call) => lower_expr_function_call(ctx, &downcast_call, builder)?,
+        None => lower_expr(ctx, builder, expr.matched_expr)?,
+    };
+    let ty = matched_expr.ty();
+
+    if ty == ctx.db.core_info().felt252 {
+        report_numeric_match_on_enum_conversion(ctx, &matched_expr);
+        let match_input = lowered_expr.as_var_usage(ctx, builder)?;
+        return lower_expr_match_felt252(ctx, expr, match_input, builder, None, None);
+    }
+    if let Some(convert_function) =
+        corelib::get_convert_to_felt252_libfunc_name_by_type(ctx.db.upcast(), ty)
+    {
+        report_numeric_match_on_enum_conversion(ctx, &matched_expr);
+        let match_input = lowered_expr.as_var_usage(ctx, builder)?;
+        return lower_expr_match_felt252(
+            ctx,
+            expr,
+            match_input,
+            builder,
+            Some(convert_function),
+            None,
+        );
+    }
+    if let Some(bounded_int_range) =
+        corelib::try_extract_bounded_int_type_ranges(ctx.db.upcast(), ty)
+    {
+        let match_input = lowered_expr.as_var_usage(ctx, builder)?;
+        return lower_expr_match_felt252(
+            ctx,
+            expr,
+            match_input,
+            builder,
+            None,
+            Some(bounded_int_range),
+        );
+    }
+
+    let (n_snapshots, long_type_id) = peel_snapshots(ctx.db.upcast(), ty);
+    if n_snapshots > 0 {
+        report_redundant_snapshot(ctx, &matched_expr, &expr.arms);
+    }
+    if let TypeLongId::Concrete(ConcreteTypeId::Enum(concrete_enum_id)) = &long_type_id {
+        report_forbidden_catch_all(ctx, *concrete_enum_id, &expr.arms);
+        report_match_coverage_mismatch(ctx, expr, *concrete_enum_id);
+        report_match_coverage_regression(ctx, expr);
+    }
+
+    let is_bool_match = long_type_id
+        == TypeLongId::Concrete(ConcreteTypeId::Enum(corelib::core_bool_enum(ctx.db.upcast())));
+
+    let arms = expr.arms.iter().map(|arm| arm.into()).collect_vec();
+    // A match on a struct is only lowered by its members if it is destructured by some arm, as
+    // structs such as `u256` may be matched by other patterns.
+    if let TypeLongId::Concrete(ConcreteTypeId::Struct(concrete_struct_id)) = long_type_id {
+        if has_struct_pattern_arm(ctx, &arms) {
+            ctx.match_strategies.record(MatchStrategy::StructTree);
+            return lower_expr_match_struct(
+                ctx,
+                builder,
+                lowered_expr,
+                concrete_struct_id,
+                n_snapshots,
+                &arms,
+            );
+        }
+    }
+
+    if matches!(long_type_id, TypeLongId::FixedSizeArray { .. })
+        || (n_snapshots == 0
+            && corelib::try_extract_span_element_type(ctx.db.upcast(), ty).is_some())
+    {
+        ctx.match_strategies.record(MatchStrategy::ArrayTree);
+        return lower_expr_match_array(ctx, builder, lowered_expr, ty, &arms);
+    }
+
+    if let Some(types) = try_extract_matches!(long_type_id, TypeLongId::Tuple) {
+        let tuple_info = TupleInfo { n_snapshots, types };
+        ctx.match_strategies.record(MatchStrategy::TupleTree);
+        if n_snapshots == 0 && is_tuple_of_numeric_types(ctx, &tuple_info.types) {
+            return lower_expr_match_tuple_literals(ctx, builder, lowered_expr, expr, &tuple_info);
+        }
+        return lower_expr_match_tuple(
+            ctx,
+            builder,
+            lowered_expr,
+            &matched_expr,
+            &tuple_info,
+            &arms,
+            MatchKind::Match,
+        );
+    }
+
+    // TODO(spapini): Use diagnostics.
+    // TODO(spapini): Handle more than just enums.
+    let lowered_expr = match try_extract_extern_enum(lowered_expr) {
+        Ok((extern_enum, n_snapshots)) => {
+            ctx.match_strategies.record(MatchStrategy::Extern);
+            ctx.optimized_extern_matches.insert(expr.stable_ptr, true);
+            return lower_optimized_extern_match(
+                ctx,
+                builder,
+                extern_enum,
+                n_snapshots,
+                &arms,
+                MatchKind::Match,
+            );
+        }
+        Err(lowered_expr) => lowered_expr,
+    };
+    ctx.match_strategies.record(MatchStrategy::Enum);
+    ctx.optimized_extern_matches.insert(expr.stable_ptr, false);
+    if is_bool_match {
+        return lower_expr_match_bool(ctx, builder, lowered_expr, &arms, n_snapshots, location);
+    }
+    lower_concrete_enum_match(
+        ctx,
+        builder,
+        &matched_expr,
+        lowered_expr,
+        &arms,
+        location,
+        MatchKind::Match,
+    )
+}
+
+/// Lowers a match on a `bool`, or on a snapshot of one, in the canonical form of the lowering of
+/// an `if` expression: the block of `true` is created before the block of `false`, so that later
+/// optimizations handle both expressions alike.
+fn lower_expr_match_bool(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    lowered_expr: LoweredExpr,
+    arms: &[MatchArmWrapper],
+    n_snapshots: usize,
+    location: LocationId,
+) -> LoweringResult<LoweredExpr> {
+    let semantic_db = ctx.db.upcast();
+    let concrete_enum_id = corelib::core_bool_enum(semantic_db);
+    let false_variant = corelib::false_variant(semantic_db);
+    let true_variant = corelib::true_variant(semantic_db);
+    let unit_ty = wrap_in_snapshots(semantic_db, corelib::unit_ty(semantic_db), n_snapshots);
+    let match_input = lowered_expr.as_var_usage(ctx, builder)?;
+
+    let otherwise_variant =
+        get_underscore_pattern_path(ctx, arms, MatchKind::Match, n_snapshots == 0);
+    let variant_map = get_variant_to_arm_map(
+        ctx,
+        arms.iter().take(
+            otherwise_variant
+                .as_ref()
+                .map(|PatternPath { arm_index, .. }| *arm_index)
+                .unwrap_or(arms.len()),
+        ),
+        concrete_enum_id,
+        2,
+        MatchKind::Match,
+    )?;
+
+    report_missing_enum_arms(
+        ctx,
+        location,
+        concrete_enum_id,
+        [&false_variant, &true_variant]
+            .into_iter()
+            .filter(|variant| !variant_map.contains_key(*variant) && otherwise_variant.is_none()),
+        MatchKind::Match,
+    )?;
+
+    let mut leaves = vec![];
+    let mut match_arms = vec![];
+    for variant in [true_variant, false_variant] {
+        let mut subscope = create_subscope(ctx, builder);
+        let arm_path = variant_map.get(&variant).or(otherwise_variant.as_ref());
+        let var_location = match arm_path {
+            Some(path) => enum_variant_pattern_location(ctx, arms, path, location),
+            None => location,
+        };
+        let var_id = ctx.new_var(VarRequest { ty: unit_ty, location: var_location });
+        match_arms.push(MatchArm {
+            arm_selector: MatchArmSelector::VariantId(variant.clone()),
+            block_id: subscope.block_id,
+            var_ids: vec![var_id],
+        });
+        let Some(path) = arm_path else {
+            leaves.push(lower_uncovered_leaf(ctx, subscope, location, arms.len() + variant.idx));
+            continue;
+        };
+        let lowering_result = lower_enum_variant_pattern(
+            ctx,
+            &mut subscope,
+            arms,
+            path,
+            &variant,
+            var_id,
+            match_input,
+        );
+        leaves.push(MatchLeafBuilder {
+            arm_index: path.arm_index,
+            lowering_result,
+            builder: subscope,
+        });
+    }
+    // The arms of the match follow the order of the variants of the enum.
+    match_arms.reverse();
+
+    let empty_match_info = MatchInfo::Enum(MatchEnumInfo {
+        concrete_enum_id,
+        input: match_input,
+        arms: vec![],
+        location,
+    });
+    let sealed_blocks =
+        group_match_arms(ctx, empty_match_info, location, arms, leaves, MatchKind::Match)?;
+    let match_info = MatchInfo::Enum(MatchEnumInfo {
+        concrete_enum_id,
+        input: match_input,
+        arms: match_arms,
+        location,
+    });
+    builder.merge_and_end_with_match(ctx, match_info, sealed_blocks, location)
+}
+
+/// Lowers a match expression with guards on some of its arms.
+///
+/// Guards are only supported in matches on enums, where the arms of each variant are tried in
+/// order until one without a guard, or whose guard holds, is found.
+fn lower_expr_match_with_guards(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+    builder: &mut BlockBuilder,
+) -> LoweringResult<LoweredExpr> {
+    let location = ctx.get_location(expr.stable_ptr.untyped());
+    let matched_expr = ctx.function_body.arenas.exprs[expr.matched_expr].clone();
+    let TypeLongId::Concrete(ConcreteTypeId::Enum(concrete_enum_id)) =
+        peel_snapshots(ctx.db.upcast(), matched_expr.ty()).1
+    else {
+        let guard = expr.arms.iter().find_map(|arm| arm.guard).unwrap();
+        return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+            ctx.function_body.arenas.exprs[guard].stable_ptr().untyped(),
+            MatchError(MatchError {
+                kind: MatchKind::Match,
+                error: MatchDiagnostic::UnsupportedMatchArmGuard,
+            }),
+        )));
+    };
+    report_forbidden_catch_all(ctx, concrete_enum_id, &expr.arms);
+    report_constant_guards(ctx, &expr.arms);
+    // Extern enums are materialized, as the arms of a variant may need its payload several times.
+    let lowered_expr = lower_expr(ctx, builder, expr.matched_expr)?;
+    let arms = expr.arms.iter().map(|arm| arm.into()).collect_vec();
+    ctx.match_strategies.record(MatchStrategy::Enum);
+    ctx.optimized_extern_matches.insert(expr.stable_ptr, false);
+    lower_concrete_enum_match(
+        ctx,
+        builder,
+        &matched_expr,
+        lowered_expr,
+        &arms,
+        location,
+        MatchKind::Match,
+    )
+}
+
+/// Reports the catch-all patterns of `arms` if the matched enum is marked with `#[no_catch_all]`.
+fn report_forbidden_catch_all(
+    ctx: &mut LoweringContext<'_, '_>,
+    concrete_enum_id: semantic::ConcreteEnumId,
+    arms: &[semantic::MatchArm],
+) {
+    if !concrete_enum_id.has_attr(ctx.db.upcast(), NO_CATCH_ALL_ATTR).unwrap_or(false) {
+        return;
+    }
+    let enum_name = concrete_enum_id.enum_id(ctx.db.upcast()).name(ctx.db.upcast());
+    for pattern in arms.iter().flat_map(|arm| arm.patterns.iter()) {
+        let pattern = &ctx.function_body.arenas.patterns[*pattern];
+        if matches!(pattern, semantic::Pattern::Otherwise(_) | semantic::Pattern::Variable(_)) {
+            let stable_ptr = pattern.stable_ptr().untyped();
+            ctx.diagnostics.report(
+                stable_ptr,
+                MatchError(MatchError {
+                    kind: MatchKind::Match,
+                    error: MatchDiagnostic::CatchAllForbidden(enum_name.to_string()),
+                }),
+            );
+        }
+    }
+}
+
+/// Reports an error if the match on the given enum does not handle explicitly the same variants as
+/// the matches on it in the function linked by the `consistent_match` attribute, if any.
+fn report_match_coverage_mismatch(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+    concrete_enum_id: semantic::ConcreteEnumId,
+) {
+    let Ok(Some(attr)) = ctx.semantic_function_id.find_attr(ctx.db.upcast(), CONSISTENT_MATCH_ATTR)
+    else {
+        return;
+    };
+    let linked_function = match &attr.args[..] {
+        [AttributeArg { variant: AttributeArgVariant::Unnamed(value), .. }] => {
+            let name = value.as_syntax_node().get_text_without_trivia(ctx.db.upcast());
+            let module_id = ctx.semantic_function_id.parent_module(ctx.db.upcast());
+            match ctx.db.module_item_by_name(module_id, name.into()) {
+                Ok(Some(ModuleItemId::FreeFunction(id))) => Some(id),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    let Some(linked_function) = linked_function else {
+        ctx.diagnostics.report(
+            attr.args_stable_ptr.untyped(),
+            MatchError(MatchError {
+                kind: MatchKind::Match,
+                error: MatchDiagnostic::UnsupportedConsistentMatchArguments,
+            }),
+        );
+        return;
+    };
+    let Ok(linked_body) = ctx.db.function_body(FunctionWithBodyId::Free(linked_function)) else {
+        return;
+    };
+    let Ok(concrete_variants) = ctx.concrete_enum_variants(concrete_enum_id) else {
+        return;
+    };
+    let Some(handled) = explicitly_handled_variants(ctx, ctx.function_body.clone(), expr) else {
+        return;
+    };
+    for linked_expr in linked_body.arenas.exprs.iter().map(|(_, expr)| expr) {
+        let semantic::Expr::Match(linked_expr) = linked_expr else {
+            continue;
+        };
+        let matched_ty = linked_body.arenas.exprs[linked_expr.matched_expr].ty();
+        if peel_snapshots(ctx.db.upcast(), matched_ty).1
+            != TypeLongId::Concrete(ConcreteTypeId::Enum(concrete_enum_id))
+        {
+            continue;
+        }
+        let Some(linked_handled) =
+            explicitly_handled_variants(ctx, linked_body.clone(), linked_expr)
+        else {
+            continue;
+        };
+        let variants = concrete_variants
+            .iter()
+            .filter(|variant| handled.contains(variant) != linked_handled.contains(variant))
+            .map(|variant| variant.id.name(ctx.db.upcast()))
+            .collect_vec();
+        if variants.is_empty() {
+            continue;
+        }
+        let location = Location::new(StableLocation::new(expr.stable_ptr.untyped())).with_note(
+            DiagnosticNote::with_location(
+                "the linked match".into(),
+                StableLocation::new(linked_expr.stable_ptr.untyped())
+                    .diagnostic_location(ctx.db.upcast()),
+            ),
+        );
+        ctx.diagnostics.report_by_location(
+            location,
+            MatchError(MatchError {
+                kind: MatchKind::Match,
+                error: MatchDiagnostic::MatchCoverageMismatch {
+                    function: linked_function.name(ctx.db.upcast()),
+                    variants,
+                },
+            }),
+        );
+        return;
+    }
+}
+
+/// Returns the variants covered by the arms of the given match in `function_body` that have no
+/// catch-all pattern, or `None` if the match is invalid.
+/// Diagnostics of the match are not reported, as they are reported when it is lowered.
+fn explicitly_handled_variants(
+    ctx: &mut LoweringContext<'_, '_>,
+    function_body: Arc<semantic::FunctionBody>,
+    expr: &semantic::ExprMatch,
+) -> Option<Vec<semantic::ConcreteVariant>> {
+    let function_body = std::mem::replace(&mut ctx.function_body, function_body);
+    let diagnostics = std::mem::take(&mut ctx.diagnostics);
+    let coverage = match_arm_coverage(ctx, expr).ok().map(|coverage| {
+        zip_eq(&expr.arms, coverage)
+            .filter(|(arm, _)| {
+                !arm.patterns.iter().any(|pattern| {
+                    matches!(
+                        ctx.function_body.arenas.patterns[*pattern],
+                        Pattern::Otherwise(_) | Pattern::Variable(_)
+                    )
+                })
+            })
+            .flat_map(|(_, variants)| variants)
+            .collect()
+    });
+    ctx.function_body = function_body;
+    ctx.diagnostics = diagnostics;
+    coverage
+}
+
+/// Records the variants handled by explicit arms of the given match, and reports the variants the
+/// match no longer handles explicitly compared to the baseline, if one is given.
+fn report_match_coverage_regression(ctx: &mut LoweringContext<'_, '_>, expr: &semantic::ExprMatch) {
+    let Some(baseline) = ctx.match_coverage_baseline.clone() else {
+        return;
+    };
+    let Some(match_index) = ctx
+        .function_body
+        .arenas
+        .exprs
+        .iter()
+        .filter_map(|(_, expr)| try_extract_matches!(expr, semantic::Expr::Match))
+        .position(|other| other.stable_ptr == expr.stable_ptr)
+    else {
+        return;
+    };
+    let Some(handled) = explicitly_handled_variants(ctx, ctx.function_body.clone(), expr) else {
+        return;
+    };
+    let handled = handled.iter().map(|variant| variant.id.name(ctx.db.upcast())).collect_vec();
+    let key = format!("{}#{match_index}", ctx.semantic_function_id.full_path(ctx.db.upcast()));
+    if let Some(baseline_handled) = baseline.get(&key) {
+        let variants = baseline_handled
+            .iter()
+            .filter(|variant| !handled.contains(variant))
+            .cloned()
+            .collect_vec();
+        if !variants.is_empty() {
+            ctx.diagnostics.report(
+                expr.stable_ptr.untyped(),
+                MatchError(MatchError {
+                    kind: MatchKind::Match,
+                    error: MatchDiagnostic::MatchCoverageRegression(variants),
+                }),
+            );
+        }
+    }
+    ctx.match_coverage.coverage.insert(key, handled);
+}
+
+/// Reports a warning on the first match of a chain of matches which is clearer as an
+/// `if let ... else if let ...` chain - each of the matches has an arm of a single variant, and a
+/// catch-all arm whose body is the next match of the chain.
+fn report_single_variant_match_chain(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+) {
+    if ctx.single_variant_match_chains.contains(&expr.stable_ptr)
+        || !is_single_variant_match(ctx, expr)
+    {
+        return;
+    }
+    let mut chain = vec![];
+    let mut current = expr;
+    while let Some(next) = next_single_variant_match(ctx, current) {
+        chain.push(next.stable_ptr);
+        current = next;
+    }
+    if chain.is_empty() {
+        return;
+    }
+    ctx.single_variant_match_chains.extend(chain);
+    ctx.diagnostics.report(
+        expr.stable_ptr.untyped(),
+        MatchWarning(MatchError {
+            kind: MatchKind::Match,
+            error: MatchDiagnostic::SingleVariantMatchChain,
+        }),
+    );
+}
+
+/// Returns whether `expr` is a match with exactly two arms - an arm of a single variant followed by
+/// a catch-all arm.
+fn is_single_variant_match(ctx: &LoweringContext<'_, '_>, expr: &semantic::ExprMatch) -> bool {
+    let patterns = &ctx.function_body.arenas.patterns;
+    let [variant_arm, otherwise_arm] = &expr.arms[..] else {
+        return false;
+    };
+    let ([variant_pattern], [otherwise_pattern]) =
+        (&variant_arm.patterns[..], &otherwise_arm.patterns[..])
+    else {
+        return false;
+    };
+    matches!(patterns[*variant_pattern], semantic::Pattern::EnumVariant(_))
+        && matches!(patterns[*otherwise_pattern], semantic::Pattern::Otherwise(_))
+}
+
+/// Returns the match which is the body of the catch-all arm of the single variant match `expr`, if
+/// it is a single variant match as well.
+fn next_single_variant_match<'a>(
+    ctx: &'a LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+) -> Option<&'a semantic::ExprMatch> {
+    let exprs = &ctx.function_body.arenas.exprs;
+    let mut body = &exprs[expr.arms[1].expression];
+    if let semantic::Expr::Block(semantic::ExprBlock { statements, tail: Some(tail), .. }) = body {
+        if statements.is_empty() {
+            body = &exprs[*tail];
+        }
+    }
+    let semantic::Expr::Match(next) = body else {
+        return None;
+    };
+    is_single_variant_match(ctx, next).then_some(next)
+}
+
+/// Reports a warning if the matched numeric expression is a conversion of an enum value, i.e. a
+/// call of a function whose only argument is an enum, as matching the enum directly is checked for
+/// exhaustiveness and does not depend on the values the conversion maps the variants to.
+fn report_numeric_match_on_enum_conversion(
+    ctx: &mut LoweringContext<'_, '_>,
+    matched_expr: &semantic::Expr,
+) {
+    let semantic::Expr::FunctionCall(call) = matched_expr else {
+        return;
+    };
+    let [semantic::ExprFunctionCallArg::Value(arg)] = call.args[..] else {
+        return;
+    };
+    let arg_ty = ctx.function_body.arenas.exprs[arg].ty();
+    let TypeLongId::Concrete(ConcreteTypeId::Enum(concrete_enum_id)) =
+        peel_snapshots(ctx.db.upcast(), arg_ty).1
+    else {
+        return;
+    };
+    let enum_name = concrete_enum_id.enum_id(ctx.db.upcast()).name(ctx.db.upcast());
+    ctx.diagnostics.report(
+        call.stable_ptr.untyped(),
+        MatchWarning(MatchError {
+            kind: MatchKind::Match,
+            error: MatchDiagnostic::NumericMatchOnEnumConversion(enum_name.to_string()),
+        }),
+    );
+}
+
+/// Reports a warning if the matched expression is an explicit snapshot (`match @x`) which is not
+/// required - the snapshotted type is copyable and no arm binds any part of the matched value.
+fn report_redundant_snapshot(
+    ctx: &mut LoweringContext<'_, '_>,
+    matched_expr: &semantic::Expr,
+    arms: &[semantic::MatchArm],
+) {
+    let semantic::Expr::Snapshot(snapshot) = matched_expr else {
+        return;
+    };
+    let patterns = &ctx.function_body.arenas.patterns;
+    if arms
+        .iter()
+        .flat_map(|arm| arm.patterns.iter())
+        .any(|pattern| !patterns[*pattern].variables(patterns).is_empty())
+    {
+        return;
+    }
+    let inner_ty = ctx.function_body.arenas.exprs[snapshot.inner].ty();
+    let Ok(type_info) = ctx.db.type_info(ctx.variables.lookup_context.clone(), inner_ty) else {
+        return;
+    };
+    if type_info.copyable.is_err() {
+        return;
+    }
+    ctx.diagnostics.report(
+        snapshot.stable_ptr.untyped(),
+        MatchWarning(MatchError {
+            kind: MatchKind::Match,
+            error: MatchDiagnostic::RedundantSnapshotInMatchedExpr,
+        }),
+    );
+}
+
+pub(crate) fn lower_concrete_enum_match(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    matched_expr: &semantic::Expr,
+    lowered_matched_expr: LoweredExpr,
+    arms: &[MatchArmWrapper],
+    location: LocationId,
+    match_type: MatchKind,
+) -> LoweringResult<LoweredExpr> {
+    let ExtractedEnumDetails { concrete_enum_id, concrete_variants, n_snapshots } =
+        extract_concrete_enum(ctx, matched_expr.into(), matched_expr.ty(), match_type)?;
+    let match_input = lowered_matched_expr.as_var_usage(ctx, builder)?;
+    // TODO: A match on an enum with a single variant could bind the payload without a match, and a
+    // tuple payload could then be destructured directly into the variables of its elements. This
+    // requires a statement extracting the payload of an enum, which lowering does not have yet, so
+    // such matches are lowered into a match with a single arm.
+
+    // Merge arm blocks. A variable pattern is a catch-all bound to the matched value, which can
+    // only be reconstructed from the variant and its payload if the value is not a snapshot.
+    let otherwise_variant = get_underscore_pattern_path(ctx, arms, match_type, n_snapshots == 0);
+
+    let variant_map = get_variant_to_arm_map(
+        ctx,
+        arms.iter().take(
+            otherwise_variant
+                .as_ref()
+                .map(|PatternPath { arm_index, .. }| *arm_index)
+                .unwrap_or(arms.len()),
+        ),
+        concrete_enum_id,
+        concrete_variants.len(),
+        match_type,
+    )?;
+    report_unreachable_otherwise_arm(
+        ctx,
+        arms,
+        otherwise_variant.as_ref(),
+        &variant_map,
+        concrete_variants.len(),
+        match_type,
+    );
+    let conditional_arm_paths =
+        get_conditional_arm_paths(ctx, arms, concrete_enum_id, match_type, n_snapshots == 0)?;
+    let mut arm_var_ids = vec![];
+    let mut block_ids = vec![];
+    let variants_block_builders = concrete_variants
+        .iter()
+        .enumerate()
+        .map(|(variant_index, concrete_variant)| {
+            let arm_path = variant_map.get(concrete_variant).or(otherwise_variant.as_ref());
+            // The conditional arms matching the variant before its arm are tried first, in order,
+            // each falling through to the next one when it is not taken.
+            let conditional_paths = conditional_arm_paths
+                .iter()
+                .filter(|(PatternPath { arm_index, .. }, variant)| {
+                    *arm_index < arm_path.map_or(arms.len(), |path| path.arm_index)
+                        && !matches!(variant, Some(variant) if variant != concrete_variant)
+                })
+                .map(|(path, _)| path)
+                .dedup_by(|path, prev_path| path.arm_index == prev_path.arm_index)
+                .collect_vec();
+
+            let subscope = create_subscope(ctx, builder);
+            block_ids.push(subscope.block_id);
+            let var_location = match conditional_paths.first().copied().or(arm_path) {
+                Some(path) => enum_variant_pattern_location(ctx, arms, path, location),
+                None => location,
+            };
+            let var_id = ctx.new_var(VarRequest {
+                ty: wrap_in_snapshots(ctx.db.upcast(), concrete_variant.ty, n_snapshots),
+                location: var_location,
+            });
+            arm_var_ids.push(vec![var_id]);
+
+            let mut leaves = vec![];
+            // The blocks where none of the conditional arms tried so far is taken, along with the
+            // variable of the payload of the variant in each of them.
+            let mut subscopes = vec![(subscope, var_id)];
+            for path in conditional_paths {
+                for (subscope, var_id) in std::mem::take(&mut subscopes) {
+                    let (path_leaves, fallthrough_subscopes) = lower_conditional_arm_pattern(
+                        ctx,
+                        subscope,
+                        arms,
+                        path,
+                        concrete_variant,
+                        var_id,
+                        match_input,
+                        match_type,
+                    );
+                    leaves.extend(path_leaves);
+                    subscopes.extend(fallthrough_subscopes);
+                }
+            }
+            if subscopes.is_empty() {
+                return Ok(leaves);
+            }
+
+            let Some(path) = arm_path else {
+                leaves.extend(subscopes.into_iter().map(|(subscope, _)| {
+                    lower_uncovered_leaf(ctx, subscope, location, arms.len() + variant_index)
+                }));
+                return Ok(leaves);
+            };
+            for (mut subscope, var_id) in subscopes {
+                let lowering_result = lower_enum_variant_pattern(
+                    ctx,
+                    &mut subscope,
+                    arms,
+                    path,
+                    concrete_variant,
+                    var_id,
+                    match_input,
+                );
+                leaves.push(MatchLeafBuilder {
+                    arm_index: path.arm_index,
+                    lowering_result,
+                    builder: subscope,
+                });
+            }
+            Ok(leaves)
+        })
+        .flatten_ok()
+        .collect::<LoweringResult<Vec<_>>>()?;
+    report_missing_enum_arms(
+        ctx,
+        location,
+        concrete_enum_id,
+        concrete_variants
+            .iter()
+            .filter(|variant| !variant_map.contains_key(*variant) && otherwise_variant.is_none()),
+        match_type,
+    )?;
+
+    let empty_match_info = MatchInfo::Enum(MatchEnumInfo {
+        concrete_enum_id,
+        input: match_input,
+        arms: vec![],
+        location,
+    });
+
+    let sealed_blocks = group_match_arms(
+        ctx,
+        empty_match_info,
+        location,
+        arms,
+        variants_block_builders,
+        match_type,
+    )?;
+
+    let match_info = MatchInfo::Enum(MatchEnumInfo {
+        concrete_enum_id,
+        input: match_input,
+        arms: zip_eq(zip_eq(concrete_variants.iter().cloned(), block_ids), arm_var_ids)
+            .map(|((variant_id, block_id), var_ids)| MatchArm {
+                arm_selector: MatchArmSelector::VariantId(variant_id),
+                block_id,
+                var_ids,
+            })
+            .collect(),
+        location,
+    });
+    builder.merge_and_end_with_match(ctx, match_info, sealed_blocks, location)
+}
+
+/// Reports the guards of `arms` whose value is known at compile time. An arm whose guard is always
+/// false is unreachable, and a guard which is always true is redundant.
+fn report_constant_guards(ctx: &mut LoweringContext<'_, '_>, arms: &[semantic::MatchArm]) {
+    let function_body = ctx.function_body.clone();
+    for guard in arms.iter().filter_map(|arm| arm.guard) {
+        // The bindings of the pattern, and any other variable, are only known at runtime.
+        let usage = Usages::scope_expr_usage(&function_body.arenas, guard);
+        if !usage.usage.is_empty() || !usage.snap_usage.is_empty() {
+            continue;
+        }
+        let Some(ConstValue::Enum(variant, _)) =
+            try_evaluate_const_expr(ctx.db.upcast(), &function_body.arenas, guard)
+        else {
+            continue;
+        };
+        let stable_ptr = function_body.arenas.exprs[guard].stable_ptr().untyped();
+        if variant == corelib::false_variant(ctx.db.upcast()) {
+            report_unreachable_match_arm(ctx, stable_ptr, MatchKind::Match);
+        } else {
+            ctx.diagnostics.report(
+                stable_ptr,
+                MatchWarning(MatchError {
+                    kind: MatchKind::Match,
+                    error: MatchDiagnostic::MatchArmGuardAlwaysTrue,
+                }),
+            );
+        }
+    }
+}
+
+/// Returns the paths of the patterns of the conditional arms, which may not be taken even when
+/// their pattern matches the variant, along with the variant each of them matches, or `None` for a
+/// catch-all pattern. These are the patterns of the arms with a guard, and the patterns of a
+/// variant whose payload is matched by an or-pattern.
+///
+/// If `allow_variable_catch_all` is set, a variable pattern is also considered a catch-all.
+fn get_conditional_arm_paths(
+    ctx: &mut LoweringContext<'_, '_>,
+    arms: &[MatchArmWrapper],
+    concrete_enum_id: semantic::ConcreteEnumId,
+    match_type: MatchKind,
+    allow_variable_catch_all: bool,
+) -> LoweringResult<Vec<(PatternPath, Option<semantic::ConcreteVariant>)>> {
+    let mut paths = vec![];
+    for (arm_index, arm) in arms.iter().enumerate() {
+        for (pattern_index, pattern) in arm.patterns.iter().enumerate() {
+            let path = PatternPath { arm_index, pattern_index: Some(pattern_index) };
+            let pattern =
+                &ctx.function_body.arenas.patterns[peel_pattern_bindings(ctx, *pattern).1];
+            match pattern {
+                Pattern::Otherwise(_) if arm.guard.is_some() => paths.push((path, None)),
+                Pattern::Variable(_) if arm.guard.is_some() && allow_variable_catch_all => {
+                    paths.push((path, None))
+                }
+                Pattern::EnumVariant(enum_pattern)
+                    if enum_pattern.variant.concrete_enum_id == concrete_enum_id
+                        && (arm.guard.is_some() || has_inner_or_pattern(ctx, enum_pattern)) =>
+                {
+                    paths.push((path, Some(enum_pattern.variant.clone())));
+                }
+                // Variants of other enums are reported by `get_variant_to_arm_map`, as are the
+                // patterns of arms without a guard.
+                Pattern::EnumVariant(_) => {}
+                _ if arm.guard.is_none() => {}
+                _ => {
+                    return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+                        pattern.stable_ptr(),
+                        MatchError(MatchError {
+                            kind: match_type,
+                            error: MatchDiagnostic::UnsupportedMatchArmNotAVariant,
+                        }),
+                    )));
+                }
+            }
+        }
+    }
+    Ok(paths)
+}
+
+/// Returns whether the payload of the variant of `enum_pattern` is matched by an or-pattern.
+fn has_inner_or_pattern(
+    ctx: &LoweringContext<'_, '_>,
+    enum_pattern: &semantic::PatternEnumVariant,
+) -> bool {
+    enum_pattern.inner_pattern.is_some_and(|inner_pattern| {
+        matches!(ctx.function_body.arenas.patterns[inner_pattern], Pattern::Or(_))
+    })
+}
+
+/// Returns the location of the variable of the payload of a variant, for the pattern at `path`.
+/// `location` is the location of the match, used for arms without patterns.
+fn enum_variant_pattern_location(
+    ctx: &mut LoweringContext<'_, '_>,
+    arms: &[MatchArmWrapper],
+    path: &PatternPath,
+    location: LocationId,
+) -> LocationId {
+    let Some(pattern_index) = path.pattern_index else {
+        return location;
+    };
+    let (_, pattern) = peel_pattern_bindings(ctx, arms[path.arm_index].patterns[pattern_index]);
+    let pattern = &ctx.function_body.arenas.patterns[pattern];
+    let stable_ptr = match pattern {
+        Pattern::EnumVariant(PatternEnumVariant { inner_pattern: Some(inner_pattern), .. }) => {
+            ctx.function_body.arenas.patterns[*inner_pattern].stable_ptr()
+        }
+        _ => pattern.stable_ptr(),
+    };
+    ctx.get_location(stable_ptr.untyped())
+}
+
+/// Lowers the pattern at `path`, of an arm taken for `concrete_variant`, binding it to the payload
+/// of the variant in `var_id`. `match_input` is the matched value, bound by the pattern if it is a
+/// snapshot.
+fn lower_enum_variant_pattern(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    arms: &[MatchArmWrapper],
+    path: &PatternPath,
+    concrete_variant: &semantic::ConcreteVariant,
+    var_id: VariableId,
+    match_input: VarUsage,
+) -> LoweringResult<()> {
+    let Some(pattern_index) = path.pattern_index else {
+        return Ok(());
+    };
+    let (bindings, pattern) =
+        peel_pattern_bindings(ctx, arms[path.arm_index].patterns[pattern_index]);
+    bind_matched_variant(ctx, builder, bindings, concrete_variant, var_id, match_input)?;
+    let pattern = ctx.function_body.arenas.patterns[pattern].clone();
+    // TODO: When the payloads of all the variants start with a common field (such as a header)
+    // bound by every arm, the field is destructured separately in each arm, as the payload is only
+    // available after the match. Extracting it once requires knowing the common field of the
+    // payloads before the match, which enums can not provide yet.
+    match pattern {
+        Pattern::EnumVariant(PatternEnumVariant { inner_pattern: Some(inner_pattern), .. }) => {
+            let inner_pattern = ctx.function_body.arenas.patterns[inner_pattern].clone();
+            let pattern_location = ctx.get_location(inner_pattern.stable_ptr().untyped());
+            let variant_expr =
+                LoweredExpr::AtVariable(VarUsage { var_id, location: pattern_location });
+            lower_single_pattern(ctx, builder, inner_pattern, variant_expr)
+        }
+        Pattern::EnumVariant(PatternEnumVariant { inner_pattern: None, .. })
+        | Pattern::Otherwise(_) => Ok(()),
+        // A variable pattern is a catch-all bound to the matched value, which is reconstructed
+        // from the variant and its payload.
+        Pattern::Variable(pattern) => {
+            let pattern_location = ctx.get_location(pattern.stable_ptr.untyped());
+            let reconstructed = generators::EnumConstruct {
+                input: VarUsage { var_id, location: pattern_location },
+                variant: concrete_variant.clone(),
+                location: pattern_location,
+            }
+            .add(ctx, &mut builder.statements);
+            lower_single_pattern(
+                ctx,
+                builder,
+                Pattern::Variable(pattern),
+                LoweredExpr::AtVariable(reconstructed),
+            )
+        }
+        _ => unreachable!(
+            "function `get_variant_to_arm_map` should have reported every other pattern type"
+        ),
+    }
+}
+
+/// Lowers the pattern at `path` of a conditional arm, taken for `concrete_variant` whose payload is
+/// in `var_id`, at the end of `builder`. `match_input` is the matched value. See
+/// [get_conditional_arm_paths].
+///
+/// Returns the leaves of the arm, and the builders of the blocks where the arm is not taken, along
+/// with the variable of the payload of the variant in each of them.
+#[allow(clippy::too_many_arguments)]
+fn lower_conditional_arm_pattern(
+    ctx: &mut LoweringContext<'_, '_>,
+    mut builder: BlockBuilder,
+    arms: &[MatchArmWrapper],
+    path: &PatternPath,
+    concrete_variant: &semantic::ConcreteVariant,
+    var_id: VariableId,
+    match_input: VarUsage,
+    match_type: MatchKind,
+) -> (Vec<MatchLeafBuilder>, Vec<(BlockBuilder, VariableId)>) {
+    let arm_index = path.arm_index;
+    let inner_or_pattern = path.pattern_index.and_then(|pattern_index| {
+        let (_, pattern) = peel_pattern_bindings(ctx, arms[arm_index].patterns[pattern_index]);
+        let pattern = &ctx.function_body.arenas.patterns[pattern];
+        let inner_pattern = try_extract_matches!(pattern, Pattern::EnumVariant)?.inner_pattern?;
+        try_extract_matches!(&ctx.function_body.arenas.patterns[inner_pattern], Pattern::Or)
+            .cloned()
+    });
+    let (pattern_leaves, mut fallthrough_subscopes) = match inner_or_pattern {
+        Some(or_pattern) => {
+            // The whole matched value is the same for all the alternatives, so it is bound before
+            // matching them.
+            let pattern_index = path.pattern_index.unwrap();
+            let (bindings, _) = peel_pattern_bindings(ctx, arms[arm_index].patterns[pattern_index]);
+            match bind_matched_variant(
+                ctx,
+                &mut builder,
+                bindings,
+                concrete_variant,
+                var_id,
+                match_input,
+            ) {
+                Ok(()) => {
+                    lower_inner_or_pattern(ctx, builder, arm_index, &or_pattern, var_id, match_type)
+                }
+                Err(err) => (
+                    vec![MatchLeafBuilder { arm_index, lowering_result: Err(err), builder }],
+                    vec![],
+                ),
+            }
+        }
+        None => {
+            let lowering_result = lower_enum_variant_pattern(
+                ctx,
+                &mut builder,
+                arms,
+                path,
+                concrete_variant,
+                var_id,
+                match_input,
+            );
+            (vec![MatchLeafBuilder { arm_index, lowering_result, builder }], vec![])
+        }
+    };
+    let Some(guard) = arms[arm_index].guard else {
+        return (pattern_leaves, fallthrough_subscopes);
+    };
+    let mut leaves = vec![];
+    for leaf in pattern_leaves {
+        if leaf.lowering_result.is_err() {
+            leaves.push(leaf);
+            continue;
+        }
+        let (leaf, guard_fails_subscope) =
+            lower_match_arm_guard(ctx, leaf.builder, arm_index, guard);
+        leaves.push(leaf);
+        fallthrough_subscopes.extend(guard_fails_subscope.map(|subscope| (subscope, var_id)));
+    }
+    (leaves, fallthrough_subscopes)
+}
+
+/// Lowers the or-pattern of the payload of a variant in `var_id`, of the arm at `arm_index`, at
+/// the end of `builder`, into a match on the payload.
+///
+/// Returns the leaves of the alternatives, and the builders of the blocks where no alternative
+/// matches, along with the variable of the payload in each of them.
+fn lower_inner_or_pattern(
+    ctx: &mut LoweringContext<'_, '_>,
+    mut builder: BlockBuilder,
+    arm_index: usize,
+    or_pattern: &semantic::PatternOr,
+    var_id: VariableId,
+    match_type: MatchKind,
+) -> (Vec<MatchLeafBuilder>, Vec<(BlockBuilder, VariableId)>) {
+    let semantic_db = ctx.db.upcast();
+    let location = ctx.get_location(or_pattern.stable_ptr.untyped());
+    let mut input = VarUsage { var_id, location };
+    let (n_snapshots, long_ty) = peel_snapshots(semantic_db, ctx.variables[var_id].ty);
+    if let TypeLongId::Concrete(ConcreteTypeId::Enum(concrete_enum_id)) = long_ty {
+        return match get_or_pattern_variants(ctx, or_pattern, concrete_enum_id, match_type) {
+            Ok((variant_patterns, has_catch_all)) => lower_inner_or_pattern_enum(
+                ctx,
+                builder,
+                arm_index,
+                input,
+                n_snapshots,
+                variant_patterns,
+                has_catch_all,
+            ),
+            Err(err) => {
+                (vec![MatchLeafBuilder { arm_index, lowering_result: Err(err), builder }], vec![])
+            }
+        };
+    }
+
+    let ty = long_ty.intern(ctx.db);
+    let literals = if ty == ctx.db.core_info().felt252
+        || corelib::get_convert_to_felt252_libfunc_name_by_type(semantic_db, ty).is_some()
+        || corelib::try_extract_bounded_int_type_ranges(semantic_db, ty).is_some()
+    {
+        get_or_pattern_literals(ctx, or_pattern, match_type)
+    } else {
+        Err(report_unsupported_inner_or_pattern(ctx, or_pattern.stable_ptr, match_type))
+    };
+    let (values, has_catch_all) = match literals {
+        Ok(literals) => literals,
+        Err(err) => {
+            return (
+                vec![MatchLeafBuilder { arm_index, lowering_result: Err(err), builder }],
+                vec![],
+            );
+        }
+    };
+    for _ in 0..n_snapshots {
+        input = generators::Desnap { input, location }.add(ctx, &mut builder.statements);
+    }
+    lower_inner_or_pattern_numeric(ctx, builder, arm_index, input, var_id, values, has_catch_all)
+}
+
+/// Returns the variants matched by the alternatives of an or-pattern on a value of the enum
+/// `concrete_enum_id`, along with the pattern of the payload of each of them, and whether the
+/// pattern has a catch-all alternative.
+fn get_or_pattern_variants(
+    ctx: &mut LoweringContext<'_, '_>,
+    or_pattern: &semantic::PatternOr,
+    concrete_enum_id: semantic::ConcreteEnumId,
+    match_type: MatchKind,
+) -> LoweringResult<(OrderedHashMap<semantic::ConcreteVariant, Option<PatternId>>, bool)> {
+    let mut variant_patterns = OrderedHashMap::default();
+    for alternative in get_or_pattern_alternatives(ctx, or_pattern, match_type) {
+        let Pattern::EnumVariant(enum_pattern) = &alternative else {
+            return Err(report_unsupported_inner_or_pattern(
+                ctx,
+                alternative.stable_ptr(),
+                match_type,
+            ));
+        };
+        if enum_pattern.variant.concrete_enum_id != concrete_enum_id {
+            return Err(report_unknown_variant(
+                ctx,
+                &alternative,
+                &enum_pattern.variant,
+                concrete_enum_id,
+                match_type,
+            ));
+        }
+        if variant_patterns.contains_key(&enum_pattern.variant) {
+            report_unreachable_match_arm(ctx, &alternative, match_type);
+        } else {
+            variant_patterns.insert(enum_pattern.variant.clone(), enum_pattern.inner_pattern);
+        }
+    }
+    Ok((variant_patterns, has_or_pattern_catch_all(ctx, or_pattern)))
+}
+
+/// Returns the values of the literals of the alternatives of an or-pattern on a numeric value, and
+/// whether the pattern has a catch-all alternative.
+fn get_or_pattern_literals(
+    ctx: &mut LoweringContext<'_, '_>,
+    or_pattern: &semantic::PatternOr,
+    match_type: MatchKind,
+) -> LoweringResult<(Vec<BigInt>, bool)> {
+    let mut values = vec![];
+    for alternative in get_or_pattern_alternatives(ctx, or_pattern, match_type) {
+        let Pattern::Literal(literal_pattern) = &alternative else {
+            return Err(report_unsupported_inner_or_pattern(
+                ctx,
+                alternative.stable_ptr(),
+                match_type,
+            ));
+        };
+        if values.contains(&literal_pattern.literal.value) {
+            report_unreachable_match_arm(ctx, &alternative, match_type);
+        } else {
+            values.push(literal_pattern.literal.value.clone());
+        }
+    }
+    Ok((values, has_or_pattern_catch_all(ctx, or_pattern)))
+}
+
+/// Returns the alternatives of an or-pattern before its first catch-all alternative, reporting the
+/// alternatives after it as unreachable.
+fn get_or_pattern_alternatives(
+    ctx: &mut LoweringContext<'_, '_>,
+    or_pattern: &semantic::PatternOr,
+    match_type: MatchKind,
+) -> Vec<semantic::Pattern> {
+    let mut alternatives = or_pattern
+        .alternatives
+        .iter()
+        .map(|alternative| ctx.function_body.arenas.patterns[*alternative].clone())
+        .collect_vec();
+    if let Some(catch_all_index) =
+        alternatives.iter().position(|alternative| matches!(alternative, Pattern::Otherwise(_)))
+    {
+        for alternative in alternatives.drain(catch_all_index..).skip(1) {
+            report_unreachable_match_arm(ctx, &alternative, match_type);
+        }
+    }
+    alternatives
+}
+
+/// Returns whether an or-pattern has a catch-all alternative.
+fn has_or_pattern_catch_all(
+    ctx: &LoweringContext<'_, '_>,
+    or_pattern: &semantic::PatternOr,
+) -> bool {
+    or_pattern.alternatives.iter().any(|alternative| {
+        matches!(ctx.function_body.arenas.patterns[*alternative], Pattern::Otherwise(_))
+    })
+}
+
+/// Reports an or-pattern, or an alternative of it, which is not supported in the pattern of a
+/// variant.
+fn report_unsupported_inner_or_pattern(
+    ctx: &mut LoweringContext<'_, '_>,
+    stable_ptr: impl Into<SyntaxStablePtrId>,
+    match_type: MatchKind,
+) -> LoweringFlowError {
+    LoweringFlowError::Failed(ctx.diagnostics.report(
+        stable_ptr,
+        MatchError(MatchError {
+            kind: match_type,
+            error: MatchDiagnostic::UnsupportedInnerOrPattern,
+        }),
+    ))
+}
+
+/// Lowers an or-pattern on the value of an enum in `input`, at the end of `builder`, into a match
+/// on the value. See [lower_inner_or_pattern].
+fn lower_inner_or_pattern_enum(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: BlockBuilder,
+    arm_index: usize,
+    input: VarUsage,
+    n_snapshots: usize,
+    variant_patterns: OrderedHashMap<semantic::ConcreteVariant, Option<PatternId>>,
+    has_catch_all: bool,
+) -> (Vec<MatchLeafBuilder>, Vec<(BlockBuilder, VariableId)>) {
+    let location = input.location;
+    let TypeLongId::Concrete(ConcreteTypeId::Enum(concrete_enum_id)) =
+        peel_snapshots(ctx.db.upcast(), ctx.variables[input.var_id].ty).1
+    else {
+        unreachable!("The or-pattern is on a value of an enum.");
+    };
+    let concrete_variants = match ctx.concrete_enum_variants(concrete_enum_id) {
+        Ok(concrete_variants) => concrete_variants,
+        Err(diag_added) => {
+            let lowering_result = Err(LoweringFlowError::Failed(diag_added));
+            return (vec![MatchLeafBuilder { arm_index, lowering_result, builder }], vec![]);
+        }
+    };
+    let mut leaves = vec![];
+    let mut fallthrough_subscopes = vec![];
+    let mut match_arms = vec![];
+    for concrete_variant in concrete_variants.iter().cloned() {
+        let mut subscope = create_subscope(ctx, &builder);
+        let variant_var_id = ctx.new_var(VarRequest {
+            ty: wrap_in_snapshots(ctx.db.upcast(), concrete_variant.ty, n_snapshots),
+            location,
+        });
+        match_arms.push(MatchArm {
+            arm_selector: MatchArmSelector::VariantId(concrete_variant.clone()),
+            block_id: subscope.block_id,
+            var_ids: vec![variant_var_id],
+        });
+        let lowering_result = match variant_patterns.get(&concrete_variant) {
+            Some(Some(inner_pattern)) => {
+                let inner_pattern = ctx.function_body.arenas.patterns[*inner_pattern].clone();
+                let pattern_location = ctx.get_location(inner_pattern.stable_ptr().untyped());
+                let variant_expr = LoweredExpr::AtVariable(VarUsage {
+                    var_id: variant_var_id,
+                    location: pattern_location,
+                });
+                lower_single_pattern(ctx, &mut subscope, inner_pattern, variant_expr)
+            }
+            Some(None) => Ok(()),
+            None if has_catch_all => Ok(()),
+            None => {
+                // The value is consumed by the match, so it is reconstructed for the next arms,
+                // unless it is a snapshot.
+                let var_id = if n_snapshots == 0 {
+                    generators::EnumConstruct {
+                        input: VarUsage { var_id: variant_var_id, location },
+                        variant: concrete_variant,
+                        location,
+                    }
+                    .add(ctx, &mut subscope.statements)
+                    .var_id
+                } else {
+                    input.var_id
+                };
+                fallthrough_subscopes.push((subscope, var_id));
+                continue;
+            }
+        };
+        leaves.push(MatchLeafBuilder { arm_index, lowering_result, builder: subscope });
+    }
+    builder.finalize(
+        ctx,
+        FlatBlockEnd::Match {
+            info: MatchInfo::Enum(MatchEnumInfo {
+                concrete_enum_id,
+                input,
+                arms: match_arms,
+                location,
+            }),
+        },
+    );
+    (leaves, fallthrough_subscopes)
+}
+
+/// Lowers an or-pattern on the numeric value in `input`, at the end of `builder`, into a chain of
+/// checks of the values of its literals. `var_id` is the matched variable, before it was desnapped
+/// into `input`. See [lower_inner_or_pattern].
+fn lower_inner_or_pattern_numeric(
+    ctx: &mut LoweringContext<'_, '_>,
+    mut builder: BlockBuilder,
+    arm_index: usize,
+    input: VarUsage,
+    var_id: VariableId,
+    values: Vec<BigInt>,
+    has_catch_all: bool,
+) -> (Vec<MatchLeafBuilder>, Vec<(BlockBuilder, VariableId)>) {
+    let mut leaves = vec![];
+    for value in values {
+        let equal_subscope = create_subscope(ctx, &builder);
+        let not_equal_subscope = create_subscope(ctx, &builder);
+        let end = &value + 1;
+        let match_info = numeric_range_check_match_info(
+            ctx,
+            input,
+            value,
+            end,
+            equal_subscope.block_id,
+            not_equal_subscope.block_id,
+            input.location,
+        );
+        builder.finalize(ctx, FlatBlockEnd::Match { info: match_info });
+        leaves.push(MatchLeafBuilder {
+            arm_index,
+            lowering_result: Ok(()),
+            builder: equal_subscope,
+        });
+        builder = not_equal_subscope;
+    }
+    if has_catch_all {
+        leaves.push(MatchLeafBuilder { arm_index, lowering_result: Ok(()), builder });
+        return (leaves, vec![]);
+    }
+    (leaves, vec![(builder, var_id)])
+}
+
+/// Lowers the guard of the arm at `arm_index` at the end of `builder`, where the patterns of the
+/// arm are bound, into a match on its value.
+///
+/// Returns the leaf of the arm, taken when the guard holds, and the builder of the block where it
+/// does not, unless lowering the guard failed.
+fn lower_match_arm_guard(
+    ctx: &mut LoweringContext<'_, '_>,
+    mut builder: BlockBuilder,
+    arm_index: usize,
+    guard: semantic::ExprId,
+) -> (MatchLeafBuilder, Option<BlockBuilder>) {
+    let condition = match lower_expr_to_var_usage(ctx, &mut builder, guard) {
+        Ok(condition) => condition,
+        Err(err) => {
+            return (MatchLeafBuilder { arm_index, lowering_result: Err(err), builder }, None);
+        }
+    };
+    let semantic_db = ctx.db.upcast();
+    let unit_ty = corelib::unit_ty(semantic_db);
+    let location = condition.location;
+    let guard_holds = create_subscope(ctx, &builder);
+    let guard_fails = create_subscope(ctx, &builder);
+    let match_info = MatchInfo::Enum(MatchEnumInfo {
+        concrete_enum_id: corelib::core_bool_enum(semantic_db),
+        input: condition,
+        arms: vec![
+            MatchArm {
+                arm_selector: MatchArmSelector::VariantId(corelib::false_variant(semantic_db)),
+                block_id: guard_fails.block_id,
+                var_ids: vec![ctx.new_var(VarRequest { ty: unit_ty, location })],
+            },
+            MatchArm {
+                arm_selector: MatchArmSelector::VariantId(corelib::true_variant(semantic_db)),
+                block_id: guard_holds.block_id,
+                var_ids: vec![ctx.new_var(VarRequest { ty: unit_ty, location })],
+            },
+        ],
+        location,
+    });
+    builder.finalize(ctx, FlatBlockEnd::Match { info: match_info });
+    (
+        MatchLeafBuilder { arm_index, lowering_result: Ok(()), builder: guard_holds },
+        Some(guard_fails),
+    )
+}
+
+/// Returns the variants of the matched enum covered by each arm of a match, by arm index, without
+/// reporting the diagnostics of the match.
+///
+/// Each variant is covered by the first arm matching it, so a catch-all arm
+/// covers the variants not covered by the previous arms, and the arms following it cover none.
+pub fn match_arm_coverage(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+) -> LoweringResult<Vec<Vec<semantic::ConcreteVariant>>> {
+    let diagnostics = std::mem::take(&mut ctx.diagnostics);
+    let coverage = arms_coverage(ctx, expr);
+    ctx.diagnostics = diagnostics;
+    coverage
+}
+
+/// A helper function for [match_arm_coverage], which may report diagnostics.
+fn arms_coverage(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+) -> LoweringResult<Vec<Vec<semantic::ConcreteVariant>>> {
+    let matched_expr = ctx.function_body.arenas.exprs[expr.matched_expr].clone();
+    let ExtractedEnumDetails { concrete_enum_id, concrete_variants, n_snapshots } =
+        extract_concrete_enum(ctx, (&matched_expr).into(), matched_expr.ty(), MatchKind::Match)?;
+    let arms = expr.arms.iter().map(MatchArmWrapper::from).collect_vec();
+    let otherwise_arm_index =
+        get_underscore_pattern_path(ctx, &arms, MatchKind::Match, n_snapshots == 0)
+            .map(|PatternPath { arm_index, .. }| arm_index);
+    let variant_map = get_variant_to_arm_map(
+        ctx,
+        arms.iter().take(otherwise_arm_index.unwrap_or(arms.len())),
+        concrete_enum_id,
+        concrete_variants.len(),
+        MatchKind::Match,
+    )?;
+    let mut coverage = vec![vec![]; arms.len()];
+    for concrete_variant in concrete_variants.iter() {
+        let arm_index = variant_map
+            .get(concrete_variant)
+            .map(|PatternPath { arm_index, .. }| *arm_index)
+            .or(otherwise_arm_index);
+        if let Some(arm_index) = arm_index {
+            coverage[arm_index].push(concrete_variant.clone());
+        }
+    }
+    Ok(coverage)
+}
+
+/// Returns the paths of a match which are not covered by any of its arms, without reporting the
+/// diagnostics of the match. For a match on an enum, each path is a single variant of the enum. For
+/// a match on a tuple of enums, each path has a variant for each position of the flattened tuple.
+/// A match with a catch-all arm has no missing paths.
+pub fn match_missing_variants(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+) -> LoweringResult<Vec<MatchingPath>> {
+    let diagnostics = std::mem::take(&mut ctx.diagnostics);
+    let missing_paths = missing_match_paths(ctx, expr);
+    ctx.diagnostics = diagnostics;
+    missing_paths
+}
+
+/// A helper function for [match_missing_variants], which may report diagnostics.
+fn missing_match_paths(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+) -> LoweringResult<Vec<MatchingPath>> {
+    let matched_expr = ctx.function_body.arenas.exprs[expr.matched_expr].clone();
+    let arms = expr.arms.iter().map(MatchArmWrapper::from).collect_vec();
+    let (n_snapshots, long_ty) = peel_snapshots(ctx.db.upcast(), matched_expr.ty());
+    if let TypeLongId::Tuple(types) = long_ty {
+        let (extracted_enums_details, shape) = extract_concrete_enum_tuple(
+            ctx,
+            matched_expr.stable_ptr().untyped(),
+            &types,
+            MatchKind::Match,
+        )?;
+        if get_underscore_pattern_path(ctx, &arms, MatchKind::Match, true).is_some() {
+            return Ok(vec![]);
+        }
+        let rows = get_tuple_variants_rows(
+            ctx,
+            arms.iter(),
+            &extracted_enums_details,
+            &shape,
+            MatchKind::Match,
+        )?;
+        let mut missing_paths = vec![];
+        collect_missing_tuple_paths(
+            &rows.iter().collect_vec(),
+            &extracted_enums_details,
+            &mut MatchingPath::default(),
+            &mut missing_paths,
+        );
+        return Ok(missing_paths);
+    }
+
+    let ExtractedEnumDetails { concrete_enum_id, concrete_variants, .. } =
+        extract_concrete_enum(ctx, (&matched_expr).into(), matched_expr.ty(), MatchKind::Match)?;
+    if get_underscore_pattern_path(ctx, &arms, MatchKind::Match, n_snapshots == 0).is_some() {
+        return Ok(vec![]);
+    }
+    let variant_map = get_variant_to_arm_map(
+        ctx,
+        arms.iter(),
+        concrete_enum_id,
+        concrete_variants.len(),
+        MatchKind::Match,
     )?;
+    Ok(concrete_variants
+        .iter()
+        .filter(|concrete_variant| !variant_map.contains_key(*concrete_variant))
+        .map(|concrete_variant| MatchingPath { variants: vec![concrete_variant.clone()] })
+        .collect())
+}
 
-    let match_info = MatchInfo::Enum(MatchEnumInfo {
-        concrete_enum_id,
-        input: match_input,
-        arms: zip_eq(zip_eq(concrete_variants, block_ids), arm_var_ids)
-            .map(|((variant_id, block_id), var_ids)| MatchArm {
-                arm_selector: MatchArmSelector::VariantId(variant_id),
-                block_id,
-                var_ids,
-            })
-            .collect(),
-        location,
-    });
-    builder.merge_and_end_with_match(ctx, match_info, sealed_blocks, location)
+/// Collects the paths extending `path` which are not matched by any of `rows`, the rows matching
+/// `path` by order of precedence, in declaration order.
+fn collect_missing_tuple_paths(
+    rows: &[&TupleVariantsRow],
+    extracted_enums_details: &[ExtractedEnumDetails],
+    path: &mut MatchingPath,
+    missing_paths: &mut Vec<MatchingPath>,
+) {
+    let position = path.variants.len();
+    // A row matching any variant in all the remaining positions matches every extension of the
+    // path.
+    if rows.first().is_some_and(|row| row.variants[position..].iter().all(Option::is_none)) {
+        return;
+    }
+    if position == extracted_enums_details.len() {
+        missing_paths.push(path.clone());
+        return;
+    }
+    for variant in extracted_enums_details[position].concrete_variants.iter() {
+        let matching_rows = rows
+            .iter()
+            .filter(|row| row.variants[position].as_ref().is_none_or(|v| v == variant))
+            .copied()
+            .collect_vec();
+        path.variants.push(variant.clone());
+        collect_missing_tuple_paths(&matching_rows, extracted_enums_details, path, missing_paths);
+        path.variants.pop();
+    }
+}
+
+/// Extracts the extern enum from a lowered expression which is either a LoweredExpr::ExternEnum or
+/// a snapshot of one, along with the number of snapshots taken of it.
+/// Returns the expression back if it is not an extern enum.
+pub(crate) fn try_extract_extern_enum(
+    expr: LoweredExpr,
+) -> Result<(LoweredExprExternEnum, usize), LoweredExpr> {
+    match expr {
+        LoweredExpr::ExternEnum(extern_enum) => Ok((extern_enum, 0)),
+        LoweredExpr::Snapshot { expr, location } => match try_extract_extern_enum(*expr) {
+            Ok((extern_enum, n_snapshots)) => Ok((extern_enum, n_snapshots + 1)),
+            Err(expr) => Err(LoweredExpr::Snapshot { expr: Box::new(expr), location }),
+        },
+        expr => Err(expr),
+    }
 }
 
 /// Lowers a match expression on a LoweredExpr::ExternEnum lowered expression.
+/// If the extern enum is snapshotted, `n_snapshots` is the number of snapshots taken of it, and the
+/// values bound by the arms are snapshotted accordingly, so that the extern function is still
+/// called only once, as the matched function.
 pub(crate) fn lower_optimized_extern_match(
     ctx: &mut LoweringContext<'_, '_>,
     builder: &mut BlockBuilder,
     extern_enum: LoweredExprExternEnum,
+    n_snapshots: usize,
     match_arms: &[MatchArmWrapper],
     match_type: MatchKind,
 ) -> LoweringResult<LoweredExpr> {
     log::trace!("Started lowering of an optimized extern match.");
     let location = extern_enum.location;
     let concrete_variants = ctx
-        .db
         .concrete_enum_variants(extern_enum.concrete_enum_id)
         .map_err(LoweringFlowError::Failed)?;
 
     // Merge arm blocks.
-    let otherwise_variant = get_underscore_pattern_path(ctx, match_arms, match_type);
+    let otherwise_variant = get_underscore_pattern_path(ctx, match_arms, match_type, false);
 
     let variant_map = get_variant_to_arm_map(
         ctx,
@@ -882,14 +4709,24 @@ pub(crate) fn lower_optimized_extern_match(
                 .unwrap_or(match_arms.len()),
         ),
         extern_enum.concrete_enum_id,
+        concrete_variants.len(),
         match_type,
     )?;
+    report_unreachable_otherwise_arm(
+        ctx,
+        match_arms,
+        otherwise_variant.as_ref(),
+        &variant_map,
+        concrete_variants.len(),
+        match_type,
+    );
     let mut arm_var_ids = vec![];
     let mut block_ids = vec![];
 
     let variants_block_builders = concrete_variants
         .iter()
-        .map(|concrete_variant| {
+        .enumerate()
+        .map(|(variant_index, concrete_variant)| {
             let mut subscope = create_subscope(ctx, builder);
             let block_id = subscope.block_id;
             block_ids.push(block_id);
@@ -905,37 +4742,60 @@ pub(crate) fn lower_optimized_extern_match(
             // Bind the arm inputs to implicits and semantic variables.
             match_extern_arm_ref_args_bind(ctx, &mut input_vars, &extern_enum, &mut subscope);
 
-            let variant_expr = extern_facade_expr(ctx, concrete_variant.ty, input_vars, location);
+            let variant_expr = (0..n_snapshots).fold(
+                extern_facade_expr(ctx, concrete_variant.ty, input_vars, location),
+                |expr, _| LoweredExpr::Snapshot { expr: Box::new(expr), location },
+            );
 
-            let PatternPath { arm_index, pattern_index } = variant_map
-                .get(concrete_variant)
-                .or(otherwise_variant.as_ref())
-                .ok_or_else(|| {
-                    LoweringFlowError::Failed(ctx.diagnostics.report_by_location(
-                        location.lookup_intern(ctx.db),
-                        MatchError(MatchError {
-                            kind: match_type,
-                            error: MatchDiagnostic::MissingMatchArm(format!(
-                                "{}",
-                                concrete_variant.id.name(ctx.db.upcast())
-                            )),
-                        }),
-                    ))
-                })?;
+            let Some(PatternPath { arm_index, pattern_index }) =
+                variant_map.get(concrete_variant).or(otherwise_variant.as_ref())
+            else {
+                return Ok(lower_uncovered_leaf(
+                    ctx,
+                    subscope,
+                    location,
+                    match_arms.len() + variant_index,
+                ));
+            };
 
             let arm = &match_arms[*arm_index];
-            let pattern = pattern_index.map(|pattern_index| {
-                &ctx.function_body.arenas.patterns[arm.patterns[pattern_index]]
-            });
+            let (bindings, pattern) = match pattern_index {
+                Some(pattern_index) => {
+                    let (bindings, pattern) =
+                        peel_pattern_bindings(ctx, arm.patterns[*pattern_index]);
+                    (bindings, Some(ctx.function_body.arenas.patterns[pattern].clone()))
+                }
+                None => (vec![], None),
+            };
+            // The whole matched value is reconstructed from the variant and the values of the
+            // extern function, which requires the payload as a single variable. A snapshot of the
+            // value can not be reconstructed, as the payload is already a snapshot.
+            let (variant_expr, bindings_result) = if bindings.is_empty() {
+                (variant_expr, Ok(()))
+            } else if n_snapshots > 0 {
+                let diag_added = ctx.diagnostics.report(bindings[0].stable_ptr, Unsupported);
+                (variant_expr, Err(LoweringFlowError::Failed(diag_added)))
+            } else {
+                let payload = variant_expr.as_var_usage(ctx, &mut subscope)?;
+                let bindings_result = bind_matched_variant(
+                    ctx,
+                    &mut subscope,
+                    bindings,
+                    concrete_variant,
+                    payload.var_id,
+                    payload,
+                );
+                (LoweredExpr::AtVariable(payload), bindings_result)
+            };
 
-            let lowering_inner_pattern_result = match pattern {
+            let lowering_inner_pattern_result = bindings_result.and_then(|()| match pattern {
                 Some(Pattern::EnumVariant(PatternEnumVariant {
                     inner_pattern: Some(inner_pattern),
                     ..
                 })) => lower_single_pattern(
                     ctx,
                     &mut subscope,
-                    ctx.function_body.arenas.patterns[*inner_pattern].clone(),
+                    ctx.function_body.arenas.patterns[inner_pattern].clone(),
                     variant_expr,
                 ),
                 Some(
@@ -947,7 +4807,7 @@ pub(crate) fn lower_optimized_extern_match(
                     "function `get_variant_to_arm_map` should have reported every other pattern \
                      type"
                 ),
-            };
+            });
             Ok(MatchLeafBuilder {
                 arm_index: *arm_index,
                 lowering_result: lowering_inner_pattern_result,
@@ -957,6 +4817,15 @@ pub(crate) fn lower_optimized_extern_match(
         .collect::<Vec<_>>()
         .into_iter()
         .collect::<LoweringResult<Vec<_>>>()?;
+    report_missing_enum_arms(
+        ctx,
+        location,
+        extern_enum.concrete_enum_id,
+        concrete_variants
+            .iter()
+            .filter(|variant| !variant_map.contains_key(*variant) && otherwise_variant.is_none()),
+        match_type,
+    )?;
 
     let empty_match_info = MatchInfo::Extern(MatchExternInfo {
         function: extern_enum.function.lowered(ctx.db),
@@ -975,7 +4844,7 @@ pub(crate) fn lower_optimized_extern_match(
     let match_info = MatchInfo::Extern(MatchExternInfo {
         function: extern_enum.function.lowered(ctx.db),
         inputs: extern_enum.inputs,
-        arms: zip_eq(zip_eq(concrete_variants, block_ids), arm_var_ids)
+        arms: zip_eq(zip_eq(concrete_variants.iter().cloned(), block_ids), arm_var_ids)
             .map(|((variant_id, block_id), var_ids)| MatchArm {
                 arm_selector: MatchArmSelector::VariantId(variant_id),
                 block_id,
@@ -988,6 +4857,8 @@ pub(crate) fn lower_optimized_extern_match(
 }
 
 /// Represents a leaf in match tree, with the arm index it belongs to.
+/// Leaves of values that are not covered by any arm have an arm index that is out of the arms
+/// range.
 struct MatchLeafBuilder {
     arm_index: usize,
     lowering_result: LoweringResult<()>,
@@ -1003,141 +4874,503 @@ fn group_match_arms(
     variants_block_builders: Vec<MatchLeafBuilder>,
     kind: MatchKind,
 ) -> LoweringResult<Vec<SealedBlockBuilder>> {
-    variants_block_builders
+    let _span = match_lowering_span!(
+        "group_match_arms",
+        n_arms = arms.len(),
+        n_leaves = variants_block_builders.len(),
+    );
+    let collect_all_diagnostics = collect_all_match_arm_diagnostics(ctx);
+    let variants_block_builders = variants_block_builders
         .into_iter()
         .sorted_by_key(|MatchLeafBuilder { arm_index, .. }| *arm_index)
-        .chunk_by(|MatchLeafBuilder { arm_index, .. }| *arm_index)
+        .collect_vec();
+    if kind == MatchKind::Match {
+        let reachable_arms = variants_block_builders
+            .iter()
+            .map(|MatchLeafBuilder { arm_index, .. }| *arm_index)
+            .dedup()
+            .filter(|arm_index| *arm_index < arms.len())
+            .collect_vec();
+        report_duplicate_match_arm_bodies(ctx, arms, &reachable_arms);
+    }
+    let arm_groups = variants_block_builders
         .into_iter()
-        .map(|(arm_index, group)| {
-            let arm = &arms[arm_index];
-            let mut lowering_inner_pattern_results_and_subscopes = group
-                .map(|MatchLeafBuilder { lowering_result, builder, .. }| (lowering_result, builder))
-                .collect::<Vec<_>>();
-
-            // If the arm has only one pattern, there is no need to create a parent scope.
-            if lowering_inner_pattern_results_and_subscopes.len() == 1 {
-                let (lowering_inner_pattern_result, mut subscope) =
-                    lowering_inner_pattern_results_and_subscopes.pop().unwrap();
-
-                return match lowering_inner_pattern_result {
-                    Ok(_) => {
-                        // Lower the arm expression.
-                        match (arm.expr, kind) {
-                            (Some(expr), MatchKind::IfLet | MatchKind::Match) => {
-                                lower_tail_expr(ctx, subscope, expr)
-                            }
-                            (Some(expr), MatchKind::WhileLet(loop_expr_id, stable_ptr)) => {
-                                let semantic::Expr::Block(expr) =
-                                    ctx.function_body.arenas.exprs[expr].clone()
-                                else {
-                                    unreachable!("While Let expression should be a block");
-                                };
-                                let block_expr = (|| {
-                                    lower_expr_block(ctx, &mut subscope, &expr)?;
-                                    // Add recursive call.
-                                    let signature = ctx.signature.clone();
-                                    call_loop_func(
-                                        ctx,
-                                        signature,
-                                        &mut subscope,
-                                        loop_expr_id,
-                                        stable_ptr,
-                                    )
-                                })();
-
-                                lowered_expr_to_block_scope_end(ctx, subscope, block_expr)
-                            }
-                            (None, _) => lowered_expr_to_block_scope_end(
-                                ctx,
-                                subscope,
-                                Ok(LoweredExpr::Tuple { exprs: vec![], location }),
-                            ),
-                        }
-                    }
-                    Err(err) => lowering_flow_error_to_sealed_block(ctx, subscope, err),
-                }
-                .map_err(LoweringFlowError::Failed);
-            }
+        .chunk_by(|MatchLeafBuilder { arm_index, .. }| *arm_index);
+    let sealed_blocks = arm_groups.into_iter().map(|(arm_index, group)| {
+        let Some(arm) = arms.get(arm_index) else {
+            // The leaves of values that are not covered by any arm, which end with a panic. There
+            // are several such leaves when the values fall through several conditional arms.
+            let mut sealed_blocks =
+                group.map(|MatchLeafBuilder { lowering_result, builder, .. }| {
+                    let Err(err) = lowering_result else {
+                        unreachable!("Uncovered match leaves must end with a panic.");
+                    };
+                    lowering_flow_error_to_sealed_block(ctx, builder, err)
+                        .map_err(LoweringFlowError::Failed)
+                });
+            let sealed_block = sealed_blocks.next().unwrap();
+            // The other blocks end as well, so they are not merged.
+            sealed_blocks.collect::<LoweringResult<Vec<_>>>()?;
+            return sealed_block;
+        };
+        // Only the else arm of an if-let or a while-let may have no patterns.
+        if let (MatchKind::Match, [], Some(expr)) = (kind, &arm.patterns[..], arm.expr) {
+            return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+                ctx.function_body.arenas.exprs[expr].stable_ptr().untyped(),
+                MatchError(MatchError { kind, error: MatchDiagnostic::EmptyMatchArmPatterns }),
+            )));
+        }
+        let arm_expr = match kind {
+            MatchKind::Match => arm.expr.map(|expr| transform_arm_expr(ctx, expr)),
+            MatchKind::IfLet | MatchKind::WhileLet(..) => arm.expr,
+        };
+        let mut lowering_inner_pattern_results_and_subscopes = group
+            .map(|MatchLeafBuilder { lowering_result, builder, .. }| (lowering_result, builder))
+            .collect::<Vec<_>>();
+        if ctx.dedup_match_arm_diagnostics && lowering_inner_pattern_results_and_subscopes.len() > 1
+        {
+            dedup_arm_pattern_diagnostics(ctx, arm);
+        }
 
-            // A parent block builder where the variables of each pattern are introduced.
-            // The parent block should have the same semantics and changed_member_paths as any of
-            // the child blocks.
-            let mut outer_subscope = lowering_inner_pattern_results_and_subscopes[0]
-                .1
-                .sibling_block_builder(alloc_empty_block(ctx));
+        // If the arm has only one pattern, there is no need to create a parent scope.
+        if lowering_inner_pattern_results_and_subscopes.len() == 1 {
+            let (lowering_inner_pattern_result, mut subscope) =
+                lowering_inner_pattern_results_and_subscopes.pop().unwrap();
 
-            let sealed_blocks: Vec<_> = lowering_inner_pattern_results_and_subscopes
-                .into_iter()
-                .map(|(lowering_inner_pattern_result, subscope)| {
-                    // Use the first pattern for the location of the for variable assignment block.
-                    let location = arm
-                        .patterns
-                        .first()
-                        .map(|pattern| {
-                            ctx.get_location(
-                                ctx.function_body.arenas.patterns[*pattern].stable_ptr().untyped(),
-                            )
-                        })
-                        .unwrap_or(location);
-                    match lowering_inner_pattern_result {
-                        Ok(_) => lowered_expr_to_block_scope_end(
+            return match lowering_inner_pattern_result {
+                Ok(_) => {
+                    // Lower the arm expression.
+                    match (arm_expr, kind) {
+                        (Some(expr), MatchKind::Match) => in_match_arm(ctx, arm_index, |ctx| {
+                            add_catch_all_reached_call(ctx, &mut subscope, arm, location);
+                            lower_match_arm_body(ctx, subscope, expr)
+                        }),
+                        (Some(expr), MatchKind::IfLet) => lower_tail_expr(ctx, subscope, expr),
+                        (Some(expr), MatchKind::WhileLet(loop_expr_id, stable_ptr)) => {
+                            let semantic::Expr::Block(expr) =
+                                ctx.function_body.arenas.exprs[expr].clone()
+                            else {
+                                unreachable!("While Let expression should be a block");
+                            };
+                            let block_expr = (|| {
+                                lower_expr_block(ctx, &mut subscope, &expr)?;
+                                // Add recursive call.
+                                let signature = ctx.signature.clone();
+                                call_loop_func(
+                                    ctx,
+                                    signature,
+                                    &mut subscope,
+                                    loop_expr_id,
+                                    stable_ptr,
+                                )
+                            })();
+
+                            lowered_expr_to_block_scope_end(ctx, subscope, block_expr)
+                        }
+                        (None, _) => lowered_expr_to_block_scope_end(
                             ctx,
                             subscope,
                             Ok(LoweredExpr::Tuple { exprs: vec![], location }),
                         ),
-                        Err(err) => lowering_flow_error_to_sealed_block(ctx, subscope, err),
                     }
-                    .map_err(LoweringFlowError::Failed)
-                })
-                .collect::<LoweringResult<Vec<_>>>()?;
-
-            outer_subscope.merge_and_end_with_match(
-                ctx,
-                empty_match_info.clone(),
-                sealed_blocks,
-                location,
-            )?;
-            match (arm.expr, kind) {
-                (Some(expr), MatchKind::IfLet | MatchKind::Match) => {
-                    lower_tail_expr(ctx, outer_subscope, expr)
                 }
-                (Some(expr), MatchKind::WhileLet(loop_expr_id, stable_ptr)) => {
-                    let semantic::Expr::Block(expr) = ctx.function_body.arenas.exprs[expr].clone()
-                    else {
-                        unreachable!("WhileLet expression should be a block");
-                    };
-                    let block_expr = (|| {
-                        lower_expr_block(ctx, &mut outer_subscope, &expr)?;
-                        // Add recursive call.
-                        let signature = ctx.signature.clone();
-                        call_loop_func(
-                            ctx,
-                            signature,
-                            &mut outer_subscope,
-                            loop_expr_id,
-                            stable_ptr,
-                        )
-                    })();
+                Err(err) => lowering_flow_error_to_sealed_block(ctx, subscope, err),
+            }
+            .map_err(LoweringFlowError::Failed);
+        }
 
-                    lowered_expr_to_block_scope_end(ctx, outer_subscope, block_expr)
+        // A parent block builder where the variables of each pattern are introduced.
+        // The parent block should have the same semantics and changed_member_paths as any of
+        // the child blocks.
+        let mut outer_subscope = lowering_inner_pattern_results_and_subscopes[0]
+            .1
+            .sibling_block_builder(alloc_empty_block(ctx));
+
+        let sealed_blocks: Vec<_> = lowering_inner_pattern_results_and_subscopes
+            .into_iter()
+            .map(|(lowering_inner_pattern_result, subscope)| {
+                // Use the first pattern for the location of the for variable assignment block.
+                let location = arm
+                    .patterns
+                    .first()
+                    .map(|pattern| {
+                        ctx.get_location(
+                            ctx.function_body.arenas.patterns[*pattern].stable_ptr().untyped(),
+                        )
+                    })
+                    .unwrap_or(location);
+                match lowering_inner_pattern_result {
+                    Ok(_) => lowered_expr_to_block_scope_end(
+                        ctx,
+                        subscope,
+                        Ok(LoweredExpr::Tuple { exprs: vec![], location }),
+                    ),
+                    Err(err) => lowering_flow_error_to_sealed_block(ctx, subscope, err),
                 }
-                (None, _) => lowered_expr_to_block_scope_end(
-                    ctx,
-                    outer_subscope,
-                    Ok(LoweredExpr::Tuple { exprs: vec![], location }),
-                ),
+                .map_err(LoweringFlowError::Failed)
+            })
+            .collect::<LoweringResult<Vec<_>>>()?;
+
+        outer_subscope.merge_and_end_with_match(
+            ctx,
+            empty_match_info.clone(),
+            sealed_blocks,
+            location,
+        )?;
+        match (arm_expr, kind) {
+            (Some(expr), MatchKind::Match) => in_match_arm(ctx, arm_index, |ctx| {
+                add_catch_all_reached_call(ctx, &mut outer_subscope, arm, location);
+                lower_match_arm_body(ctx, outer_subscope, expr)
+            }),
+            (Some(expr), MatchKind::IfLet) => lower_tail_expr(ctx, outer_subscope, expr),
+            (Some(expr), MatchKind::WhileLet(loop_expr_id, stable_ptr)) => {
+                let semantic::Expr::Block(expr) = ctx.function_body.arenas.exprs[expr].clone()
+                else {
+                    unreachable!("WhileLet expression should be a block");
+                };
+                let block_expr = (|| {
+                    lower_expr_block(ctx, &mut outer_subscope, &expr)?;
+                    // Add recursive call.
+                    let signature = ctx.signature.clone();
+                    call_loop_func(ctx, signature, &mut outer_subscope, loop_expr_id, stable_ptr)
+                })();
+
+                lowered_expr_to_block_scope_end(ctx, outer_subscope, block_expr)
+            }
+            (None, _) => lowered_expr_to_block_scope_end(
+                ctx,
+                outer_subscope,
+                Ok(LoweredExpr::Tuple { exprs: vec![], location }),
+            ),
+        }
+        .map_err(LoweringFlowError::Failed)
+    });
+    if collect_all_diagnostics {
+        // Lower all the arms before returning the first error, so that the diagnostics of the arms
+        // after a failed one are reported as well.
+        sealed_blocks.collect_vec().into_iter().collect()
+    } else {
+        sealed_blocks.collect()
+    }
+}
+
+/// The argument of the `allow` attribute of a function allowing the duplicate match arm bodies in
+/// it.
+const DUPLICATE_MATCH_ARM_BODY_LINT: &str = "duplicate_match_arm_body";
+
+/// Reports each of the `reachable_arms` of a match whose body is identical to the body of a
+/// previous reachable arm binding the same variables, which usually signals a copy-paste bug. Arms
+/// with a guard, arms whose body is a simple value, such as a literal, and arms in code generated
+/// by a plugin are not compared.
+fn report_duplicate_match_arm_bodies(
+    ctx: &mut LoweringContext<'_, '_>,
+    arms: &[MatchArmWrapper],
+    reachable_arms: &[usize],
+) {
+    if reachable_arms.len() < 2
+        || ctx.semantic_function_id.has_attr_with_arg(
+            ctx.db.upcast(),
+            ALLOW_ATTR,
+            DUPLICATE_MATCH_ARM_BODY_LINT,
+        ) == Ok(true)
+    {
+        return;
+    }
+    let syntax_db = ctx.db.upcast();
+    let mut first_arm_bodies = UnorderedHashMap::<_, SyntaxStablePtrId>::default();
+    for arm_index in reachable_arms {
+        let arm = &arms[*arm_index];
+        let (Some(expr), None, Some(pattern)) = (arm.expr, arm.guard, arm.patterns.first()) else {
+            continue;
+        };
+        if is_simple_arm_body(ctx, expr) {
+            continue;
+        }
+        let patterns = &ctx.function_body.arenas.patterns;
+        let bindings = patterns[*pattern]
+            .variables(patterns)
+            .into_iter()
+            .map(|variable| (variable.name, variable.var.ty))
+            .sorted_by(|(name, _), (other_name, _)| name.cmp(other_name))
+            .collect_vec();
+        // The tokens of the body, ignoring whitespace and comments.
+        let stable_ptr = ctx.function_body.arenas.exprs[expr].stable_ptr().untyped();
+        let location = StableLocation::new(stable_ptr).diagnostic_location(ctx.db.upcast());
+        if location.user_location(ctx.db.upcast()) != location {
+            continue;
+        }
+        let tokens = stable_ptr
+            .lookup(syntax_db)
+            .tokens(syntax_db)
+            .map(|token| token.get_text_without_trivia(syntax_db))
+            .collect_vec();
+        let first_stable_ptr = match first_arm_bodies.entry((bindings, tokens)) {
+            Entry::Occupied(entry) => *entry.get(),
+            Entry::Vacant(entry) => {
+                entry.insert(stable_ptr);
+                continue;
             }
-            .map_err(LoweringFlowError::Failed)
+        };
+        let location = Location::new(StableLocation::new(stable_ptr)).with_note(
+            DiagnosticNote::with_location(
+                "the identical arm body".into(),
+                StableLocation::new(first_stable_ptr).diagnostic_location(ctx.db.upcast()),
+            ),
+        );
+        ctx.diagnostics.report_by_location(
+            location,
+            MatchWarning(MatchError {
+                kind: MatchKind::Match,
+                error: MatchDiagnostic::DuplicateMatchArmBody,
+            }),
+        );
+    }
+}
+
+/// Returns whether `expr` is a simple value, such as a literal or a variable, which the bodies of
+/// several arms may share without being copies of each other.
+fn is_simple_arm_body(ctx: &LoweringContext<'_, '_>, expr: semantic::ExprId) -> bool {
+    match &ctx.function_body.arenas.exprs[expr] {
+        semantic::Expr::Literal(_)
+        | semantic::Expr::StringLiteral(_)
+        | semantic::Expr::Var(_)
+        | semantic::Expr::Constant(_)
+        | semantic::Expr::Missing(_) => true,
+        semantic::Expr::Tuple(tuple) => {
+            tuple.items.iter().all(|item| is_simple_arm_body(ctx, *item))
+        }
+        semantic::Expr::EnumVariantCtor(ctor) => is_simple_arm_body(ctx, ctor.value_expr),
+        semantic::Expr::Block(block) => {
+            block.statements.is_empty()
+                && block.tail.is_none_or(|tail| is_simple_arm_body(ctx, tail))
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether all the arms of a match are lowered even after one of them fails, to report
+/// the diagnostics of all of them.
+fn collect_all_match_arm_diagnostics(ctx: &LoweringContext<'_, '_>) -> bool {
+    ctx.db
+        .get_flag(FlagId::new(ctx.db.upcast(), "collect_all_match_arm_diagnostics"))
+        .map(|flag| match *flag {
+            Flag::CollectAllMatchArmDiagnostics(collect_all) => collect_all,
+            _ => panic!("Wrong type flag `{flag:?}`."),
         })
-        .collect()
+        .unwrap_or(false)
+}
+
+/// Removes the repeated diagnostics reported on the patterns of `arm`, which may be reported once
+/// for each of the leaves of the arm, e.g. for each alternative of an or-pattern.
+fn dedup_arm_pattern_diagnostics(ctx: &mut LoweringContext<'_, '_>, arm: &MatchArmWrapper) {
+    let defs_db: &dyn DefsGroup = ctx.db.upcast();
+    let pattern_nodes = arm
+        .patterns
+        .iter()
+        .map(|pattern| {
+            ctx.function_body.arenas.patterns[*pattern]
+                .stable_ptr()
+                .untyped()
+                .lookup(defs_db.upcast())
+        })
+        .collect_vec();
+    let mut seen = UnorderedHashSet::<_>::default();
+    let diagnostics = &mut ctx.diagnostics;
+    let mut n_removed_errors = 0;
+    diagnostics.leaves.retain(|diagnostic| {
+        let node = diagnostic.location.stable_location.syntax_node(defs_db);
+        if !pattern_nodes.iter().any(|pattern_node| node.is_descendant_or_self(pattern_node))
+            || seen.insert(diagnostic.clone())
+        {
+            return true;
+        }
+        if diagnostic.severity() == Severity::Error {
+            n_removed_errors += 1;
+        }
+        false
+    });
+    diagnostics.error_count -= n_removed_errors;
+}
+
+/// Applies the match arm transform of the lowered function to the expression of a match arm, if it
+/// has one. Returns the expression to lower for the arm.
+fn transform_arm_expr(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: semantic::ExprId,
+) -> semantic::ExprId {
+    let EncapsulatingLoweringContext { function_body, match_arm_transform, .. } = &mut **ctx;
+    let Some(match_arm_transform) = match_arm_transform else {
+        return expr;
+    };
+    match_arm_transform(&mut Arc::make_mut(function_body).arenas, expr)
+}
+
+/// Lowers the body of a match arm at the end of `builder`.
+///
+/// If match arms are outlined, a body that leaves the arm only by evaluating to its value is
+/// lowered into a generated function, called from the arm with the variables the body uses.
+fn lower_match_arm_body(
+    ctx: &mut LoweringContext<'_, '_>,
+    mut builder: BlockBuilder,
+    expr: semantic::ExprId,
+) -> Maybe<SealedBlockBuilder> {
+    if !outline_match_arms(ctx) || ctx.current_loop_ctx.is_some() {
+        return lower_tail_expr(ctx, builder, expr);
+    }
+    let usage = Usages::scope_expr_usage(&ctx.function_body.arenas, expr);
+    let ty = ctx.function_body.arenas.exprs[expr].ty();
+    if usage.has_early_return || ty == corelib::never_ty(ctx.db.upcast()) {
+        return lower_tail_expr(ctx, builder, expr);
+    }
+    let lowered_expr = lower_outlined_expr(ctx, &mut builder, expr, &usage);
+    lowered_expr_to_block_scope_end(ctx, builder, lowered_expr)
+}
+
+/// Returns whether the bodies of match arms are lowered into generated functions.
+fn outline_match_arms(ctx: &LoweringContext<'_, '_>) -> bool {
+    ctx.db
+        .get_flag(FlagId::new(ctx.db.upcast(), "outline_match_arms"))
+        .map(|flag| match *flag {
+            Flag::OutlineMatchArms(outline) => outline,
+            _ => panic!("Wrong type flag `{flag:?}`."),
+        })
+        .unwrap_or(false)
+}
+
+/// If `arm` is a catch-all arm of the match at `location`, and the `emit_catch_all_events` flag is
+/// set, adds a call to `core::internal::catch_all_reached` with the id of the match to `builder`.
+///
+/// The id of a match is the offset of the match expression in its file. Only arms with a single
+/// wildcard or variable pattern are catch-all arms, as otherwise the taken pattern is not known.
+fn add_catch_all_reached_call(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    arm: &MatchArmWrapper,
+    location: LocationId,
+) {
+    let [pattern] = arm.patterns[..] else {
+        return;
+    };
+    if !matches!(
+        ctx.function_body.arenas.patterns[pattern],
+        semantic::Pattern::Otherwise(_) | semantic::Pattern::Variable(_)
+    ) || !emit_catch_all_events(ctx)
+    {
+        return;
+    }
+    let defs_db: &dyn DefsGroup = ctx.db.upcast();
+    let match_id = location
+        .lookup_intern(ctx.db)
+        .stable_location
+        .syntax_node(defs_db)
+        .span_start_without_trivia(ctx.db.upcast())
+        .as_u32();
+    let felt252_ty = ctx.db.core_info().felt252;
+    let match_id = generators::Const {
+        value: ConstValue::Int(match_id.into(), felt252_ty),
+        location,
+        ty: felt252_ty,
+    }
+    .add(ctx, &mut builder.statements);
+    generators::Call {
+        function: corelib::internal_catch_all_reached(ctx.db.upcast()).lowered(ctx.db),
+        inputs: vec![match_id],
+        coupon_input: None,
+        extra_ret_tys: vec![],
+        ret_tys: vec![corelib::unit_ty(ctx.db.upcast())],
+        location,
+    }
+    .add(ctx, &mut builder.statements);
+}
+
+/// Returns whether the catch-all arms of matches emit an event when taken. See
+/// [add_catch_all_reached_call].
+fn emit_catch_all_events(ctx: &LoweringContext<'_, '_>) -> bool {
+    ctx.db
+        .get_flag(FlagId::new(ctx.db.upcast(), "emit_catch_all_events"))
+        .map(|flag| match *flag {
+            Flag::EmitCatchAllEvents(emit) => emit,
+            _ => panic!("Wrong type flag `{flag:?}`."),
+        })
+        .unwrap_or(false)
+}
+
+/// Returns whether to lower matches on a `bool` selecting between two `felt252` values without
+/// branching. See [try_as_branchless_select].
+fn branchless_select(ctx: &LoweringContext<'_, '_>) -> bool {
+    ctx.db
+        .get_flag(FlagId::new(ctx.db.upcast(), "branchless_select"))
+        .map(|flag| match *flag {
+            Flag::BranchlessSelect(branchless) => branchless,
+            _ => panic!("Wrong type flag `{flag:?}`."),
+        })
+        .unwrap_or(false)
+}
+
+/// Runs `lower` with `arm_index` as the index of the innermost match arm enclosing the lowered
+/// expression, as returned by `match_arm_index()` calls in it.
+fn in_match_arm<T>(
+    ctx: &mut LoweringContext<'_, '_>,
+    arm_index: usize,
+    lower: impl FnOnce(&mut LoweringContext<'_, '_>) -> T,
+) -> T {
+    ctx.match_arm_indices.push(arm_index);
+    let result = lower(ctx);
+    ctx.match_arm_indices.pop();
+    result
+}
+
+/// Lowers a call to `match_arm_index()` into a constant of the index of the innermost enclosing
+/// match arm.
+pub(crate) fn lower_match_arm_index(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    stable_ptr: SyntaxStablePtrId,
+    location: LocationId,
+) -> LoweringResult<LoweredExpr> {
+    let arm_index = match ctx.match_arm_indices.last() {
+        Some(arm_index) if bind_match_arm_index(ctx) => *arm_index,
+        _ => {
+            return Err(LoweringFlowError::Failed(
+                ctx.diagnostics.report(stable_ptr, MatchArmIndexUnavailable),
+            ));
+        }
+    };
+    let felt252_ty = ctx.db.core_info().felt252;
+    Ok(LoweredExpr::AtVariable(
+        generators::Const {
+            value: ConstValue::Int(arm_index.into(), felt252_ty),
+            location,
+            ty: felt252_ty,
+        }
+        .add(ctx, &mut builder.statements),
+    ))
+}
+
+/// Returns whether `match_arm_index()` may be called in match arms.
+fn bind_match_arm_index(ctx: &LoweringContext<'_, '_>) -> bool {
+    ctx.db
+        .get_flag(FlagId::new(ctx.db.upcast(), "bind_match_arm_index"))
+        .map(|flag| match *flag {
+            Flag::BindMatchArmIndex(bind) => bind,
+            _ => panic!("Wrong type flag `{flag:?}`."),
+        })
+        .unwrap_or(false)
 }
 
 /// Lowers the [semantic::MatchArm] of an expression of type [semantic::ExprMatch] where the matched
 /// expression is a felt252.
+///
+/// `prev_diff` is the difference of `match_input` from the previously checked literal, along with
+/// the value of that literal, if any. `matched_value` is the matched value before its conversion
+/// into `match_input`, bound by a variable pattern of the wildcard arm.
+#[allow(clippy::too_many_arguments)]
 fn lower_expr_felt252_arm(
     ctx: &mut LoweringContext<'_, '_>,
     expr: &semantic::ExprMatch,
     match_input: VarUsage,
+    matched_value: VarUsage,
+    prev_diff: Option<(VarUsage, BigInt)>,
     builder: &mut BlockBuilder,
     arm_index: usize,
     pattern_index: usize,
@@ -1148,6 +5381,8 @@ fn lower_expr_felt252_arm(
             ctx,
             expr,
             match_input,
+            matched_value,
+            prev_diff,
             builder,
             arm_index + 1,
             0,
@@ -1157,7 +5392,6 @@ fn lower_expr_felt252_arm(
 
     let location = ctx.get_location(expr.stable_ptr.untyped());
     let arm = &expr.arms[arm_index];
-    let semantic_db = ctx.db.upcast();
 
     let main_block = create_subscope(ctx, builder);
     let main_block_id = main_block.block_id;
@@ -1175,49 +5409,141 @@ fn lower_expr_felt252_arm(
             }),
         )));
     };
+    let literal = literal.clone();
+
+    let if_input =
+        lower_felt252_diff(ctx, builder, match_input, prev_diff.as_ref(), &literal, location)?;
+    let match_info =
+        felt252_is_zero_match_info(ctx, if_input, main_block_id, block_else_id, location);
+    branches_block_builders.push(MatchLeafBuilder {
+        arm_index,
+        lowering_result: Ok(()),
+        builder: main_block,
+    });
+    let otherwise_arm_index = felt252_otherwise_arm_index(ctx, expr);
+    if pattern_index + 1 == expr.arms[arm_index].patterns.len()
+        && arm_index + 1 == otherwise_arm_index
+    {
+        branches_block_builders.push(lower_otherwise_leaf(
+            ctx,
+            expr,
+            else_block,
+            otherwise_arm_index,
+            matched_value,
+            location,
+        ));
+    } else {
+        let match_info = lower_expr_felt252_arm(
+            ctx,
+            expr,
+            match_input,
+            matched_value,
+            Some((if_input, literal.value)),
+            &mut else_block,
+            arm_index,
+            pattern_index + 1,
+            branches_block_builders,
+        )?;
+
+        // we can use finalize here because the else block is an inner block of the match expression
+        // and does not have sibling block it goes to.
+        else_block.finalize(ctx, FlatBlockEnd::Match { info: match_info });
+    }
+    Ok(match_info)
+}
+
+/// Returns whether the constants subtracted from the input of felt252 matches are shared by all
+/// the matches of the function.
+fn pool_match_consts(ctx: &LoweringContext<'_, '_>) -> bool {
+    ctx.db
+        .get_flag(FlagId::new(ctx.db.upcast(), "pool_match_consts"))
+        .map(|flag| match *flag {
+            Flag::PoolMatchConsts(pool) => pool,
+            _ => panic!("Wrong type flag `{flag:?}`."),
+        })
+        .unwrap_or(false)
+}
 
+/// Returns a felt252 value which is zero if and only if `match_input` equals the value of
+/// `literal`, for matching the literal using `felt252_is_zero`.
+///
+/// If `literal` follows the previously checked literal, the value is computed by subtracting 1 from
+/// the difference of `match_input` from it (`prev_diff`), instead of from `match_input`.
+fn lower_felt252_diff(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    match_input: VarUsage,
+    prev_diff: Option<&(VarUsage, BigInt)>,
+    literal: &semantic::ExprLiteral,
+    location: LocationId,
+) -> LoweringResult<VarUsage> {
+    if literal.value.is_zero() {
+        return Ok(match_input);
+    }
+    let (minuend, subtrahend) = match prev_diff {
+        Some((prev_diff, prev_value)) if prev_value + 1 == literal.value => {
+            (*prev_diff, BigInt::from(1))
+        }
+        _ => (match_input, literal.value.clone()),
+    };
     let felt252_ty = ctx.db.core_info().felt252;
-    let if_input = if literal.value == 0.into() {
-        match_input
+    // TODO(TomerStarkware): Use the same type of literal as the input, without the cast to
+    // felt252.
+    let lowered_arm_val = if pool_match_consts(ctx) {
+        ctx.pooled_const(
+            ConstValue::Int(subtrahend, felt252_ty),
+            felt252_ty,
+            ctx.get_location(literal.stable_ptr.untyped()),
+        )
     } else {
-        // TODO(TomerStarkware): Use the same type of literal as the input, without the cast to
-        // felt252.
-        let lowered_arm_val = lower_expr_literal(
+        lower_expr_literal(
             ctx,
             &semantic::ExprLiteral {
                 stable_ptr: literal.stable_ptr,
-                value: literal.value.clone(),
+                value: subtrahend,
                 ty: felt252_ty,
             },
             builder,
         )?
-        .as_var_usage(ctx, builder)?;
-
-        let call_result = generators::Call {
-            function: corelib::felt252_sub(ctx.db.upcast()).lowered(ctx.db),
-            inputs: vec![match_input, lowered_arm_val],
-            coupon_input: None,
-            extra_ret_tys: vec![],
-            ret_tys: vec![felt252_ty],
-            location,
-        }
-        .add(ctx, &mut builder.statements);
-        call_result.returns.into_iter().next().unwrap()
+        .as_var_usage(ctx, builder)?
     };
 
+    let call_result = generators::Call {
+        function: corelib::felt252_sub(ctx.db.upcast()).lowered(ctx.db),
+        inputs: vec![minuend, lowered_arm_val],
+        coupon_input: None,
+        extra_ret_tys: vec![],
+        ret_tys: vec![felt252_ty],
+        location,
+    }
+    .add(ctx, &mut builder.statements);
+    Ok(call_result.returns.into_iter().next().unwrap())
+}
+
+/// Returns the info of a `felt252_is_zero` match on `input`, going to `zero_block_id` if it is zero
+/// and to `nonzero_block_id` otherwise.
+fn felt252_is_zero_match_info(
+    ctx: &mut LoweringContext<'_, '_>,
+    input: VarUsage,
+    zero_block_id: BlockId,
+    nonzero_block_id: BlockId,
+    location: LocationId,
+) -> MatchInfo {
+    let semantic_db = ctx.db.upcast();
+    let felt252_ty = ctx.db.core_info().felt252;
     let non_zero_type = corelib::core_nonzero_ty(semantic_db, felt252_ty);
-    let else_block_input_var_id = ctx.new_var(VarRequest { ty: non_zero_type, location });
+    let nonzero_var_id = ctx.new_var(VarRequest { ty: non_zero_type, location });
 
-    let match_info = MatchInfo::Extern(MatchExternInfo {
+    MatchInfo::Extern(MatchExternInfo {
         function: corelib::core_felt252_is_zero(semantic_db).lowered(ctx.db),
-        inputs: vec![if_input],
+        inputs: vec![input],
         arms: vec![
             MatchArm {
                 arm_selector: MatchArmSelector::VariantId(corelib::jump_nz_zero_variant(
                     semantic_db,
                     felt252_ty,
                 )),
-                block_id: main_block_id,
+                block_id: zero_block_id,
                 var_ids: vec![],
             },
             MatchArm {
@@ -1225,53 +5551,24 @@ fn lower_expr_felt252_arm(
                     semantic_db,
                     felt252_ty,
                 )),
-                block_id: block_else_id,
-                var_ids: vec![else_block_input_var_id],
+                block_id: nonzero_block_id,
+                var_ids: vec![nonzero_var_id],
             },
         ],
         location,
-    });
-    branches_block_builders.push(MatchLeafBuilder {
-        arm_index,
-        lowering_result: Ok(()),
-        builder: main_block,
-    });
-    if pattern_index + 1 == expr.arms[arm_index].patterns.len() && arm_index == expr.arms.len() - 2
-    {
-        branches_block_builders.push(MatchLeafBuilder {
-            arm_index: arm_index + 1,
-            lowering_result: Ok(()),
-            builder: else_block,
-        });
-    } else {
-        let match_info = lower_expr_felt252_arm(
-            ctx,
-            expr,
-            match_input,
-            &mut else_block,
-            arm_index,
-            pattern_index + 1,
-            branches_block_builders,
-        )?;
-
-        // we can use finalize here because the else block is an inner block of the match expression
-        // and does not have sibling block it goes to.
-        else_block.finalize(ctx, FlatBlockEnd::Match { info: match_info });
-    }
-    Ok(match_info)
+    })
 }
 
 /// lowers an expression of type [semantic::ExprMatch] where the matched expression is a felt252,
 /// using an index enum.
 fn lower_expr_match_index_enum(
     ctx: &mut LoweringContext<'_, '_>,
-    expr: &semantic::ExprMatch,
+    location: LocationId,
     match_input: VarUsage,
     builder: &BlockBuilder,
     literals_to_arm_map: &UnorderedHashMap<usize, usize>,
     branches_block_builders: &mut Vec<MatchLeafBuilder>,
 ) -> LoweringResult<MatchInfo> {
-    let location = ctx.get_location(expr.stable_ptr.untyped());
     let semantic_db = ctx.db.upcast();
     let unit_type = unit_ty(semantic_db);
     let mut arm_var_ids = vec![];
@@ -1314,22 +5611,38 @@ fn lower_expr_match_index_enum(
 
 /// Lowers an expression of type [semantic::ExprMatch] where the matched expression is a felt252.
 /// using an index enum to create a jump table.
+///
+/// If the matched expression is a bounded int, `bounded_int_range` is its range of values, and the
+/// match is always lowered into a jump table, as no cast to felt252 is required.
 fn lower_expr_match_felt252(
     ctx: &mut LoweringContext<'_, '_>,
     expr: &semantic::ExprMatch,
     mut match_input: VarUsage,
     builder: &mut BlockBuilder,
     convert_function: Option<semantic::FunctionId>,
+    bounded_int_range: Option<(BigInt, BigInt)>,
 ) -> LoweringResult<LoweredExpr> {
     log::trace!("Lowering a match-felt252 expression.");
+    let span = match_lowering_span!(
+        "lower_expr_match_felt252",
+        n_arms = expr.arms.len(),
+        n_variants = tracing::field::Empty,
+        strategy = tracing::field::Empty,
+    );
+    let location = ctx.get_location(expr.stable_ptr.untyped());
     if expr.arms.is_empty() {
-        return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
-            expr.stable_ptr.untyped(),
-            MatchError(MatchError {
-                kind: MatchKind::Match,
-                error: MatchDiagnostic::NonExhaustiveMatchFelt252,
-            }),
-        )));
+        report_non_exhaustive_match(
+            ctx,
+            non_exhaustive_numeric_match_location(ctx, expr),
+            MatchDiagnostic::NonExhaustiveMatchFelt252(wildcard_arm_suggestion(ctx, expr)),
+            MatchKind::Match,
+        )?;
+        return Err(lower_uncovered_value_panic(ctx, builder, location));
+    }
+    if expr.arms.iter().flat_map(|arm| &arm.patterns).any(|pattern| {
+        matches!(ctx.function_body.arenas.patterns[*pattern], semantic::Pattern::Range(_))
+    }) {
+        return lower_expr_match_numeric_ranges(ctx, expr, match_input, builder, bounded_int_range);
     }
     let mut max = 0;
     let mut literals_to_arm_map = UnorderedHashMap::default();
@@ -1338,39 +5651,53 @@ fn lower_expr_match_felt252(
         for pattern in arm.patterns.iter() {
             let pattern = &ctx.function_body.arenas.patterns[*pattern];
             if otherwise_exist {
-                return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+                return Err(LoweringFlowError::Failed(report_unreachable_match_arm(
+                    ctx,
                     pattern.stable_ptr().untyped(),
-                    MatchError(MatchError {
-                        kind: MatchKind::Match,
-                        error: MatchDiagnostic::UnreachableMatchArm,
-                    }),
+                    MatchKind::Match,
                 )));
             }
             match pattern {
                 semantic::Pattern::Literal(semantic::PatternLiteral { literal, .. }) => {
+                    if let Some((min, max)) = &bounded_int_range {
+                        if literal.value < *min || literal.value > *max {
+                            return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+                                pattern.stable_ptr().untyped(),
+                                MatchError(MatchError {
+                                    kind: MatchKind::Match,
+                                    error: MatchDiagnostic::LiteralOutOfRangeForMatch {
+                                        min: min.clone(),
+                                        max: max.clone(),
+                                    },
+                                }),
+                            )));
+                        }
+                    }
                     let Some(literal) = literal.value.to_usize() else {
                         return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
-                            expr.stable_ptr.untyped(),
+                            pattern.stable_ptr().untyped(),
                             MatchError(MatchError {
                                 kind: MatchKind::Match,
-                                error: MatchDiagnostic::UnsupportedMatchArmNonSequential,
+                                error: MatchDiagnostic::UnsupportedMatchArmNonSequential {
+                                    missing: None,
+                                },
                             }),
                         )));
                     };
                     if otherwise_exist || literals_to_arm_map.insert(literal, arm_index).is_some() {
-                        return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+                        return Err(LoweringFlowError::Failed(report_unreachable_match_arm(
+                            ctx,
                             pattern.stable_ptr().untyped(),
-                            MatchError(MatchError {
-                                kind: MatchKind::Match,
-                                error: MatchDiagnostic::UnreachableMatchArm,
-                            }),
+                            MatchKind::Match,
                         )));
                     }
                     if literal > max {
                         max = literal;
                     }
                 }
-                semantic::Pattern::Otherwise(_) => otherwise_exist = true,
+                semantic::Pattern::Otherwise(_) | semantic::Pattern::Variable(_) => {
+                    otherwise_exist = true
+                }
                 _ => {
                     return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
                         pattern.stable_ptr().untyped(),
@@ -1384,27 +5711,353 @@ fn lower_expr_match_felt252(
         }
     }
 
-    if !otherwise_exist {
-        return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
-            expr.stable_ptr.untyped(),
-            MatchError(MatchError {
-                kind: MatchKind::Match,
-                error: MatchDiagnostic::NonExhaustiveMatchFelt252,
-            }),
+    // Whether the literals cover all the values of the matched bounded int, in which case it can be
+    // used as the index of the jump table directly.
+    let covers_bounded_int_range = bounded_int_range.as_ref().is_some_and(|(min, max_value)| {
+        min.is_zero() && *max_value == max.into() && literals_to_arm_map.len() == max + 1
+    });
+    // Whether the literals cover all the values of the matched unsigned integer, in which case the
+    // downcast of the value into the range of the jump table can not fail.
+    let covers_type_range = convert_function.is_some() && literals_to_arm_map.len() == max + 1 && {
+        let ty = ctx.function_body.arenas.exprs[expr.matched_expr].ty();
+        let semantic_db = ctx.db.upcast();
+        corelib::validate_literal(semantic_db, ty, &BigInt::from(-1)).is_err()
+            && corelib::validate_literal(semantic_db, ty, &BigInt::from(max + 1)).is_err()
+    };
+    if (covers_bounded_int_range || covers_type_range) && otherwise_exist {
+        let otherwise_pattern = expr.arms.last().unwrap().patterns.iter().find(|pattern| {
+            matches!(
+                ctx.function_body.arenas.patterns[**pattern],
+                semantic::Pattern::Otherwise(_) | semantic::Pattern::Variable(_)
+            )
+        });
+        let otherwise_pattern = &ctx.function_body.arenas.patterns[*otherwise_pattern.unwrap()];
+        return Err(LoweringFlowError::Failed(report_unreachable_match_arm(
+            ctx,
+            otherwise_pattern.stable_ptr().untyped(),
+            MatchKind::Match,
+        )));
+    }
+    if !otherwise_exist && !covers_bounded_int_range && !covers_type_range {
+        report_non_exhaustive_match(
+            ctx,
+            non_exhaustive_numeric_match_location(ctx, expr),
+            MatchDiagnostic::NonExhaustiveMatchFelt252(wildcard_arm_suggestion(ctx, expr)),
+            MatchKind::Match,
+        )?;
+    }
+    let otherwise_arm_index = felt252_otherwise_arm_index(ctx, expr);
+    span.record("n_variants", literals_to_arm_map.len());
+    if max + 1 != literals_to_arm_map.len() {
+        return Err(LoweringFlowError::Failed(report_non_sequential_literal(
+            ctx,
+            expr,
+            &literals_to_arm_map,
+        )));
+    };
+
+    let mut arms_vec = vec![];
+
+    let empty_match_info = MatchInfo::Extern(MatchExternInfo {
+        function: corelib::core_felt252_is_zero(ctx.db.upcast()).lowered(ctx.db),
+        inputs: vec![match_input],
+        arms: vec![],
+        location,
+    });
+
+    let felt252_ty = ctx.db.core_info().felt252;
+
+    if covers_bounded_int_range {
+        span.record("strategy", "direct_jump_table");
+        ctx.match_strategies.record(MatchStrategy::Felt252JumpTable);
+        let match_info = lower_expr_match_index_enum(
+            ctx,
+            location,
+            match_input,
+            builder,
+            &literals_to_arm_map,
+            &mut arms_vec,
+        )?;
+        let sealed_blocks = group_match_arms(
+            ctx,
+            empty_match_info,
+            location,
+            &expr.arms.iter().map(|arm| arm.into()).collect_vec(),
+            arms_vec,
+            MatchKind::Match,
+        )?;
+        return builder.merge_and_end_with_match(ctx, match_info, sealed_blocks, location);
+    }
+
+    // max +2 is the number of arms in the match.
+    if bounded_int_range.is_none()
+        && max + 2 < numeric_match_optimization_threshold(ctx, convert_function.is_some())
+    {
+        span.record("strategy", "if_chain");
+        ctx.match_strategies.record(MatchStrategy::Felt252IfChain);
+        let matched_value = match_input;
+        if let Some(convert_function) = convert_function {
+            let call_result = generators::Call {
+                function: convert_function.lowered(ctx.db),
+                inputs: vec![match_input],
+                coupon_input: None,
+                extra_ret_tys: vec![],
+                ret_tys: vec![felt252_ty],
+                location,
+            }
+            .add(ctx, &mut builder.statements);
+
+            match_input = call_result.returns.into_iter().next().unwrap();
+        }
+
+        let match_info = lower_expr_felt252_arm(
+            ctx,
+            expr,
+            match_input,
+            matched_value,
+            None,
+            builder,
+            0,
+            0,
+            &mut arms_vec,
+        )?;
+
+        let sealed_blocks = group_match_arms(
+            ctx,
+            empty_match_info,
+            location,
+            &expr.arms.iter().map(|arm| arm.into()).collect_vec(),
+            arms_vec,
+            MatchKind::Match,
+        )?;
+
+        return builder.merge_and_end_with_match(ctx, match_info, sealed_blocks, location);
+    }
+
+    span.record("strategy", "jump_table");
+    ctx.match_strategies.record(MatchStrategy::Felt252JumpTable);
+    let semantic_db = ctx.db.upcast();
+    let bounded_int_ty = corelib::bounded_int_ty(semantic_db, 0.into(), max.into());
+
+    let ty = ctx.function_body.arenas.exprs[expr.matched_expr].ty();
+    let function_id = corelib::core_downcast(semantic_db, ty, bounded_int_ty).lowered(ctx.db);
+
+    let in_range_block_input_var_id = ctx.new_var(VarRequest { ty: bounded_int_ty, location });
+
+    let in_range_block = create_subscope(ctx, builder);
+    let in_range_block_id = in_range_block.block_id;
+    let inner_match_info = lower_expr_match_index_enum(
+        ctx,
+        location,
+        VarUsage { var_id: in_range_block_input_var_id, location: match_input.location },
+        &in_range_block,
+        &literals_to_arm_map,
+        &mut arms_vec,
+    )?;
+    in_range_block.finalize(ctx, FlatBlockEnd::Match { info: inner_match_info });
+
+    let otherwise_block = create_subscope(ctx, builder);
+    let otherwise_block_id = otherwise_block.block_id;
+
+    arms_vec.push(if covers_type_range {
+        lower_unreachable_leaf(ctx, otherwise_block, location, otherwise_arm_index)
+    } else {
+        lower_otherwise_leaf(ctx, expr, otherwise_block, otherwise_arm_index, match_input, location)
+    });
+
+    // The placement of the default block is not configurable: the arms of an extern match follow
+    // the order of the branches of the libfunc, so `Option::None` is always the second arm, and the
+    // blocks are later reordered by the arms that reach them, regardless of their creation order.
+    let match_info = MatchInfo::Extern(MatchExternInfo {
+        function: function_id,
+        inputs: vec![match_input],
+        arms: vec![
+            MatchArm {
+                arm_selector: MatchArmSelector::VariantId(corelib::option_some_variant(
+                    semantic_db,
+                    bounded_int_ty,
+                )),
+                block_id: in_range_block_id,
+                var_ids: vec![in_range_block_input_var_id],
+            },
+            MatchArm {
+                arm_selector: MatchArmSelector::VariantId(corelib::option_none_variant(
+                    semantic_db,
+                    bounded_int_ty,
+                )),
+                block_id: otherwise_block_id,
+                var_ids: vec![],
+            },
+        ],
+        location,
+    });
+    let sealed_blocks = group_match_arms(
+        ctx,
+        empty_match_info,
+        location,
+        &expr.arms.iter().map(|arm| arm.into()).collect_vec(),
+        arms_vec,
+        MatchKind::Match,
+    )?;
+    builder.merge_and_end_with_match(ctx, match_info, sealed_blocks, location)
+}
+
+/// Lowers a numeric match with range patterns into a chain of range checks.
+///
+/// The literal and range patterns are checked in order, each by a downcast of the matched value
+/// into the bounded int of the values it covers, so that the lowering does not grow with the width
+/// of the ranges.
+fn lower_expr_match_numeric_ranges(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+    match_input: VarUsage,
+    builder: &mut BlockBuilder,
+    bounded_int_range: Option<(BigInt, BigInt)>,
+) -> LoweringResult<LoweredExpr> {
+    let _span = match_lowering_span!("lower_expr_match_numeric_ranges", n_arms = expr.arms.len());
+    let location = ctx.get_location(expr.stable_ptr.untyped());
+    // The inclusive bounds of the values of each pattern, along with the index of its arm.
+    let mut ranges: Vec<(BigInt, BigInt, usize)> = vec![];
+    let mut otherwise_exist = false;
+    for (arm_index, arm) in expr.arms.iter().enumerate() {
+        for pattern in arm.patterns.iter() {
+            let pattern = &ctx.function_body.arenas.patterns[*pattern];
+            if otherwise_exist {
+                return Err(LoweringFlowError::Failed(report_unreachable_match_arm(
+                    ctx,
+                    pattern.stable_ptr().untyped(),
+                    MatchKind::Match,
+                )));
+            }
+            let (start, end) = match pattern {
+                semantic::Pattern::Literal(semantic::PatternLiteral { literal, .. }) => {
+                    (literal.value.clone(), literal.value.clone())
+                }
+                semantic::Pattern::Range(semantic::PatternRange {
+                    start,
+                    end,
+                    is_inclusive,
+                    ..
+                }) => {
+                    let end = if *is_inclusive { end.value.clone() } else { &end.value - 1 };
+                    (start.value.clone(), end)
+                }
+                semantic::Pattern::Otherwise(_) | semantic::Pattern::Variable(_) => {
+                    otherwise_exist = true;
+                    continue;
+                }
+                _ => {
+                    return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+                        pattern.stable_ptr().untyped(),
+                        MatchError(MatchError {
+                            kind: MatchKind::Match,
+                            error: MatchDiagnostic::UnsupportedMatchArmNotALiteral,
+                        }),
+                    )));
+                }
+            };
+            if let Some((min, max)) = &bounded_int_range {
+                if start < *min || end > *max {
+                    return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
+                        pattern.stable_ptr().untyped(),
+                        MatchError(MatchError {
+                            kind: MatchKind::Match,
+                            error: MatchDiagnostic::LiteralOutOfRangeForMatch {
+                                min: min.clone(),
+                                max: max.clone(),
+                            },
+                        }),
+                    )));
+                }
+            }
+            // The ranges are checked in order, so the values of overlapping ranges go to the
+            // first arm covering them. Only a range without values of its own is unreachable.
+            if is_range_covered(&ranges, &start, &end) {
+                return Err(LoweringFlowError::Failed(report_unreachable_match_arm(
+                    ctx,
+                    pattern.stable_ptr().untyped(),
+                    MatchKind::Match,
+                )));
+            }
+            ranges.push((start, end, arm_index));
+        }
+    }
+
+    // Whether the patterns cover all the values of the matched type, in which case the last range
+    // check can not fail.
+    let ty = ctx.function_body.arenas.exprs[expr.matched_expr].ty();
+    let min = ranges.iter().map(|(start, ..)| start).min().unwrap();
+    let max = ranges.iter().map(|(_, end, _)| end).max().unwrap();
+    let covers_type_range = is_range_covered(&ranges, min, max) && {
+        let semantic_db = ctx.db.upcast();
+        corelib::validate_literal(semantic_db, ty, &(min - 1)).is_err()
+            && corelib::validate_literal(semantic_db, ty, &(max + 1)).is_err()
+    };
+    if covers_type_range && otherwise_exist {
+        let otherwise_pattern = expr.arms.last().unwrap().patterns.iter().find(|pattern| {
+            matches!(
+                ctx.function_body.arenas.patterns[**pattern],
+                semantic::Pattern::Otherwise(_) | semantic::Pattern::Variable(_)
+            )
+        });
+        let otherwise_pattern = &ctx.function_body.arenas.patterns[*otherwise_pattern.unwrap()];
+        return Err(LoweringFlowError::Failed(report_unreachable_match_arm(
+            ctx,
+            otherwise_pattern.stable_ptr().untyped(),
+            MatchKind::Match,
         )));
     }
-    if max + 1 != literals_to_arm_map.len() {
-        return Err(LoweringFlowError::Failed(ctx.diagnostics.report(
-            expr.stable_ptr.untyped(),
-            MatchError(MatchError {
-                kind: MatchKind::Match,
-                error: MatchDiagnostic::UnsupportedMatchArmNonSequential,
-            }),
-        )));
-    };
-    let location = ctx.get_location(expr.stable_ptr.untyped());
+    if !otherwise_exist && !covers_type_range {
+        report_non_exhaustive_match(
+            ctx,
+            non_exhaustive_numeric_match_location(ctx, expr),
+            MatchDiagnostic::NonExhaustiveMatchFelt252(wildcard_arm_suggestion(ctx, expr)),
+            MatchKind::Match,
+        )?;
+    }
+    let otherwise_arm_index = felt252_otherwise_arm_index(ctx, expr);
+    ctx.match_strategies.record(MatchStrategy::Felt252IfChain);
 
+    // Each range check is the end of the block where the previous one failed.
     let mut arms_vec = vec![];
+    let mut match_info = None;
+    let mut out_of_range_block: Option<BlockBuilder> = None;
+    for (start, end, arm_index) in ranges {
+        let parent_block = out_of_range_block.as_ref().unwrap_or(builder);
+        let in_range_block = create_subscope(ctx, parent_block);
+        let next_block = create_subscope(ctx, parent_block);
+        let range_check_info = numeric_range_check_match_info(
+            ctx,
+            match_input,
+            start,
+            end,
+            in_range_block.block_id,
+            next_block.block_id,
+            location,
+        );
+        arms_vec.push(MatchLeafBuilder {
+            arm_index,
+            lowering_result: Ok(()),
+            builder: in_range_block,
+        });
+        match out_of_range_block.replace(next_block) {
+            Some(block) => block.finalize(ctx, FlatBlockEnd::Match { info: range_check_info }),
+            None => match_info = Some(range_check_info),
+        }
+    }
+    let out_of_range_block = out_of_range_block.unwrap();
+    arms_vec.push(if covers_type_range {
+        lower_unreachable_leaf(ctx, out_of_range_block, location, otherwise_arm_index)
+    } else {
+        lower_otherwise_leaf(
+            ctx,
+            expr,
+            out_of_range_block,
+            otherwise_arm_index,
+            match_input,
+            location,
+        )
+    });
 
     let empty_match_info = MatchInfo::Extern(MatchExternInfo {
         function: corelib::core_felt252_is_zero(ctx.db.upcast()).lowered(ctx.db),
@@ -1412,72 +6065,50 @@ fn lower_expr_match_felt252(
         arms: vec![],
         location,
     });
+    let sealed_blocks = group_match_arms(
+        ctx,
+        empty_match_info,
+        location,
+        &expr.arms.iter().map(|arm| arm.into()).collect_vec(),
+        arms_vec,
+        MatchKind::Match,
+    )?;
+    builder.merge_and_end_with_match(ctx, match_info.unwrap(), sealed_blocks, location)
+}
 
-    let felt252_ty = ctx.db.core_info().felt252;
-
-    // max +2 is the number of arms in the match.
-    if max + 2 < numeric_match_optimization_threshold(ctx, convert_function.is_some()) {
-        if let Some(convert_function) = convert_function {
-            let call_result = generators::Call {
-                function: convert_function.lowered(ctx.db),
-                inputs: vec![match_input],
-                coupon_input: None,
-                extra_ret_tys: vec![],
-                ret_tys: vec![felt252_ty],
-                location,
-            }
-            .add(ctx, &mut builder.statements);
-
-            match_input = call_result.returns.into_iter().next().unwrap();
+/// Returns true if all the values from `start` to `end` (inclusive) are in one of `ranges`.
+fn is_range_covered(ranges: &[(BigInt, BigInt, usize)], start: &BigInt, end: &BigInt) -> bool {
+    // The smallest value from `start` that is not covered by the ranges seen so far.
+    let mut uncovered = start.clone();
+    for (range_start, range_end, _) in ranges.iter().sorted_by_key(|(start, ..)| start) {
+        if *range_start > uncovered {
+            break;
+        }
+        if *range_end >= uncovered {
+            uncovered = range_end + 1;
         }
-
-        let match_info =
-            lower_expr_felt252_arm(ctx, expr, match_input, builder, 0, 0, &mut arms_vec)?;
-
-        let sealed_blocks = group_match_arms(
-            ctx,
-            empty_match_info,
-            location,
-            &expr.arms.iter().map(|arm| arm.into()).collect_vec(),
-            arms_vec,
-            MatchKind::Match,
-        )?;
-
-        return builder.merge_and_end_with_match(ctx, match_info, sealed_blocks, location);
     }
+    uncovered > *end
+}
 
+/// Returns the info of a match on the downcast of `input` into the bounded int from `start` to
+/// `end`, going to `in_range_block_id` if it succeeds and to `out_of_range_block_id` otherwise.
+fn numeric_range_check_match_info(
+    ctx: &mut LoweringContext<'_, '_>,
+    input: VarUsage,
+    start: BigInt,
+    end: BigInt,
+    in_range_block_id: BlockId,
+    out_of_range_block_id: BlockId,
+    location: LocationId,
+) -> MatchInfo {
     let semantic_db = ctx.db.upcast();
-    let bounded_int_ty = corelib::bounded_int_ty(semantic_db, 0.into(), max.into());
-
-    let ty = ctx.function_body.arenas.exprs[expr.matched_expr].ty();
-    let function_id = corelib::core_downcast(semantic_db, ty, bounded_int_ty).lowered(ctx.db);
-
-    let in_range_block_input_var_id = ctx.new_var(VarRequest { ty: bounded_int_ty, location });
-
-    let in_range_block = create_subscope(ctx, builder);
-    let in_range_block_id = in_range_block.block_id;
-    let inner_match_info = lower_expr_match_index_enum(
-        ctx,
-        expr,
-        VarUsage { var_id: in_range_block_input_var_id, location: match_input.location },
-        &in_range_block,
-        &literals_to_arm_map,
-        &mut arms_vec,
-    )?;
-    in_range_block.finalize(ctx, FlatBlockEnd::Match { info: inner_match_info });
-
-    let otherwise_block = create_subscope(ctx, builder);
-    let otherwise_block_id = otherwise_block.block_id;
-
-    arms_vec.push(MatchLeafBuilder {
-        arm_index: expr.arms.len() - 1,
-        lowering_result: Ok(()),
-        builder: otherwise_block,
-    });
-
-    let match_info = MatchInfo::Extern(MatchExternInfo {
-        function: function_id,
-        inputs: vec![match_input],
+    let bounded_int_ty = corelib::bounded_int_ty(semantic_db, start, end);
+    let input_ty = ctx.variables[input.var_id].ty;
+    let in_range_var_id = ctx.new_var(VarRequest { ty: bounded_int_ty, location });
+    MatchInfo::Extern(MatchExternInfo {
+        function: corelib::core_downcast(semantic_db, input_ty, bounded_int_ty).lowered(ctx.db),
+        inputs: vec![input],
         arms: vec![
             MatchArm {
                 arm_selector: MatchArmSelector::VariantId(corelib::option_some_variant(
@@ -1485,28 +6116,320 @@ fn lower_expr_match_felt252(
                     bounded_int_ty,
                 )),
                 block_id: in_range_block_id,
-                var_ids: vec![in_range_block_input_var_id],
+                var_ids: vec![in_range_var_id],
             },
             MatchArm {
                 arm_selector: MatchArmSelector::VariantId(corelib::option_none_variant(
                     semantic_db,
                     bounded_int_ty,
                 )),
-                block_id: otherwise_block_id,
+                block_id: out_of_range_block_id,
                 var_ids: vec![],
             },
         ],
         location,
+    })
+}
+
+/// Returns the index of the arm of the values that are not covered by any literal in a numeric
+/// match. If there is no wildcard arm, it is out of the arms range, and the values are lowered
+/// into a panic.
+fn felt252_otherwise_arm_index(ctx: &LoweringContext<'_, '_>, expr: &semantic::ExprMatch) -> usize {
+    let otherwise_exist = expr.arms.last().is_some_and(|arm| {
+        arm.patterns.iter().any(|pattern| {
+            matches!(
+                ctx.function_body.arenas.patterns[*pattern],
+                semantic::Pattern::Otherwise(_) | semantic::Pattern::Variable(_)
+            )
+        })
     });
-    let sealed_blocks = group_match_arms(
+    if otherwise_exist { expr.arms.len() - 1 } else { expr.arms.len() }
+}
+
+/// Returns the leaf of the values of a numeric match that are not covered by any literal.
+/// `matched_value` is the matched value, before any conversion, bound by a variable pattern of the
+/// wildcard arm.
+fn lower_otherwise_leaf(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+    mut builder: BlockBuilder,
+    otherwise_arm_index: usize,
+    matched_value: VarUsage,
+    location: LocationId,
+) -> MatchLeafBuilder {
+    if otherwise_arm_index < expr.arms.len() {
+        let lowering_result =
+            bind_otherwise_variable(ctx, expr, &mut builder, otherwise_arm_index, matched_value);
+        MatchLeafBuilder { arm_index: otherwise_arm_index, lowering_result, builder }
+    } else {
+        lower_uncovered_leaf(ctx, builder, location, otherwise_arm_index)
+    }
+}
+
+/// Binds the variable pattern of the wildcard arm of a numeric match, if any, to the matched value.
+fn bind_otherwise_variable(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+    builder: &mut BlockBuilder,
+    otherwise_arm_index: usize,
+    matched_value: VarUsage,
+) -> LoweringResult<()> {
+    let Some(pattern) = expr.arms[otherwise_arm_index].patterns.iter().find_map(|pattern| {
+        try_extract_matches!(
+            &ctx.function_body.arenas.patterns[*pattern],
+            semantic::Pattern::Variable
+        )
+        .cloned()
+    }) else {
+        return Ok(());
+    };
+    lower_single_pattern(
         ctx,
-        empty_match_info,
+        builder,
+        semantic::Pattern::Variable(pattern),
+        LoweredExpr::AtVariable(matched_value),
+    )
+}
+
+/// Returns the level non-exhaustive matches are reported at.
+fn nonexhaustive_match_severity(ctx: &LoweringContext<'_, '_>) -> DiagnosticLevel {
+    ctx.db
+        .get_flag(FlagId::new(ctx.db.upcast(), "nonexhaustive_match_severity"))
+        .map(|flag| match *flag {
+            Flag::NonExhaustiveMatchSeverity(level) => level,
+            _ => panic!("Wrong type flag `{flag:?}`."),
+        })
+        .unwrap_or(DiagnosticLevel::Error)
+}
+
+/// Reports a non-exhaustive match. Fails the lowering, unless non-exhaustive matches are reported
+/// as warnings or not at all, in which case the values that are not covered should be lowered into
+/// a panic.
+fn report_non_exhaustive_match(
+    ctx: &mut LoweringContext<'_, '_>,
+    location: LocationId,
+    error: MatchDiagnostic,
+    match_type: MatchKind,
+) -> LoweringResult<()> {
+    let match_error = MatchError { kind: match_type, error };
+    let location = location.lookup_intern(ctx.db);
+    let location =
+        location.clone().maybe_with_note(macro_expansion_note(ctx, location.stable_location));
+    match nonexhaustive_match_severity(ctx) {
+        DiagnosticLevel::Error => Err(LoweringFlowError::Failed(
+            ctx.diagnostics.report_by_location(location, MatchError(match_error)),
+        )),
+        DiagnosticLevel::Warning => {
+            ctx.diagnostics.report_by_location(location, MatchWarning(match_error));
+            Ok(())
+        }
+        DiagnosticLevel::Allow => Ok(()),
+    }
+}
+
+/// Reports an unreachable arm pattern of a match.
+fn report_unreachable_match_arm(
+    ctx: &mut LoweringContext<'_, '_>,
+    stable_ptr: impl Into<SyntaxStablePtrId>,
+    match_type: MatchKind,
+) -> DiagnosticAdded {
+    let stable_location = StableLocation::new(stable_ptr.into());
+    let location =
+        Location::new(stable_location).maybe_with_note(macro_expansion_note(ctx, stable_location));
+    ctx.diagnostics.report_by_location(
         location,
-        &expr.arms.iter().map(|arm| arm.into()).collect_vec(),
-        arms_vec,
-        MatchKind::Match,
-    )?;
-    builder.merge_and_end_with_match(ctx, match_info, sealed_blocks, location)
+        MatchError(MatchError { kind: match_type, error: MatchDiagnostic::UnreachableMatchArm }),
+    )
+}
+
+/// Returns a note showing the code expanded from a macro at the given location, if it is in such
+/// code. Diagnostics in expanded code are shown at the macro call site, which does not show the
+/// match to fix.
+fn macro_expansion_note(
+    ctx: &LoweringContext<'_, '_>,
+    stable_location: StableLocation,
+) -> Option<DiagnosticNote> {
+    let files_db = ctx.db.upcast();
+    let location = stable_location.diagnostic_location(files_db);
+    require(location.user_location(files_db) != location)?;
+    Some(DiagnosticNote::text_only(format!(
+        "the match is in code expanded from a macro, at {:?}",
+        location.debug(files_db)
+    )))
+}
+
+/// Returns the location to report a non-exhaustive numeric match at - the matched expression, whose
+/// type determines the values to cover, with a note pointing at the whole match.
+fn non_exhaustive_numeric_match_location(
+    ctx: &LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+) -> LocationId {
+    let matched_expr_ptr = ctx.function_body.arenas.exprs[expr.matched_expr].stable_ptr();
+    ctx.get_location(matched_expr_ptr.untyped()).with_note(
+        ctx.db,
+        DiagnosticNote::with_location(
+            "in this match".into(),
+            StableLocation::new(expr.stable_ptr.untyped()).diagnostic_location(ctx.db.upcast()),
+        ),
+    )
+}
+
+/// Returns the suggestion to add a wildcard arm at the end of a numeric match, which must have one.
+fn wildcard_arm_suggestion(
+    ctx: &LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+) -> MatchArmSuggestion {
+    let syntax_db = ctx.db.upcast();
+    let insert_before = match expr.stable_ptr.lookup(syntax_db) {
+        ast::Expr::Match(match_syntax) => match_syntax.rbrace(syntax_db).stable_ptr().untyped(),
+        _ => expr.stable_ptr.untyped(),
+    };
+    MatchArmSuggestion { pattern: "_".into(), insert_before }
+}
+
+/// Reports the literal breaking the sequence from 0 of the literals of a numeric match, given by
+/// `literals_to_arm_map` - the smallest literal above the smallest value they do not cover.
+fn report_non_sequential_literal(
+    ctx: &mut LoweringContext<'_, '_>,
+    expr: &semantic::ExprMatch,
+    literals_to_arm_map: &UnorderedHashMap<usize, usize>,
+) -> DiagnosticAdded {
+    let missing = (0..).find(|value| !literals_to_arm_map.contains_key(value)).unwrap();
+    let breaking_literal = expr
+        .arms
+        .iter()
+        .flat_map(|arm| &arm.patterns)
+        .filter_map(|pattern| {
+            let pattern = &ctx.function_body.arenas.patterns[*pattern];
+            let literal_pattern = try_extract_matches!(pattern, semantic::Pattern::Literal)?;
+            Some((literal_pattern.literal.value.to_usize()?, pattern.stable_ptr().untyped()))
+        })
+        .filter(|(value, _)| *value > missing)
+        .min_by_key(|(value, _)| *value);
+    let stable_ptr = breaking_literal.map_or(expr.stable_ptr.untyped(), |(_, ptr)| ptr);
+    ctx.diagnostics.report(
+        stable_ptr,
+        MatchError(MatchError {
+            kind: MatchKind::Match,
+            error: MatchDiagnostic::UnsupportedMatchArmNonSequential { missing: Some(missing) },
+        }),
+    )
+}
+
+/// Returns `location` with a note pointing at the definition of the matched enum, to report a
+/// missing arm of a match on it. Corelib enums get no note, as their location depends on the
+/// installation of the corelib.
+fn missing_enum_arm_location(
+    ctx: &LoweringContext<'_, '_>,
+    location: LocationId,
+    concrete_enum_id: semantic::ConcreteEnumId,
+) -> LocationId {
+    let defs_db: &dyn DefsGroup = ctx.db.upcast();
+    let enum_id = concrete_enum_id.enum_id(ctx.db.upcast());
+    if enum_id.parent_module(defs_db).owning_crate(defs_db) == ctx.db.core_crate() {
+        return location;
+    }
+    let enum_name = enum_id.name_identifier(defs_db);
+    location.with_note(
+        ctx.db,
+        DiagnosticNote::with_location(
+            "the enum is defined here".into(),
+            StableLocation::new(enum_name.stable_ptr().untyped()).diagnostic_location(defs_db),
+        ),
+    )
+}
+
+/// Reports the variants of an enum which are not covered by any arm of a match on it, all in a
+/// single diagnostic. Does nothing if every variant is covered.
+fn report_missing_enum_arms<'a>(
+    ctx: &mut LoweringContext<'_, '_>,
+    location: LocationId,
+    concrete_enum_id: semantic::ConcreteEnumId,
+    missing_variants: impl Iterator<Item = &'a semantic::ConcreteVariant>,
+    match_type: MatchKind,
+) -> LoweringResult<()> {
+    let missing_variants =
+        missing_variants.map(|variant| variant.id.name(ctx.db.upcast()).to_string()).collect_vec();
+    if missing_variants.is_empty() {
+        return Ok(());
+    }
+    report_non_exhaustive_match(
+        ctx,
+        missing_enum_arm_location(ctx, location, concrete_enum_id),
+        MatchDiagnostic::MissingMatchArm(missing_variants),
+        match_type,
+    )
+}
+
+/// Adds a panic for values that are not covered by any arm to the builder, and returns the
+/// resulting flow error.
+fn lower_uncovered_value_panic(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    location: LocationId,
+) -> LoweringFlowError {
+    lower_match_panic(ctx, builder, location, b"Non-exhaustive match")
+}
+
+/// Adds a panic with the given error message to the builder, and returns the resulting flow error.
+fn lower_match_panic(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    location: LocationId,
+    message: &[u8],
+) -> LoweringFlowError {
+    let semantic_db = ctx.db.upcast();
+    let felt252_ty = ctx.db.core_info().felt252;
+    let err_code = generators::Const {
+        value: ConstValue::Int(BigInt::from_bytes_be(Sign::Plus, message), felt252_ty),
+        location,
+        ty: felt252_ty,
+    }
+    .add(ctx, &mut builder.statements);
+    let never_ty = corelib::never_ty(semantic_db);
+    let call_result = generators::Call {
+        function: corelib::get_core_function_id(semantic_db, "panic_with_felt252".into(), vec![])
+            .lowered(ctx.db),
+        inputs: vec![err_code],
+        coupon_input: None,
+        extra_ret_tys: vec![],
+        ret_tys: vec![never_ty],
+        location,
+    }
+    .add(ctx, &mut builder.statements);
+    LoweringFlowError::Match(MatchInfo::Enum(MatchEnumInfo {
+        concrete_enum_id: extract_matches!(
+            extract_matches!(never_ty.lookup_intern(ctx.db), TypeLongId::Concrete),
+            ConcreteTypeId::Enum
+        ),
+        input: call_result.returns[0],
+        arms: vec![],
+        location,
+    }))
+}
+
+/// Returns a leaf for values that are not covered by any arm, which ends with a panic.
+fn lower_uncovered_leaf(
+    ctx: &mut LoweringContext<'_, '_>,
+    mut builder: BlockBuilder,
+    location: LocationId,
+    arm_index: usize,
+) -> MatchLeafBuilder {
+    let err = lower_uncovered_value_panic(ctx, &mut builder, location);
+    MatchLeafBuilder { arm_index, lowering_result: Err(err), builder }
+}
+
+/// Returns a leaf for values that are proven to be covered by the arms but can not be excluded by
+/// the lowering, e.g. the failure of a downcast that always succeeds. The leaf traps, so that
+/// reaching it due to a bug fails loudly.
+fn lower_unreachable_leaf(
+    ctx: &mut LoweringContext<'_, '_>,
+    mut builder: BlockBuilder,
+    location: LocationId,
+    arm_index: usize,
+) -> MatchLeafBuilder {
+    let err = lower_match_panic(ctx, &mut builder, location, b"Unreachable match arm");
+    MatchLeafBuilder { arm_index, lowering_result: Err(err), builder }
 }
 
 /// Returns the threshold for the number of arms for optimising numeric match expressions, by using
@@ -1519,6 +6442,9 @@ fn numeric_match_optimization_threshold(
     // For felt252 the number of steps with if-else is 2 * min(n, number_of_arms) + 2 and 11~13 for
     // jump table for small_types the number of steps with if-else is 2 * min(n, number_of_arms) + 4
     // and 9~12 for jump table.
+    if let Some(threshold) = ctx.function_match_threshold {
+        return threshold;
+    }
     let default_threshold = if is_small_type { 8 } else { 10 };
     ctx.db
         .get_flag(FlagId::new(ctx.db.upcast(), "numeric_match_optimization_min_arms_threshold"))
@@ -1528,3 +6454,104 @@ fn numeric_match_optimization_threshold(
         })
         .unwrap_or(default_threshold)
 }
+
+/// Returns the threshold set by the attributes of the lowered function, if it has any - either
+/// `#[match_strategy(...)]` or `#[match_jump_table(min_arms = N)]`. If the attributes set
+/// different thresholds, the conflict is reported and none of them is used.
+///
+/// Called once when the function is lowered, so the attributes are reported even if the function
+/// has no numeric match.
+pub(crate) fn function_match_threshold(ctx: &mut LoweringContext<'_, '_>) -> Option<usize> {
+    let mut threshold = match_strategy_threshold(ctx);
+    for (min_arms, stable_ptr) in match_jump_table_thresholds(ctx) {
+        match threshold {
+            Some(threshold) if threshold != min_arms => {
+                ctx.diagnostics.report(
+                    stable_ptr,
+                    MatchError(MatchError {
+                        kind: MatchKind::Match,
+                        error: MatchDiagnostic::ConflictingMatchJumpTableThresholds,
+                    }),
+                );
+                return None;
+            }
+            _ => threshold = Some(min_arms),
+        }
+    }
+    threshold
+}
+
+/// Returns the thresholds set by the `#[match_jump_table(min_arms = N)]` attributes of the lowered
+/// function, along with the arguments of each attribute. Attributes with unsupported arguments are
+/// reported and skipped.
+fn match_jump_table_thresholds(
+    ctx: &mut LoweringContext<'_, '_>,
+) -> Vec<(usize, SyntaxStablePtrId)> {
+    let Ok(attrs) = ctx.semantic_function_id.query_attr(ctx.db.upcast(), MATCH_JUMP_TABLE_ATTR)
+    else {
+        return vec![];
+    };
+    let syntax_db = ctx.db.upcast();
+    attrs
+        .into_iter()
+        .filter_map(|attr| {
+            let literal = match &attr.args[..] {
+                [
+                    AttributeArg {
+                        variant: AttributeArgVariant::Unnamed(ast::Expr::Binary(arg)),
+                        ..
+                    },
+                ] if matches!(arg.op(syntax_db), ast::BinaryOperator::Eq(_))
+                    && arg.lhs(syntax_db).as_syntax_node().get_text_without_trivia(syntax_db)
+                        == "min_arms" =>
+                {
+                    try_extract_matches!(arg.rhs(syntax_db), ast::Expr::Literal)
+                }
+                // The `min_arms: N` syntax is accepted as well.
+                [
+                    AttributeArg {
+                        variant:
+                            AttributeArgVariant::Named { name, value: ast::Expr::Literal(literal) },
+                        ..
+                    },
+                ] if name.text == "min_arms" => Some(literal.clone()),
+                _ => None,
+            };
+            let min_arms = literal.and_then(|literal| literal.numeric_value(syntax_db)?.to_usize());
+            if min_arms.is_none() {
+                ctx.diagnostics.report(
+                    attr.args_stable_ptr.untyped(),
+                    MatchError(MatchError {
+                        kind: MatchKind::Match,
+                        error: MatchDiagnostic::UnsupportedMatchJumpTableArguments,
+                    }),
+                );
+            }
+            Some((min_arms?, attr.args_stable_ptr.untyped()))
+        })
+        .collect()
+}
+
+/// Returns the threshold set by the `#[match_strategy(...)]` attribute of the lowered function, if
+/// it has one - `jump_table` always lowers numeric matches into a jump table, and `if_chain` never
+/// does.
+fn match_strategy_threshold(ctx: &mut LoweringContext<'_, '_>) -> Option<usize> {
+    let Ok(Some(attr)) = ctx.semantic_function_id.find_attr(ctx.db.upcast(), MATCH_STRATEGY_ATTR)
+    else {
+        return None;
+    };
+    if attr.is_single_unnamed_arg(ctx.db.upcast(), "jump_table") {
+        Some(0)
+    } else if attr.is_single_unnamed_arg(ctx.db.upcast(), "if_chain") {
+        Some(usize::MAX)
+    } else {
+        ctx.diagnostics.report(
+            attr.args_stable_ptr.untyped(),
+            MatchError(MatchError {
+                kind: MatchKind::Match,
+                error: MatchDiagnostic::UnsupportedMatchStrategyArguments,
+            }),
+        );
+        None
+    }
+}
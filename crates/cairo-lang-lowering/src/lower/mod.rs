@@ -1,9 +1,11 @@
+use std::sync::Arc;
 use std::vec;
 
 use block_builder::BlockBuilder;
 use cairo_lang_debug::DebugWithDb;
 use cairo_lang_defs::diagnostic_utils::StableLocation;
 use cairo_lang_diagnostics::{Diagnostics, Maybe};
+use cairo_lang_filesystem::ids::CrateId;
 use cairo_lang_semantic::corelib::{
     ErrorPropagationType, get_enum_concrete_variant, try_get_ty_by_name,
     unwrap_error_propagation_type,
@@ -11,12 +13,12 @@ use cairo_lang_semantic::corelib::{
 use cairo_lang_semantic::db::SemanticGroup;
 use cairo_lang_semantic::items::functions::{GenericFunctionId, ImplGenericFunctionId};
 use cairo_lang_semantic::items::imp::ImplLongId;
-use cairo_lang_semantic::usage::MemberPath;
+use cairo_lang_semantic::usage::{MemberPath, Usage};
 use cairo_lang_semantic::{
     ConcreteFunction, ConcreteTraitLongId, ExprVar, LocalVariable, VarId, corelib,
 };
-use cairo_lang_syntax::node::TypedStablePtr;
 use cairo_lang_syntax::node::ids::SyntaxStablePtrId;
+use cairo_lang_syntax::node::{TypedStablePtr, ast};
 use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
 use cairo_lang_utils::unordered_hash_map::{Entry, UnorderedHashMap};
 use cairo_lang_utils::{Intern, LookupIntern, extract_matches, try_extract_matches};
@@ -39,12 +41,16 @@ use {cairo_lang_defs as defs, cairo_lang_semantic as semantic};
 use self::block_builder::SealedBlockBuilder;
 use self::context::{
     EncapsulatingLoweringContext, LoweredExpr, LoweredExprExternEnum, LoweringContext,
-    LoweringFlowError, lowering_flow_error_to_sealed_block,
+    LoweringFlowError, MatchArmTransform, lowering_flow_error_to_sealed_block,
 };
 use self::external::{extern_facade_expr, extern_facade_return_tys};
 use self::logical_op::lower_logical_op;
 use self::lower_if::lower_expr_if;
-use self::lower_match::lower_expr_match;
+pub use self::lower_match::{
+    MatchCoverageBaseline, MatchStrategy, MatchStrategyHistogram, MatchingPath, match_arm_coverage,
+    match_missing_variants,
+};
+use self::lower_match::{function_match_threshold, lower_expr_match, lower_match_arm_index};
 use crate::blocks::FlatBlocks;
 use crate::db::LoweringGroup;
 use crate::diagnostic::LoweringDiagnosticKind::{self, *};
@@ -57,10 +63,11 @@ use crate::lower::context::{LoopContext, LoopEarlyReturnInfo, LoweringResult, Va
 use crate::lower::generators::StructDestructure;
 use crate::lower::lower_match::{
     MatchArmWrapper, TupleInfo, lower_concrete_enum_match, lower_expr_match_tuple,
-    lower_optimized_extern_match,
+    lower_optimized_extern_match, try_extract_extern_enum,
 };
 use crate::{
-    BlockId, FlatLowered, MatchArm, MatchEnumInfo, MatchExternInfo, MatchInfo, VarUsage, VariableId,
+    BlockId, FlatLowered, MatchArm, MatchEnumInfo, MatchExternInfo, MatchInfo, Statement,
+    StatementConst, VarUsage, VariableId,
 };
 
 mod block_builder;
@@ -87,12 +94,66 @@ pub fn lower_semantic_function(
     db: &dyn LoweringGroup,
     semantic_function_id: defs::ids::FunctionWithBodyId,
 ) -> Maybe<MultiLowering> {
+    lower_semantic_function_ex(db, semantic_function_id, None)
+}
+
+/// Lowers a semantic free function, applying `match_arm_transform` to the body of each arm of the
+/// matches in the function before it is lowered.
+///
+/// Unlike [lower_semantic_function], the result is not cached by the lowering queries, so it is
+/// meant for tooling rewriting the lowered code (e.g. to add instrumentation).
+pub fn lower_semantic_function_with_arm_transform<'db>(
+    db: &'db dyn LoweringGroup,
+    semantic_function_id: defs::ids::FunctionWithBodyId,
+    match_arm_transform: MatchArmTransform<'db>,
+) -> Maybe<MultiLowering> {
+    lower_semantic_function_ex(db, semantic_function_id, Some(match_arm_transform))
+}
+
+/// Lowers a semantic free function, reporting the matches on enums which no longer handle
+/// explicitly variants they handled explicitly in `baseline`.
+///
+/// Returns the lowering along with the variants currently handled explicitly by each match, to
+/// update the baseline with. Like [lower_semantic_function_with_arm_transform], the result is not
+/// cached, so it is meant for tooling (e.g. for checking that a refactor did not drop match arms).
+pub fn lower_semantic_function_with_coverage_baseline(
+    db: &dyn LoweringGroup,
+    semantic_function_id: defs::ids::FunctionWithBodyId,
+    baseline: Arc<MatchCoverageBaseline>,
+) -> Maybe<(MultiLowering, MatchCoverageBaseline)> {
+    let mut encapsulating_ctx = EncapsulatingLoweringContext::new(db, semantic_function_id)?;
+    encapsulating_ctx.match_coverage_baseline = Some(baseline);
+    let main_lowering = lower_encapsulating_function(&mut encapsulating_ctx)?;
+    Ok((
+        MultiLowering { main_lowering, generated_lowerings: encapsulating_ctx.lowerings },
+        encapsulating_ctx.match_coverage,
+    ))
+}
+
+/// Lowers a semantic free function, with an optional transform of its match arms.
+fn lower_semantic_function_ex<'db>(
+    db: &'db dyn LoweringGroup,
+    semantic_function_id: defs::ids::FunctionWithBodyId,
+    match_arm_transform: Option<MatchArmTransform<'db>>,
+) -> Maybe<MultiLowering> {
+    let mut encapsulating_ctx = EncapsulatingLoweringContext::new(db, semantic_function_id)?;
+    encapsulating_ctx.match_arm_transform = match_arm_transform;
+    let main_lowering = lower_encapsulating_function(&mut encapsulating_ctx)?;
+    Ok(MultiLowering { main_lowering, generated_lowerings: encapsulating_ctx.lowerings })
+}
+
+/// Lowers the semantic function of the given encapsulating context, after checking it has no
+/// semantic errors.
+fn lower_encapsulating_function(
+    encapsulating_ctx: &mut EncapsulatingLoweringContext<'_>,
+) -> Maybe<FlatLowered> {
+    let db = encapsulating_ctx.db;
+    let semantic_function_id = encapsulating_ctx.semantic_function_id;
     let declaration_diagnostics = db.function_declaration_diagnostics(semantic_function_id);
     check_error_free_or_warn(db, declaration_diagnostics, semantic_function_id, "declaration")?;
     let body_diagnostics = db.function_body_diagnostics(semantic_function_id);
     check_error_free_or_warn(db, body_diagnostics, semantic_function_id, "body")?;
 
-    let mut encapsulating_ctx = EncapsulatingLoweringContext::new(db, semantic_function_id)?;
     let function_id = FunctionWithBodyLongId::Semantic(semantic_function_id).intern(db);
     let signature = db.function_with_body_signature(semantic_function_id)?;
 
@@ -105,13 +166,66 @@ pub fn lower_semantic_function(
     }
 
     let block_expr_id = encapsulating_ctx.function_body.body_expr;
-    let main_lowering = lower_function(
-        &mut encapsulating_ctx,
+    lower_function(
+        encapsulating_ctx,
         function_id,
         Signature::from_semantic(db, signature),
         block_expr_id,
-    )?;
-    Ok(MultiLowering { main_lowering, generated_lowerings: encapsulating_ctx.lowerings })
+    )
+}
+
+/// Returns the ids of the free functions and impl functions of the given crate.
+pub fn crate_function_with_body_ids(
+    db: &dyn LoweringGroup,
+    crate_id: CrateId,
+) -> Maybe<Vec<defs::ids::FunctionWithBodyId>> {
+    let mut function_ids = Vec::new();
+    for module_id in db.crate_modules(crate_id).iter() {
+        for free_func in db.module_free_functions_ids(*module_id)?.iter() {
+            function_ids.push(defs::ids::FunctionWithBodyId::Free(*free_func));
+        }
+        for impl_id in db.module_impls_ids(*module_id)?.iter() {
+            for impl_func in db.impl_functions(*impl_id)?.values() {
+                function_ids.push(defs::ids::FunctionWithBodyId::Impl(*impl_func));
+            }
+        }
+    }
+    Ok(function_ids)
+}
+
+/// Returns the number of match expressions lowered with each strategy across the functions of
+/// the given crate.
+///
+/// The functions are lowered again rather than taken from the lowering queries, so this is meant
+/// for tooling (e.g. for tuning the defaults of the match lowering), and not for the compilation
+/// itself.
+pub fn crate_match_strategy_histogram(
+    db: &dyn LoweringGroup,
+    crate_id: CrateId,
+) -> Maybe<MatchStrategyHistogram> {
+    let mut histogram = MatchStrategyHistogram::default();
+    for function_id in crate_function_with_body_ids(db, crate_id)? {
+        let mut encapsulating_ctx = EncapsulatingLoweringContext::new(db, function_id)?;
+        lower_encapsulating_function(&mut encapsulating_ctx)?;
+        histogram.merge(&encapsulating_ctx.match_strategies);
+    }
+    Ok(histogram)
+}
+
+/// Returns whether the match at `match_ptr` in the given function is lowered by matching directly
+/// on the extern function call producing the matched enum, or `None` if it is not a match on an
+/// enum in the function.
+///
+/// The function is lowered again rather than taken from the lowering queries, so this is meant for
+/// tooling and tests, and not for the compilation itself.
+pub fn is_optimized_extern_match(
+    db: &dyn LoweringGroup,
+    function_id: defs::ids::FunctionWithBodyId,
+    match_ptr: ast::ExprPtr,
+) -> Maybe<Option<bool>> {
+    let mut encapsulating_ctx = EncapsulatingLoweringContext::new(db, function_id)?;
+    lower_encapsulating_function(&mut encapsulating_ctx)?;
+    Ok(encapsulating_ctx.optimized_extern_matches.get(&match_ptr).copied())
 }
 
 /// Lowers a function into [FlatLowered].
@@ -124,6 +238,7 @@ pub fn lower_function(
     log::trace!("Lowering a free function.");
     let return_type = signature.return_type;
     let mut ctx = LoweringContext::new(encapsulating_ctx, function_id, signature, return_type)?;
+    ctx.function_match_threshold = function_match_threshold(&mut ctx);
 
     // Fetch body block expr.
     let semantic_block =
@@ -157,6 +272,7 @@ pub fn lower_function(
                 block_sealed,
                 semantic_block.stable_ptr.untyped(),
             )?;
+            define_const_pool(&mut ctx, root_block_id);
             Ok(root_block_id)
         })
     };
@@ -384,8 +500,8 @@ pub fn lower_expr_while_let(
     let (n_snapshots, long_type_id) = peel_snapshots(ctx.db.upcast(), ty);
 
     let arms = vec![
-        MatchArmWrapper { patterns: patterns.into(), expr: Some(loop_expr.body) },
-        MatchArmWrapper { patterns: vec![], expr: None },
+        MatchArmWrapper { patterns: patterns.into(), guard: None, expr: Some(loop_expr.body) },
+        MatchArmWrapper { patterns: vec![], guard: None, expr: None },
     ];
 
     if let Some(types) = try_extract_matches!(long_type_id, TypeLongId::Tuple) {
@@ -400,9 +516,19 @@ pub fn lower_expr_while_let(
         );
     }
 
-    if let LoweredExpr::ExternEnum(extern_enum) = lowered_expr {
-        return lower_optimized_extern_match(ctx, builder, extern_enum, &arms, match_type);
-    }
+    let lowered_expr = match try_extract_extern_enum(lowered_expr) {
+        Ok((extern_enum, n_snapshots)) => {
+            return lower_optimized_extern_match(
+                ctx,
+                builder,
+                extern_enum,
+                n_snapshots,
+                &arms,
+                match_type,
+            );
+        }
+        Err(lowered_expr) => lowered_expr,
+    };
 
     lower_concrete_enum_match(
         ctx,
@@ -435,22 +561,7 @@ pub fn lower_loop_function(
     let mut builder = BlockBuilder::root(&mut ctx, root_block_id);
 
     let snapped_params = ctx.usages.usages[&loop_expr_id].snap_usage.clone();
-    let parameters = ctx
-        .signature
-        .params
-        .clone()
-        .into_iter()
-        .map(|param| {
-            let location = ctx.get_location(param.stable_ptr().untyped());
-            let var = ctx.new_var(VarRequest { ty: param.ty(), location });
-            if snapped_params.contains_key::<MemberPath>(&(&param).into()) {
-                builder.update_snap_ref(&param, var)
-            } else {
-                builder.semantics.introduce((&param).into(), var);
-            }
-            var
-        })
-        .collect_vec();
+    let parameters = introduce_generated_function_params(&mut ctx, &mut builder, &snapped_params);
 
     let root_ok = (|| {
         let (block_expr, stable_ptr) = match ctx.function_body.arenas.exprs[loop_expr_id].clone() {
@@ -499,6 +610,7 @@ pub fn lower_loop_function(
 
         let block_sealed = lowered_expr_to_block_scope_end(&mut ctx, builder, block_expr)?;
         wrap_sealed_block_as_function(&mut ctx, block_sealed, stable_ptr.untyped())?;
+        define_const_pool(&mut ctx, root_block_id);
 
         Ok(root_block_id)
     })();
@@ -516,6 +628,68 @@ pub fn lower_loop_function(
     })
 }
 
+/// Lowers the body of a function generated for an outlined expression, such as a match arm body.
+fn lower_outlined_function(
+    encapsulating_ctx: &mut EncapsulatingLoweringContext<'_>,
+    function_id: FunctionWithBodyId,
+    signature: Signature,
+    expr_id: ExprId,
+    snapped_params: &OrderedHashMap<MemberPath, ExprVarMemberPath>,
+) -> Maybe<FlatLowered> {
+    let return_type = signature.return_type;
+    let mut ctx = LoweringContext::new(encapsulating_ctx, function_id, signature, return_type)?;
+
+    // Initialize builder.
+    let root_block_id = alloc_empty_block(&mut ctx);
+    let mut builder = BlockBuilder::root(&mut ctx, root_block_id);
+    let parameters = introduce_generated_function_params(&mut ctx, &mut builder, snapped_params);
+
+    let root_ok = (|| {
+        let stable_ptr = ctx.function_body.arenas.exprs[expr_id].stable_ptr();
+        let lowered_expr = lower_expr(&mut ctx, &mut builder, expr_id);
+        let block_sealed = lowered_expr_to_block_scope_end(&mut ctx, builder, lowered_expr)?;
+        wrap_sealed_block_as_function(&mut ctx, block_sealed, stable_ptr.untyped())?;
+        define_const_pool(&mut ctx, root_block_id);
+
+        Ok(root_block_id)
+    })();
+
+    let blocks = root_ok
+        .map(|_| ctx.blocks.build().expect("Root block must exist."))
+        .unwrap_or_else(FlatBlocks::new_errored);
+    Ok(FlatLowered {
+        diagnostics: ctx.diagnostics.build(),
+        variables: ctx.variables.variables,
+        blocks,
+        signature: ctx.signature.clone(),
+        parameters,
+    })
+}
+
+/// Introduces the parameters of a generated function in its root block, as snapshots for the
+/// member paths in `snapped_params`.
+fn introduce_generated_function_params(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    snapped_params: &OrderedHashMap<MemberPath, ExprVarMemberPath>,
+) -> Vec<VariableId> {
+    ctx.signature
+        .params
+        .clone()
+        .into_iter()
+        .map(|param| {
+            let location = ctx.get_location(param.stable_ptr().untyped());
+            let var = ctx.new_var(VarRequest { ty: param.ty(), location });
+            if snapped_params.contains_key::<MemberPath>(&(&param).into()) {
+                builder.update_snap_ref(&param, var)
+            } else {
+                builder.semantics.introduce((&param).into(), var);
+            }
+            var
+        })
+        .collect_vec()
+}
+
 /// Wraps `block_sealed` as the root block of a function.
 fn wrap_sealed_block_as_function(
     ctx: &mut LoweringContext<'_, '_>,
@@ -561,6 +735,19 @@ fn wrap_sealed_block_as_function(
     }
 }
 
+/// Defines the constants of the constant pool of the function at the start of its root block, so
+/// they are available in all of its blocks.
+fn define_const_pool(ctx: &mut LoweringContext<'_, '_>, root_block_id: BlockId) {
+    let statements = ctx
+        .const_pool
+        .iter()
+        .map(|(value, output)| {
+            Statement::Const(StatementConst { value: value.clone(), output: *output })
+        })
+        .collect_vec();
+    ctx.blocks.0[root_block_id.0].statements.splice(0..0, statements);
+}
+
 /// Lowers a semantic block.
 fn lower_block(
     ctx: &mut LoweringContext<'_, '_>,
@@ -747,8 +934,19 @@ fn lower_single_pattern(
     log::trace!("Lowering a single pattern.");
     match pattern {
         semantic::Pattern::Literal(_)
+        | semantic::Pattern::Range(_)
         | semantic::Pattern::StringLiteral(_)
-        | semantic::Pattern::EnumVariant(_) => {
+        | semantic::Pattern::EnumVariant(_)
+        | semantic::Pattern::Or(_)
+        | semantic::Pattern::Rest(_) => {
+            return Err(LoweringFlowError::Failed(
+                ctx.diagnostics.report(&pattern, UnsupportedPattern),
+            ));
+        }
+        // The length of a span is only known by matching on it.
+        semantic::Pattern::FixedSizeArray(semantic::PatternFixedSizeArray { ty, .. })
+            if corelib::try_extract_span_element_type(ctx.db.upcast(), ty).is_some() =>
+        {
             return Err(LoweringFlowError::Failed(
                 ctx.diagnostics.report(&pattern, UnsupportedPattern),
             ));
@@ -822,6 +1020,17 @@ fn lower_single_pattern(
         }) => {
             lower_tuple_like_pattern_helper(ctx, builder, lowered_expr, &patterns, ty)?;
         }
+        semantic::Pattern::Binding(semantic::PatternBinding { binding, inner_pattern, .. }) => {
+            let var = lowered_expr.as_var_usage(ctx, builder)?;
+            lower_single_pattern(
+                ctx,
+                builder,
+                semantic::Pattern::Variable(binding),
+                LoweredExpr::AtVariable(var),
+            )?;
+            let inner_pattern = ctx.function_body.arenas.patterns[inner_pattern].clone();
+            lower_single_pattern(ctx, builder, inner_pattern, LoweredExpr::AtVariable(var))?;
+        }
         semantic::Pattern::Otherwise(pattern) => {
             let var = lowered_expr.as_var_usage(ctx, builder)?.var_id;
             ctx.variables.variables[var].location = ctx.get_location(pattern.stable_ptr.untyped());
@@ -1275,6 +1484,9 @@ fn lower_expr_function_call(
         let [input] = <[_; 1]>::try_from(arg_inputs).ok().unwrap();
         return Err(LoweringFlowError::Panic(input, location));
     }
+    if expr.function == corelib::internal_match_arm_index(ctx.db.upcast()) {
+        return lower_match_arm_index(ctx, builder, expr.stable_ptr.untyped(), location);
+    }
 
     // The following is relevant only to extern functions.
     if expr.function.try_get_extern_function_id(ctx.db.upcast()).is_some() {
@@ -1493,26 +1705,7 @@ fn lower_expr_loop(
     };
 
     // Determine signature.
-    let params = usage
-        .usage
-        .iter()
-        .map(|(_, expr)| expr.clone())
-        .chain(usage.snap_usage.iter().map(|(_, expr)| match expr {
-            ExprVarMemberPath::Var(var) => ExprVarMemberPath::Var(ExprVar {
-                ty: wrap_in_snapshots(ctx.db.upcast(), var.ty, 1),
-                ..*var
-            }),
-            ExprVarMemberPath::Member { parent, member_id, stable_ptr, concrete_struct_id, ty } => {
-                ExprVarMemberPath::Member {
-                    parent: parent.clone(),
-                    member_id: *member_id,
-                    stable_ptr: *stable_ptr,
-                    concrete_struct_id: *concrete_struct_id,
-                    ty: wrap_in_snapshots(ctx.db.upcast(), *ty, 1),
-                }
-            }
-        }))
-        .collect_vec();
+    let params = generated_function_params(ctx, usage);
     let extra_rets = usage.changes.iter().map(|(_, expr)| expr.clone()).collect_vec();
 
     let loop_location = ctx.get_location(stable_ptr.untyped());
@@ -1546,23 +1739,13 @@ fn lower_expr_loop(
     // TODO(spapini): Recursive call.
     encapsulating_ctx.lowerings.insert(GeneratedFunctionKey::Loop(stable_ptr), lowered);
     ctx.encapsulating_ctx = Some(encapsulating_ctx);
-    let call_loop_expr = call_loop_func_ex(
+    let call_loop_expr = call_generated_func_ex(
         ctx,
         loop_signature,
         builder,
-        loop_expr_id,
+        GeneratedFunctionKey::Loop(stable_ptr),
         stable_ptr.untyped(),
-        |ctx, builder, param| {
-            if let Some(var) = builder.get_snap_ref(ctx, param) {
-                return Some(var);
-            };
-            let input = builder.get_ref(ctx, param)?;
-            let location = ctx.get_location(param.stable_ptr().untyped());
-            let (original, snapped) =
-                generators::Snapshot { input, location }.add(ctx, &mut builder.statements);
-            builder.update_ref(ctx, param, original);
-            Some(VarUsage { var_id: snapped, location })
-        },
+        get_or_take_snap_ref,
     )?;
 
     let Some(LoopEarlyReturnInfo { normal_return_variant, early_return_variant }) =
@@ -1631,6 +1814,74 @@ fn lower_expr_loop(
     )
 }
 
+/// Lowers an expression into a call to a generated function whose body is the expression, given
+/// the usage of the expression. The member paths used by the expression are passed as arguments,
+/// and the ones it changes are returned back.
+///
+/// The expression must leave its scope only by evaluating to its value.
+fn lower_outlined_expr(
+    ctx: &mut LoweringContext<'_, '_>,
+    builder: &mut BlockBuilder,
+    expr_id: ExprId,
+    usage: &Usage,
+) -> LoweringResult<LoweredExpr> {
+    let expr = &ctx.function_body.arenas.exprs[expr_id];
+    let (stable_ptr, return_type) = (expr.stable_ptr(), expr.ty());
+    let signature = Signature {
+        params: generated_function_params(ctx, usage),
+        extra_rets: usage.changes.iter().map(|(_, expr)| expr.clone()).collect_vec(),
+        return_type,
+        implicits: vec![],
+        panicable: ctx.signature.panicable,
+        location: ctx.get_location(stable_ptr.untyped()),
+    };
+    let key = GeneratedFunctionKey::MatchArm(stable_ptr);
+    let function =
+        FunctionWithBodyLongId::Generated { parent: ctx.semantic_function_id, key }.intern(ctx.db);
+
+    // Generate the function.
+    let encapsulating_ctx = std::mem::take(&mut ctx.encapsulating_ctx).unwrap();
+    let lowered = lower_outlined_function(
+        encapsulating_ctx,
+        function,
+        signature.clone(),
+        expr_id,
+        &usage.snap_usage,
+    )
+    .map_err(LoweringFlowError::Failed)?;
+    encapsulating_ctx.lowerings.insert(key, lowered);
+    ctx.encapsulating_ctx = Some(encapsulating_ctx);
+    call_generated_func_ex(ctx, signature, builder, key, stable_ptr.untyped(), get_or_take_snap_ref)
+}
+
+/// Returns the parameters of a function generated for an expression with the given usage: the
+/// member paths it uses, followed by snapshots of the member paths it uses as snapshots.
+fn generated_function_params(
+    ctx: &LoweringContext<'_, '_>,
+    usage: &Usage,
+) -> Vec<ExprVarMemberPath> {
+    usage
+        .usage
+        .iter()
+        .map(|(_, expr)| expr.clone())
+        .chain(usage.snap_usage.iter().map(|(_, expr)| match expr {
+            ExprVarMemberPath::Var(var) => ExprVarMemberPath::Var(ExprVar {
+                ty: wrap_in_snapshots(ctx.db.upcast(), var.ty, 1),
+                ..*var
+            }),
+            ExprVarMemberPath::Member { parent, member_id, stable_ptr, concrete_struct_id, ty } => {
+                ExprVarMemberPath::Member {
+                    parent: parent.clone(),
+                    member_id: *member_id,
+                    stable_ptr: *stable_ptr,
+                    concrete_struct_id: *concrete_struct_id,
+                    ty: wrap_in_snapshots(ctx.db.upcast(), *ty, 1),
+                }
+            }
+        }))
+        .collect_vec()
+}
+
 /// Adds a call to an inner loop-generated function from the loop function itself.
 fn call_loop_func(
     ctx: &mut LoweringContext<'_, '_>,
@@ -1639,22 +1890,41 @@ fn call_loop_func(
     loop_expr_id: ExprId,
     stable_ptr: SyntaxStablePtrId,
 ) -> LoweringResult<LoweredExpr> {
-    call_loop_func_ex(
+    let loop_stable_ptr = ctx.function_body.arenas.exprs[loop_expr_id].stable_ptr();
+    call_generated_func_ex(
         ctx,
         loop_signature,
         builder,
-        loop_expr_id,
+        GeneratedFunctionKey::Loop(loop_stable_ptr),
         stable_ptr,
         |ctx, builder, param| builder.get_snap_ref(ctx, param),
     )
 }
 
-/// Adds a call to an inner loop-generated function.
-fn call_loop_func_ex(
+/// Returns the snapshot of a member path passed to a generated function, taking the snapshot if
+/// it is not already available.
+fn get_or_take_snap_ref(
     ctx: &mut LoweringContext<'_, '_>,
-    loop_signature: Signature,
     builder: &mut BlockBuilder,
-    loop_expr_id: ExprId,
+    param: &ExprVarMemberPath,
+) -> Option<VarUsage> {
+    if let Some(var) = builder.get_snap_ref(ctx, param) {
+        return Some(var);
+    };
+    let input = builder.get_ref(ctx, param)?;
+    let location = ctx.get_location(param.stable_ptr().untyped());
+    let (original, snapped) =
+        generators::Snapshot { input, location }.add(ctx, &mut builder.statements);
+    builder.update_ref(ctx, param, original);
+    Some(VarUsage { var_id: snapped, location })
+}
+
+/// Adds a call to a generated function.
+fn call_generated_func_ex(
+    ctx: &mut LoweringContext<'_, '_>,
+    signature: Signature,
+    builder: &mut BlockBuilder,
+    key: GeneratedFunctionKey,
     stable_ptr: SyntaxStablePtrId,
     handle_snap: impl Fn(
         &mut LoweringContext<'_, '_>,
@@ -1663,14 +1933,13 @@ fn call_loop_func_ex(
     ) -> Option<VarUsage>,
 ) -> LoweringResult<LoweredExpr> {
     let location = ctx.get_location(stable_ptr);
-    let loop_stable_ptr = ctx.function_body.arenas.exprs[loop_expr_id].stable_ptr();
     // Call it.
     let function = FunctionLongId::Generated(GeneratedFunction {
         parent: ctx.concrete_function_id.base_semantic_function(ctx.db),
-        key: GeneratedFunctionKey::Loop(loop_stable_ptr),
+        key,
     })
     .intern(ctx.db);
-    let inputs = loop_signature
+    let inputs = signature
         .params
         .into_iter()
         .map(|param| {
@@ -1690,19 +1959,19 @@ fn call_loop_func_ex(
                 })
         })
         .collect::<LoweringResult<Vec<_>>>()?;
-    let extra_ret_tys = loop_signature.extra_rets.iter().map(|path| path.ty()).collect_vec();
+    let extra_ret_tys = signature.extra_rets.iter().map(|path| path.ty()).collect_vec();
     let call_result = generators::Call {
         function,
         inputs,
         coupon_input: None,
         extra_ret_tys,
-        ret_tys: vec![loop_signature.return_type],
+        ret_tys: vec![signature.return_type],
         location,
     }
     .add(ctx, &mut builder.statements);
 
     // Rebind the ref variables.
-    for (ref_arg, output_var) in zip_eq(&loop_signature.extra_rets, call_result.extra_outputs) {
+    for (ref_arg, output_var) in zip_eq(&signature.extra_rets, call_result.extra_outputs) {
         builder.update_ref(ctx, ref_arg, output_var.var_id);
     }
 
@@ -2083,6 +2352,7 @@ fn add_closure_call_function(
     let maybe_sealed_block = lowered_expr_to_block_scope_end(&mut ctx, builder, lowered_expr);
     let root_ok = maybe_sealed_block.and_then(|block_sealed| {
         wrap_sealed_block_as_function(&mut ctx, block_sealed, expr.stable_ptr.untyped())?;
+        define_const_pool(&mut ctx, root_block_id);
         Ok(root_block_id)
     });
     let blocks = root_ok
@@ -2319,6 +2589,11 @@ fn lower_expr_assignment(
 }
 
 /// Allocates and empty block in `ctx`.
+///
+/// Block ids are allocated sequentially, in the order in which the lowering visits the code, so
+/// lowering the same function always yields the same ids. In particular, the blocks of a match are
+/// allocated by the order of the variants (or values) it matches on, and then by the order of its
+/// arms - never by the iteration order of a hash map.
 fn alloc_empty_block(ctx: &mut LoweringContext<'_, '_>) -> BlockId {
     ctx.blocks.alloc_empty()
 }
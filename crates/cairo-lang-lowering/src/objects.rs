@@ -14,7 +14,7 @@ use cairo_lang_semantic::items::imp::ImplLookupContext;
 use cairo_lang_semantic::types::TypeInfo;
 use cairo_lang_semantic::{ConcreteEnumId, ConcreteVariant};
 use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
-use cairo_lang_utils::{Intern, LookupIntern};
+use cairo_lang_utils::{Intern, LookupIntern, require};
 use id_arena::{Arena, Id};
 
 pub mod blocks;
@@ -187,6 +187,19 @@ impl FlatBlock {
     pub fn is_set(&self) -> bool {
         !matches!(self.end, FlatBlockEnd::NotSet)
     }
+
+    /// Returns the last call of the block if it is a tail call, i.e. the block returns exactly the
+    /// outputs of the call, so nothing is left to be done by the function after it.
+    pub fn tail_call(&self) -> Option<&StatementCall> {
+        let FlatBlockEnd::Return(returns, _) = &self.end else {
+            return None;
+        };
+        let Some(Statement::Call(call)) = self.statements.last() else {
+            return None;
+        };
+        require(returns.iter().map(|var_usage| var_usage.var_id).eq(call.outputs.iter().copied()))?;
+        Some(call)
+    }
 }
 
 /// Describes what happens to the program flow at the end of a [`FlatBlock`].
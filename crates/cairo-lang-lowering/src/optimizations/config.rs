@@ -18,6 +18,8 @@ pub struct OptimizationConfig {
     pub inlining_strategy: InliningStrategy,
     /// Should const folding be skipped.
     pub skip_const_folding: bool,
+    /// Should the calls shared by the blocks jumping to a common block be hoisted into it.
+    pub hoist_calls: bool,
 }
 
 impl OptimizationConfig {
@@ -40,6 +42,11 @@ impl OptimizationConfig {
         self.skip_const_folding = skip_const_folding;
         self
     }
+    /// Sets the `hoist_calls` flag.
+    pub fn with_hoist_calls(mut self, hoist_calls: bool) -> Self {
+        self.hoist_calls = hoist_calls;
+        self
+    }
 }
 
 impl Default for OptimizationConfig {
@@ -48,6 +55,7 @@ impl Default for OptimizationConfig {
             moveable_functions: vec![],
             inlining_strategy: InliningStrategy::Default,
             skip_const_folding: false,
+            hoist_calls: false,
         }
     }
 }
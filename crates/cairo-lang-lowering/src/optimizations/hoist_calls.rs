@@ -0,0 +1,144 @@
+#[cfg(test)]
+#[path = "hoist_calls_test.rs"]
+mod test;
+
+use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
+use cairo_lang_utils::unordered_hash_set::UnorderedHashSet;
+use itertools::{Itertools, zip_eq};
+
+use crate::db::LoweringGroup;
+use crate::{BlockId, FlatBlock, FlatBlockEnd, FlatLowered, Statement, VarUsage, VariableId};
+
+/// Moves identical calls out of the blocks that jump to a common block.
+///
+/// When every block that jumps to a block ends with a call to the same function, and the calls
+/// differ only by constant arguments, the call is moved to the start of the common block and each
+/// jumping block passes its constants through the remapping instead. For example, the arms of
+/// `match i { 0 => f(x, 10), 1 => f(x, 20), _ => f(x, 0) }` are left with only their constant,
+/// and `f` is called once, after the match.
+///
+/// The calls are the last statements of the jumping blocks, so moving them past the jump does not
+/// change the order of evaluation.
+///
+/// Only applied if enabled by [crate::optimizations::config::OptimizationConfig::hoist_calls].
+pub fn hoist_calls(db: &dyn LoweringGroup, lowered: &mut FlatLowered) {
+    if !db.optimization_config().hoist_calls || lowered.blocks.is_empty() {
+        return;
+    }
+
+    let mut predecessors = OrderedHashMap::<BlockId, Vec<BlockId>>::default();
+    // Blocks that are also the target of a match arm are not entered through a remapping.
+    let mut match_targets = UnorderedHashSet::<BlockId>::default();
+    for (block_id, block) in lowered.blocks.iter() {
+        match &block.end {
+            FlatBlockEnd::Goto(target, _) => {
+                predecessors.entry(*target).or_default().push(block_id);
+            }
+            FlatBlockEnd::Match { info } => {
+                match_targets.extend(info.arms().iter().map(|arm| arm.block_id));
+            }
+            FlatBlockEnd::NotSet | FlatBlockEnd::Return(..) | FlatBlockEnd::Panic(_) => {}
+        }
+    }
+
+    for (target, preds) in predecessors {
+        if preds.len() >= 2 && !match_targets.contains(&target) {
+            try_hoist_call(lowered, target, &preds);
+        }
+    }
+}
+
+/// Moves the calls ending `preds` to the start of `target`, if they can be merged into one.
+fn try_hoist_call(lowered: &mut FlatLowered, target: BlockId, preds: &[BlockId]) -> Option<()> {
+    let calls = preds
+        .iter()
+        .map(|pred| match lowered.blocks[*pred].statements.last() {
+            Some(Statement::Call(call)) if !call.with_coupon => Some(call.clone()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let first = &calls[0];
+    if calls.iter().any(|call| {
+        call.function != first.function
+            || call.inputs.len() != first.inputs.len()
+            || call.outputs.len() != first.outputs.len()
+    }) {
+        return None;
+    }
+
+    // The indices of the inputs that differ between the calls. Each of them must be a constant
+    // defined by the block making the call.
+    let mut differing_inputs = vec![];
+    for (idx, input) in first.inputs.iter().enumerate() {
+        if calls.iter().all(|call| call.inputs[idx].var_id == input.var_id) {
+            continue;
+        }
+        if !zip_eq(preds, &calls)
+            .all(|(pred, call)| defines_const(&lowered.blocks[*pred], call.inputs[idx].var_id))
+        {
+            return None;
+        }
+        differing_inputs.push(idx);
+    }
+
+    // For each output, the variable of `target` it is remapped to, which must be the same in all
+    // the blocks.
+    let mut output_dsts = vec![];
+    for idx in 0..first.outputs.len() {
+        let dsts = zip_eq(preds, &calls)
+            .map(|(pred, call)| remapped_to(&lowered.blocks[*pred], call.outputs[idx]))
+            .collect::<Option<Vec<_>>>()?;
+        if !dsts.iter().all_equal() {
+            return None;
+        }
+        output_dsts.push(dsts[0]);
+    }
+
+    let mut hoisted = first.clone();
+    for idx in differing_inputs {
+        let var_id = lowered.variables.alloc(lowered.variables[first.inputs[idx].var_id].clone());
+        hoisted.inputs[idx].var_id = var_id;
+        for (pred, call) in zip_eq(preds, &calls) {
+            remapping_mut(&mut lowered.blocks[*pred]).insert(var_id, call.inputs[idx]);
+        }
+    }
+    for (idx, dst) in output_dsts.into_iter().enumerate() {
+        let Some(dst) = dst else { continue };
+        hoisted.outputs[idx] = dst;
+        for pred in preds {
+            remapping_mut(&mut lowered.blocks[*pred]).swap_remove(&dst);
+        }
+    }
+    for pred in preds {
+        lowered.blocks[*pred].statements.pop();
+    }
+    lowered.blocks[target].statements.insert(0, Statement::Call(hoisted));
+
+    Some(())
+}
+
+/// Returns true if `var_id` is defined by a const statement of `block`.
+fn defines_const(block: &FlatBlock, var_id: VariableId) -> bool {
+    block
+        .statements
+        .iter()
+        .any(|stmt| matches!(stmt, Statement::Const(stmt) if stmt.output == var_id))
+}
+
+/// Returns the variable `var_id` is remapped to at the end of `block`, or `None` if it is not
+/// remapped.
+///
+/// Returns `None` as the outer value if `var_id` is remapped more than once.
+fn remapped_to(block: &FlatBlock, var_id: VariableId) -> Option<Option<VariableId>> {
+    let FlatBlockEnd::Goto(_, remapping) = &block.end else { unreachable!() };
+    match remapping.iter().filter(|(_, src)| src.var_id == var_id).at_most_one() {
+        Ok(dst) => Some(dst.map(|(dst, _)| *dst)),
+        Err(_) => None,
+    }
+}
+
+/// Returns the remapping at the end of `block`, which must end with a goto.
+fn remapping_mut(block: &mut FlatBlock) -> &mut OrderedHashMap<VariableId, VarUsage> {
+    let FlatBlockEnd::Goto(_, remapping) = &mut block.end else { unreachable!() };
+    &mut remapping.remapping
+}
@@ -4,6 +4,7 @@ pub mod config;
 pub mod const_folding;
 pub mod dedup_blocks;
 pub mod gas_redeposit;
+pub mod hoist_calls;
 pub mod match_optimizer;
 pub mod remappings;
 pub mod reorder_statements;
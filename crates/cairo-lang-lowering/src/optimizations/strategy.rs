@@ -12,6 +12,7 @@ use crate::inline::apply_inlining;
 use crate::optimizations::branch_inversion::branch_inversion;
 use crate::optimizations::cancel_ops::cancel_ops;
 use crate::optimizations::const_folding::const_folding;
+use crate::optimizations::hoist_calls::hoist_calls;
 use crate::optimizations::match_optimizer::optimize_matches;
 use crate::optimizations::remappings::optimize_remappings;
 use crate::optimizations::reorder_statements::reorder_statements;
@@ -27,6 +28,7 @@ pub enum OptimizationPhase {
     CancelOps,
     ConstFolding,
     DedupBlocks,
+    HoistCalls,
     OptimizeMatches,
     OptimizeRemappings,
     ReorderStatements,
@@ -57,6 +59,7 @@ impl OptimizationPhase {
             OptimizationPhase::CancelOps => cancel_ops(lowered),
             OptimizationPhase::ConstFolding => const_folding(db, lowered),
             OptimizationPhase::DedupBlocks => dedup_blocks(lowered),
+            OptimizationPhase::HoistCalls => hoist_calls(db, lowered),
             OptimizationPhase::OptimizeMatches => optimize_matches(lowered),
             OptimizationPhase::OptimizeRemappings => optimize_remappings(lowered),
             OptimizationPhase::ReorderStatements => reorder_statements(db, lowered),
@@ -106,6 +109,8 @@ impl OptimizationStrategyId {
 pub fn baseline_optimization_strategy(db: &dyn LoweringGroup) -> OptimizationStrategyId {
     OptimizationStrategy(vec![
         OptimizationPhase::ApplyInlining,
+        // Must be before return optimization, which replaces the gotos it relies on.
+        OptimizationPhase::HoistCalls,
         OptimizationPhase::ReturnOptimization,
         OptimizationPhase::ReorganizeBlocks,
         // The call to `reorder_statements` before and after `branch_inversion` is intentional.
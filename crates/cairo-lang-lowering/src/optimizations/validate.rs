@@ -1,7 +1,8 @@
+use cairo_lang_semantic::MatchArmSelector;
 use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
 
 use crate::borrow_check::analysis::StatementLocation;
-use crate::{BlockId, FlatLowered, VariableId};
+use crate::{BlockId, FlatLowered, MatchInfo, VariableId};
 
 /// Possible failing validations.
 #[derive(Debug)]
@@ -12,6 +13,8 @@ pub enum ValidationError {
     UnknownUsageInEnd(VariableId, BlockId),
     /// A variable was introduced twice.
     DoubleIntroduction(VariableId, Introduction, Introduction),
+    /// The match at the end of a block is malformed.
+    InvalidMatch(BlockId, MatchInfoError),
 }
 impl ValidationError {
     pub fn to_message(&self) -> String {
@@ -30,10 +33,77 @@ impl ValidationError {
                 "Variable `v{}` introduced twice at: `{intro1}` and `{intro2}`",
                 var_id.index()
             ),
+            ValidationError::InvalidMatch(block_id, err) => {
+                format!("Invalid match in end of block{}: {}", block_id.0, err.to_message())
+            }
+        }
+    }
+}
+
+/// Possible failing validations of a single match.
+#[derive(Debug)]
+pub enum MatchInfoError {
+    /// Two arms of the match have the same selector.
+    DuplicateSelector { selector: MatchArmSelector, first_arm: usize, second_arm: usize },
+    /// An arm of the match selects a variant or a value other than the one of its position.
+    ArmOutOfOrder { arm: usize, selected: usize },
+    /// An arm of a match on an enum or on an enum value binds a number of variables other than one.
+    WrongArmArity { arm: usize, n_vars: usize },
+}
+impl MatchInfoError {
+    pub fn to_message(&self) -> String {
+        match self {
+            MatchInfoError::DuplicateSelector { selector, first_arm, second_arm } => {
+                let selector = match selector {
+                    MatchArmSelector::VariantId(variant) => format!("variant #{}", variant.idx),
+                    MatchArmSelector::Value(value) => format!("value {}", value.value),
+                };
+                format!("Arms {first_arm} and {second_arm} both select {selector}")
+            }
+            MatchInfoError::ArmOutOfOrder { arm, selected } => {
+                format!("Arm {arm} selects the branch at position {selected}")
+            }
+            MatchInfoError::WrongArmArity { arm, n_vars } => {
+                format!("Arm {arm} binds {n_vars} variables instead of one")
+            }
         }
     }
 }
 
+/// Validates that every arm of the match has a distinct selector - no two arms select the same
+/// variant, or the same value.
+pub fn validate_match_info(info: &MatchInfo) -> Result<(), MatchInfoError> {
+    let mut selectors = UnorderedHashMap::<&MatchArmSelector, usize>::default();
+    for (arm_idx, arm) in info.arms().iter().enumerate() {
+        if let Some(prev) = selectors.insert(&arm.arm_selector, arm_idx) {
+            return Err(MatchInfoError::DuplicateSelector {
+                selector: arm.arm_selector.clone(),
+                first_arm: prev,
+                second_arm: arm_idx,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Validates that the arms of the match are ordered as the branches they select, and that the arms
+/// of matches on an enum or on an enum value bind exactly the variable of their variant.
+pub fn validate_match_arms_layout(info: &MatchInfo) -> Result<(), MatchInfoError> {
+    for (arm_idx, arm) in info.arms().iter().enumerate() {
+        let selected = match &arm.arm_selector {
+            MatchArmSelector::VariantId(variant) => variant.idx,
+            MatchArmSelector::Value(value) => value.value,
+        };
+        if selected != arm_idx {
+            return Err(MatchInfoError::ArmOutOfOrder { arm: arm_idx, selected });
+        }
+        if !matches!(info, MatchInfo::Extern(_)) && arm.var_ids.len() != 1 {
+            return Err(MatchInfoError::WrongArmArity { arm: arm_idx, n_vars: arm.var_ids.len() });
+        }
+    }
+    Ok(())
+}
+
 /// Validates that the lowering structure is valid.
 ///
 /// Currently only does basic SSA validations.
@@ -102,6 +172,8 @@ pub fn validate(lowered: &FlatLowered) -> Result<(), ValidationError> {
                 stack.push(*target_block_id);
             }
             crate::FlatBlockEnd::Match { info } => {
+                validate_match_info(info)
+                    .map_err(|err| ValidationError::InvalidMatch(block_id, err))?;
                 for input in info.inputs() {
                     if !introductions.contains_key(&input.var_id) {
                         return Err(ValidationError::UnknownUsageInEnd(input.var_id, block_id));
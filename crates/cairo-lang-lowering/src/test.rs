@@ -1,27 +1,57 @@
 use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::Arc;
 
 use cairo_lang_debug::DebugWithDb;
 use cairo_lang_defs::db::DefsGroup;
 use cairo_lang_defs::diagnostic_utils::StableLocation;
-use cairo_lang_defs::ids::LanguageElementId;
+use cairo_lang_defs::ids::{FunctionWithBodyId, LanguageElementId, NamedLanguageElementId};
+use cairo_lang_defs::patcher::{PatchBuilder, RewriteNode};
+use cairo_lang_defs::plugin::{
+    InlineMacroExprPlugin, InlinePluginResult, MacroPluginMetadata, NamedPlugin,
+    PluginGeneratedFile,
+};
+use cairo_lang_defs::plugin_utils::unsupported_bracket_diagnostic;
 use cairo_lang_diagnostics::{DiagnosticNote, DiagnosticsBuilder};
+use cairo_lang_filesystem::db::FilesGroupEx;
+use cairo_lang_filesystem::flag::{DiagnosticLevel, Flag};
+use cairo_lang_filesystem::ids::FlagId;
 use cairo_lang_semantic as semantic;
-use cairo_lang_semantic::db::SemanticGroup;
-use cairo_lang_semantic::test_utils::{setup_test_expr, setup_test_function, setup_test_module};
-use cairo_lang_syntax::node::{Terminal, TypedStablePtr};
+use cairo_lang_semantic::corelib;
+use cairo_lang_semantic::db::{PluginSuiteInput, SemanticGroup};
+use cairo_lang_semantic::inline_macros::get_default_plugin_suite;
+use cairo_lang_semantic::items::constant::ConstValue;
+use cairo_lang_semantic::test_utils::{
+    setup_test_crate, setup_test_expr, setup_test_function, setup_test_module,
+};
+use cairo_lang_syntax::node::db::SyntaxGroup;
+use cairo_lang_syntax::node::{Terminal, TypedStablePtr, ast};
 use cairo_lang_test_utils::parse_test_file::TestRunnerResult;
 use cairo_lang_test_utils::verify_diagnostics_expectation;
 use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
-use cairo_lang_utils::{LookupIntern, Upcast, extract_matches};
+use cairo_lang_utils::{Intern, LookupIntern, Upcast, extract_matches};
 use itertools::Itertools;
+use num_bigint::{BigInt, Sign};
 use pretty_assertions::assert_eq;
 
-use crate::FlatLowered;
 use crate::db::LoweringGroup;
-use crate::diagnostic::{LoweringDiagnostic, LoweringDiagnosticKind};
+use crate::diagnostic::{LoweringDiagnostic, LoweringDiagnosticKind, MatchDiagnostic};
 use crate::fmt::LoweredFormatter;
-use crate::ids::{ConcreteFunctionWithBodyId, LocationId};
+use crate::ids::{
+    ConcreteFunctionWithBodyId, ConcreteFunctionWithBodyLongId, FunctionWithBodyLongId, LocationId,
+    Signature,
+};
+use crate::lower::context::{EncapsulatingLoweringContext, LoweringContext};
+use crate::lower::{
+    MatchCoverageBaseline, MatchStrategy, crate_match_strategy_histogram,
+    is_optimized_extern_match, lower_function, lower_semantic_function,
+    lower_semantic_function_with_arm_transform, lower_semantic_function_with_coverage_baseline,
+    match_arm_coverage, match_missing_variants,
+};
+use crate::optimizations::strategy::OptimizationPhase;
+use crate::optimizations::validate::validate_match_info;
 use crate::test_utils::LoweringDatabaseForTesting;
+use crate::{DependencyType, FlatBlockEnd, FlatLowered, MatchInfo, Statement, StatementConst};
 
 cairo_lang_test_utils::test_file_test!(
     lowering,
@@ -64,7 +94,7 @@ fn test_function_lowering(
     inputs: &OrderedHashMap<String, String>,
     args: &OrderedHashMap<String, String>,
 ) -> TestRunnerResult {
-    let db = &mut LoweringDatabaseForTesting::default();
+    let db = &mut test_db_with_flags(inputs);
     let (test_function, semantic_diagnostics) = setup_test_function(
         db,
         inputs["function"].as_str(),
@@ -101,6 +131,73 @@ fn test_function_lowering(
     }
 }
 
+/// Parses the value of a flag given in the inputs of a test into the flag.
+type ParseFlag = fn(&str) -> Flag;
+
+/// The flags that may be set by the inputs of lowering tests, with the parsers of their values.
+const TEST_FLAGS: [(&str, ParseFlag); 9] = [
+    ("nonexhaustive_match_severity", |value| {
+        Flag::NonExhaustiveMatchSeverity(match value {
+            "error" => DiagnosticLevel::Error,
+            "warning" => DiagnosticLevel::Warning,
+            "allow" => DiagnosticLevel::Allow,
+            _ => panic!("Unknown diagnostic level `{value}`."),
+        })
+    }),
+    ("aggregate_unreachable_match_arms", |value| {
+        Flag::AggregateUnreachableMatchArms(value.parse().unwrap())
+    }),
+    ("collect_all_match_arm_diagnostics", |value| {
+        Flag::CollectAllMatchArmDiagnostics(value.parse().unwrap())
+    }),
+    ("bind_match_arm_index", |value| Flag::BindMatchArmIndex(value.parse().unwrap())),
+    ("pool_match_consts", |value| Flag::PoolMatchConsts(value.parse().unwrap())),
+    ("validate_match_lowering", |value| Flag::ValidateMatchLowering(value.parse().unwrap())),
+    ("outline_match_arms", |value| Flag::OutlineMatchArms(value.parse().unwrap())),
+    ("branchless_select", |value| Flag::BranchlessSelect(value.parse().unwrap())),
+    ("emit_catch_all_events", |value| Flag::EmitCatchAllEvents(value.parse().unwrap())),
+];
+
+/// Returns a db with the flags of [TEST_FLAGS] given in `inputs` set.
+fn test_db_with_flags(inputs: &OrderedHashMap<String, String>) -> LoweringDatabaseForTesting {
+    if !TEST_FLAGS.iter().any(|(name, _)| inputs.contains_key(*name)) {
+        return LoweringDatabaseForTesting::default();
+    }
+    // Flags can't be set on a snapshot of the shared db, so a fresh db is used.
+    let mut db = LoweringDatabaseForTesting::new();
+    for (name, parse_flag) in TEST_FLAGS {
+        if let Some(value) = inputs.get(name) {
+            let flag_id = FlagId::new(db.upcast(), name);
+            db.set_flag(flag_id, Some(Arc::new(parse_flag(value))));
+        }
+    }
+    db
+}
+
+/// Calls `f` with a lowering context of `function_id` and the match at the tail of its body.
+fn with_tail_match<R>(
+    db: &dyn LoweringGroup,
+    function_id: FunctionWithBodyId,
+    f: impl FnOnce(&mut LoweringContext<'_, '_>, &semantic::ExprMatch) -> R,
+) -> R {
+    let mut encapsulating_ctx = EncapsulatingLoweringContext::new(db, function_id).unwrap();
+    let signature =
+        Signature::from_semantic(db, db.function_with_body_signature(function_id).unwrap());
+    let return_type = signature.return_type;
+    let lowered_function_id = FunctionWithBodyLongId::Semantic(function_id).intern(db);
+    let mut ctx =
+        LoweringContext::new(&mut encapsulating_ctx, lowered_function_id, signature, return_type)
+            .unwrap();
+    let function_body = ctx.function_body.clone();
+    let body = extract_matches!(
+        &function_body.arenas.exprs[function_body.body_expr],
+        semantic::Expr::Block
+    );
+    let match_expr =
+        extract_matches!(&function_body.arenas.exprs[body.tail.unwrap()], semantic::Expr::Match);
+    f(&mut ctx, match_expr)
+}
+
 fn formatted_lowered(db: &dyn LoweringGroup, lowered: &FlatLowered) -> String {
     let lowered_formatter = LoweredFormatter::new(db, &lowered.variables);
     format!("{:?}", lowered.debug(&lowered_formatter))
@@ -226,3 +323,1142 @@ fn test_sizes() {
         assert_eq!(size, expected_size, "Wrong size for type alias `{}`", ty.format(db.upcast()));
     }
 }
+
+#[test]
+fn test_validate_match_info() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        "fn foo(a: Option<felt252>) -> felt252 { match a { Some(x) => x, None => 0 } }",
+        "foo",
+        "",
+    )
+    .unwrap();
+    let function_id =
+        ConcreteFunctionWithBodyId::from_semantic(db, test_function.concrete_function_id);
+    let lowered = db.concrete_function_with_body_postpanic_lowered(function_id).unwrap();
+    let FlatBlockEnd::Match { info } = &lowered.blocks.root_block().unwrap().end else {
+        panic!("Expected the root block to end with a match.");
+    };
+    assert!(validate_match_info(info).is_ok());
+
+    // Duplicate the first arm so that the match selects the same variant twice.
+    let mut info = info.clone();
+    let MatchInfo::Enum(enum_info) = &mut info else {
+        panic!("Expected an enum match.");
+    };
+    enum_info.arms[1].arm_selector = enum_info.arms[0].arm_selector.clone();
+    assert_eq!(
+        validate_match_info(&info).unwrap_err().to_message(),
+        "Arms 0 and 1 both select variant #0"
+    );
+}
+
+#[test]
+fn test_match_arm_transform() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            fn foo(a: Option<felt252>, b: felt252) -> felt252 {
+                let c = match a {
+                    Some(x) => x,
+                    None => 0,
+                };
+                match b {
+                    0 => c,
+                    _ => 1,
+                }
+            }
+        "},
+        "foo",
+        "extern fn log(value: felt252) -> felt252 nopanic;",
+    )
+    .unwrap();
+    let log_function = corelib::get_function_id(db, test_function.module_id, "log".into(), vec![]);
+
+    // Wrap the body of each arm in a call to `log`.
+    let mut n_transformed_arms = 0;
+    let lowering = lower_semantic_function_with_arm_transform(
+        db,
+        test_function.function_id,
+        Box::new(|arenas: &mut semantic::Arenas, expr_id| {
+            n_transformed_arms += 1;
+            let expr = &arenas.exprs[expr_id];
+            let (ty, stable_ptr) = (expr.ty(), expr.stable_ptr());
+            arenas.exprs.alloc(semantic::Expr::FunctionCall(semantic::ExprFunctionCall {
+                function: log_function,
+                args: vec![semantic::ExprFunctionCallArg::Value(expr_id)],
+                coupon_arg: None,
+                ty,
+                stable_ptr,
+            }))
+        }),
+    )
+    .unwrap();
+    assert_eq!(n_transformed_arms, 4);
+    let lowered = formatted_lowered(db, &lowering.main_lowering);
+    assert_eq!(lowered.matches("test::log(").count(), 4, "{lowered}");
+}
+
+#[test]
+fn test_match_arm_without_patterns() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            fn foo(a: Option<felt252>, b: bool) -> felt252 {
+                match b {
+                    true => match a {
+                        Some(x) => x,
+                        None => 0,
+                    },
+                    false => 1,
+                }
+            }
+        "},
+        "foo",
+        "",
+    )
+    .unwrap();
+
+    // Drop the patterns of the last arm of the inner match, which can't be expressed in code.
+    let lowering = lower_semantic_function_with_arm_transform(
+        db,
+        test_function.function_id,
+        Box::new(|arenas: &mut semantic::Arenas, expr_id| {
+            if let semantic::Expr::Match(expr) = &mut arenas.exprs[expr_id] {
+                expr.arms.last_mut().unwrap().patterns.clear();
+            }
+            expr_id
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        lowering.main_lowering.diagnostics.format(db),
+        indoc::indoc! {"
+            error: Match arm has no patterns.
+             --> lib.cairo:5:21
+                        None => 0,
+                                ^
+
+        "}
+    );
+}
+
+#[test]
+fn test_match_arm_variant_of_another_enum() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            fn foo(a: MyEnum, b: Other, c: bool) -> felt252 {
+                let x = match b {
+                    Other::X => 0,
+                    Other::Y => 1,
+                };
+                match c {
+                    true => match a {
+                        MyEnum::A => x,
+                        MyEnum::B => 1,
+                    },
+                    false => 2,
+                }
+            }
+        "},
+        "foo",
+        indoc::indoc! {"
+            #[derive(Drop)]
+            enum MyEnum {
+                A,
+                B,
+            }
+            #[derive(Drop)]
+            enum Other {
+                X,
+                Y,
+            }
+        "},
+    )
+    .unwrap();
+
+    // Replace the pattern of the last arm of the inner match with a pattern of the first match,
+    // which the semantic model never produces.
+    let lowering = lower_semantic_function_with_arm_transform(
+        db,
+        test_function.function_id,
+        Box::new(|arenas: &mut semantic::Arenas, expr_id| {
+            let semantic::Expr::Match(expr) = &arenas.exprs[expr_id] else {
+                return expr_id;
+            };
+            let matched_enum = extract_matches!(
+                &arenas.patterns[expr.arms[0].patterns[0]],
+                semantic::Pattern::EnumVariant
+            )
+            .variant
+            .concrete_enum_id;
+            let other_pattern = arenas
+                .patterns
+                .iter()
+                .find_map(|(pattern_id, pattern)| match pattern {
+                    semantic::Pattern::EnumVariant(pattern)
+                        if pattern.variant.concrete_enum_id != matched_enum =>
+                    {
+                        Some(pattern_id)
+                    }
+                    _ => None,
+                })
+                .unwrap();
+            let semantic::Expr::Match(expr) = &mut arenas.exprs[expr_id] else { unreachable!() };
+            expr.arms.last_mut().unwrap().patterns = vec![other_pattern];
+            expr_id
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        lowering.main_lowering.diagnostics.format(db),
+        indoc::indoc! {"
+            error: Variant `test::Other::X` does not belong to the matched enum `test::MyEnum`. \
+             Valid variants are: `A`, `B`.
+             --> lib.cairo:14:9
+                    Other::X => 0,
+                    ^^^^^^^^
+
+        "}
+    );
+}
+
+#[test]
+fn test_match_arm_coverage() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            fn foo(a: MyEnum) -> felt252 {
+                match a {
+                    MyEnum::B => 0,
+                    _ => 1,
+                    MyEnum::A => 2,
+                }
+            }
+        "},
+        "foo",
+        indoc::indoc! {"
+            #[derive(Drop)]
+            enum MyEnum {
+                A,
+                B,
+                C,
+            }
+        "},
+    )
+    .unwrap();
+
+    let db = &*db;
+    // The unreachable arm following the catch-all arm is not reported by the query.
+    let coverage = with_tail_match(db, test_function.function_id, |ctx, match_expr| {
+        let coverage = match_arm_coverage(ctx, match_expr).unwrap();
+        assert!(ctx.diagnostics.leaves.is_empty());
+        coverage
+    });
+    let variant_names = coverage
+        .iter()
+        .map(|variants| variants.iter().map(|variant| variant.id.name(db)).collect_vec())
+        .collect_vec();
+    assert_eq!(variant_names, [vec!["B"], vec!["A", "C"], vec![]]);
+}
+
+/// Returns the paths not covered by the match at the tail of `foo`, with the variants of each path
+/// joined by commas. Asserts that no diagnostics are reported.
+fn match_missing_variant_names(function_code: &str, module_code: &str) -> Vec<String> {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(db, function_code, "foo", module_code).unwrap();
+
+    let db = &*db;
+    let missing_paths = with_tail_match(db, test_function.function_id, |ctx, match_expr| {
+        let missing_paths = match_missing_variants(ctx, match_expr).unwrap();
+        assert!(ctx.diagnostics.leaves.is_empty());
+        missing_paths
+    });
+    missing_paths
+        .iter()
+        .map(|path| path.variants.iter().map(|variant| variant.id.name(db)).join(", "))
+        .collect()
+}
+
+#[test]
+fn test_match_missing_variants() {
+    let module_code = indoc::indoc! {"
+        #[derive(Drop)]
+        enum MyEnum {
+            A,
+            B,
+            C,
+        }
+    "};
+    let missing = match_missing_variant_names(
+        indoc::indoc! {"
+            fn foo(a: MyEnum) -> felt252 {
+                match a {
+                    MyEnum::B => 0,
+                    MyEnum::B => 1,
+                }
+            }
+        "},
+        module_code,
+    );
+    assert_eq!(missing, ["A", "C"]);
+
+    let missing = match_missing_variant_names(
+        indoc::indoc! {"
+            fn foo(a: MyEnum) -> felt252 {
+                match a {
+                    MyEnum::B => 0,
+                    _ => 1,
+                }
+            }
+        "},
+        module_code,
+    );
+    assert!(missing.is_empty());
+}
+
+#[test]
+fn test_match_missing_variants_tuple() {
+    let missing = match_missing_variant_names(
+        indoc::indoc! {"
+            fn foo(a: MyEnum, b: MyEnum, c: MyEnum) -> felt252 {
+                match (a, (b, c)) {
+                    (MyEnum::A, _) => 0,
+                    (_, (MyEnum::B, _)) => 1,
+                    (MyEnum::A, (MyEnum::B, MyEnum::A)) => 2,
+                }
+            }
+        "},
+        indoc::indoc! {"
+            #[derive(Drop)]
+            enum MyEnum {
+                A,
+                B,
+            }
+        "},
+    );
+    assert_eq!(missing, ["B, A, A", "B, A, B"]);
+}
+
+#[test]
+fn test_match_coverage_baseline() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            fn foo(a: MyEnum) -> felt252 {
+                match a {
+                    MyEnum::B => 0,
+                    _ => 1,
+                }
+            }
+        "},
+        "foo",
+        indoc::indoc! {"
+            #[derive(Drop)]
+            enum MyEnum {
+                A,
+                B,
+                C,
+            }
+        "},
+    )
+    .unwrap();
+
+    // `A` was handled by an explicit arm in the baseline, and is now handled by the catch-all arm.
+    let baseline = MatchCoverageBaseline::parse("test::foo#0: A, B\n");
+    let (lowering, coverage) = lower_semantic_function_with_coverage_baseline(
+        db,
+        test_function.function_id,
+        baseline.into(),
+    )
+    .unwrap();
+    assert_eq!(
+        lowering.main_lowering.diagnostics.format(db),
+        indoc::indoc! {"
+            error: Match no longer handles variants handled explicitly in the coverage baseline: `A`.
+             --> lib.cairo:9:5-12:5
+                  match a {
+             _____^
+            | ...
+            |     }
+            |_____^
+
+        "}
+    );
+    assert_eq!(coverage.to_string(), "test::foo#0: B\n");
+}
+
+#[test]
+fn test_match_on_nested_generic_enums_payload_types() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            fn foo(a: @Option<Result<Array<felt252>, felt252>>) -> usize {
+                match a {
+                    Some(r) => match r {
+                        Ok(arr) => arr.len(),
+                        Err(_) => 1,
+                    },
+                    None => 0,
+                }
+            }
+        "},
+        "foo",
+        "",
+    )
+    .unwrap();
+
+    let lowering = lower_semantic_function(db, test_function.function_id).unwrap();
+    let lowered = &lowering.main_lowering;
+    // The payload types must be the substituted types of the variants, wrapped in the snapshots of
+    // the matched value.
+    let payload_types = lowered
+        .blocks
+        .iter()
+        .filter_map(|(_, block)| match &block.end {
+            FlatBlockEnd::Match { info: MatchInfo::Enum(info) } => Some(&info.arms),
+            _ => None,
+        })
+        .flatten()
+        .map(|arm| lowered.variables[arm.var_ids[0]].ty.format(db))
+        .collect_vec();
+    assert_eq!(
+        payload_types,
+        [
+            "@core::result::Result::<core::array::Array::<core::felt252>, core::felt252>",
+            "@()",
+            "@core::array::Array::<core::felt252>",
+            "@core::felt252",
+        ]
+    );
+}
+
+#[test]
+fn test_felt252_match_consecutive_literals_subtractions() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            #[match_strategy(if_chain)]
+            fn foo(a: felt252) -> felt252 {
+                match a {
+                    0 => 10,
+                    1 => 20,
+                    2 => 30,
+                    3 => 40,
+                    _ => 0,
+                }
+            }
+        "},
+        "foo",
+        "",
+    )
+    .unwrap();
+
+    let lowering = lower_semantic_function(db, test_function.function_id).unwrap();
+    let lowered = formatted_lowered(db, &lowering.main_lowering);
+    // Only the first nonzero literal is subtracted from the matched value, the following ones
+    // subtract 1 from the previous difference.
+    assert_eq!(lowered.matches("core::Felt252Sub::sub(").count(), 3, "{lowered}");
+    assert_eq!(lowered.matches("core::Felt252Sub::sub(v0, ").count(), 1, "{lowered}");
+}
+
+#[test]
+fn test_match_lowering_is_deterministic() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            fn foo(a: MyEnum, b: Option<felt252>, c: felt252) -> felt252 {
+                let x = match (a, b) {
+                    (MyEnum::A, Some(x)) | (MyEnum::B(x), _) => x,
+                    (MyEnum::C, None) => 1,
+                    _ => 2,
+                };
+                match c {
+                    0 | 1 => x,
+                    2 => match a {
+                        MyEnum::A | MyEnum::C => 3,
+                        MyEnum::B(y) => y,
+                    },
+                    _ => 4,
+                }
+            }
+        "},
+        "foo",
+        indoc::indoc! {"
+            #[derive(Copy, Drop)]
+            enum MyEnum {
+                A,
+                B: felt252,
+                C,
+            }
+        "},
+    )
+    .unwrap();
+
+    // Lowering directly bypasses the query cache, so each call allocates the blocks anew.
+    let lowerings: Vec<_> = (0..2)
+        .map(|_| {
+            let lowering = lower_semantic_function(db, test_function.function_id).unwrap();
+            formatted_lowered(db, &lowering.main_lowering)
+        })
+        .collect();
+    assert_eq!(lowerings[0], lowerings[1]);
+}
+
+#[test]
+fn test_match_on_enum_with_many_variants() {
+    const N_VARIANTS: usize = 256;
+    let variants = (0..N_VARIANTS).map(|i| format!("V{i}: felt252,")).join("\n");
+    let arms = (0..N_VARIANTS).map(|i| format!("MyEnum::V{i}(x) => x + {i},")).join("\n");
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        &format!("fn foo(a: MyEnum) -> felt252 {{ match a {{ {arms} }} }}"),
+        "foo",
+        &format!("#[derive(Drop)] enum MyEnum {{ {variants} }}"),
+    )
+    .unwrap();
+
+    // Each of the variants gets its own arm in a single match on the enum.
+    let lowering = lower_semantic_function(db, test_function.function_id).unwrap();
+    let lowered = &lowering.main_lowering;
+    assert!(lowered.diagnostics.get_all().is_empty(), "{:?}", lowered.diagnostics.get_all());
+    let root_block = lowered.blocks.root_block().unwrap();
+    let FlatBlockEnd::Match { info: MatchInfo::Enum(info) } = &root_block.end else {
+        panic!("Expected the root block to end with a match on the enum.");
+    };
+    assert_eq!(info.arms.len(), N_VARIANTS);
+}
+
+#[test]
+fn test_match_on_tuple_of_enums_with_many_variants() {
+    const N_VARIANTS: usize = 50;
+    let variants = (0..N_VARIANTS).map(|i| format!("V{i},")).join("\n");
+    let arms = (0..N_VARIANTS).map(|i| format!("(MyEnum::V{i}, MyEnum::V{i}) => {i},")).join("\n");
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        &format!("fn foo(a: MyEnum, b: MyEnum) -> felt252 {{ match (a, b) {{ {arms} _ => 0, }} }}"),
+        "foo",
+        &format!("#[derive(Drop)] enum MyEnum {{ {variants} }}"),
+    )
+    .unwrap();
+
+    // The variants of the enum are shared by both members of the tuple: each variant of the first
+    // member leads to a match on the second.
+    let lowering = lower_semantic_function(db, test_function.function_id).unwrap();
+    let lowered = &lowering.main_lowering;
+    assert!(lowered.diagnostics.get_all().is_empty(), "{:?}", lowered.diagnostics.get_all());
+    let root_block = lowered.blocks.root_block().unwrap();
+    let FlatBlockEnd::Match { info: MatchInfo::Enum(info) } = &root_block.end else {
+        panic!("Expected the root block to end with a match on the enum.");
+    };
+    assert_eq!(info.arms.len(), N_VARIANTS);
+    for arm in &info.arms {
+        let FlatBlockEnd::Match { info: MatchInfo::Enum(inner_info) } =
+            &lowered.blocks[arm.block_id].end
+        else {
+            panic!("Expected each arm to match on the second member.");
+        };
+        assert_eq!(inner_info.arms.len(), N_VARIANTS);
+    }
+}
+
+#[test]
+fn test_match_arm_diagnostics_reported_once() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            fn foo(a: Option<Option<felt252>>, b: Option<felt252>) -> felt252 {
+                match (a, b) {
+                    (Some(Some(_)), _) | (None, Some(_)) => 1,
+                    _ => 0,
+                }
+            }
+        "},
+        "foo",
+        "",
+    )
+    .unwrap();
+
+    // The inner pattern is lowered for each leaf of the arm, but is reported only once.
+    let lowering = lower_semantic_function(db, test_function.function_id).unwrap();
+    let diagnostics = &lowering.main_lowering.diagnostics;
+    assert_eq!(diagnostics.get_all().len(), 1, "{}", diagnostics.format(db));
+    assert_eq!(
+        diagnostics.format(db),
+        indoc::indoc! {"
+            error: Inner patterns are not allowed in this context.
+             --> lib.cairo:3:15
+                    (Some(Some(_)), _) | (None, Some(_)) => 1,
+                          ^^^^^^^
+
+        "}
+    );
+}
+
+#[test]
+fn test_non_exhaustive_numeric_match_suggestion() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            fn foo(a: felt252) -> felt252 {
+                match a {
+                    0 => 1,
+                    1 => 2,
+                }
+            }
+        "},
+        "foo",
+        "",
+    )
+    .unwrap();
+
+    let lowering = lower_semantic_function(db, test_function.function_id).unwrap();
+    let diagnostics = lowering.main_lowering.diagnostics.get_all();
+    let [LoweringDiagnostic { kind: LoweringDiagnosticKind::MatchError(match_error), .. }] =
+        &diagnostics[..]
+    else {
+        panic!("Expected a single match error, got: {diagnostics:?}");
+    };
+    let MatchDiagnostic::NonExhaustiveMatchFelt252(suggestion) = &match_error.error else {
+        panic!("Expected a non exhaustive numeric match, got: {match_error:?}");
+    };
+    // The wildcard arm is inserted before the closing brace of the match.
+    assert_eq!(suggestion.pattern, "_");
+    assert_eq!(
+        suggestion.insert_before.lookup(db).get_text_without_trivia(db),
+        "}",
+        "Expected the suggestion to point at the closing brace of the match."
+    );
+}
+
+/// An inline macro expanding `missing_none_arm!(x)` into a match on `x` without an arm for
+/// `Option::None`.
+#[derive(Debug, Default)]
+struct MissingNoneArmMacro;
+impl NamedPlugin for MissingNoneArmMacro {
+    const NAME: &'static str = "missing_none_arm";
+}
+impl InlineMacroExprPlugin for MissingNoneArmMacro {
+    fn generate_code(
+        &self,
+        db: &dyn SyntaxGroup,
+        syntax: &ast::ExprInlineMacro,
+        _metadata: &MacroPluginMetadata<'_>,
+    ) -> InlinePluginResult {
+        let ast::WrappedArgList::ParenthesizedArgList(arguments_syntax) = syntax.arguments(db)
+        else {
+            return unsupported_bracket_diagnostic(db, syntax);
+        };
+        let mut builder = PatchBuilder::new(db, syntax);
+        builder.add_modified(RewriteNode::interpolate_patched(
+            "match $arg$ {\n    Option::Some(x) => x,\n}",
+            &[("arg".to_string(), RewriteNode::from_ast_trimmed(&arguments_syntax.arguments(db)))]
+                .into(),
+        ));
+        let (content, code_mappings) = builder.build();
+        InlinePluginResult {
+            code: Some(PluginGeneratedFile {
+                name: format!("{}_macro", Self::NAME).into(),
+                content,
+                code_mappings,
+                aux_data: None,
+                diagnostics_note: Default::default(),
+            }),
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[test]
+fn test_macro_expanded_match_diagnostics() {
+    let mut db = LoweringDatabaseForTesting::new();
+    let mut suite = get_default_plugin_suite();
+    suite.add_inline_macro_plugin::<MissingNoneArmMacro>();
+    let suite = db.intern_plugin_suite(suite);
+    db.set_default_plugins_from_suite(suite);
+    let test_function = setup_test_function(
+        &db,
+        indoc::indoc! {"
+            fn foo(a: Option<felt252>) -> felt252 {
+                missing_none_arm!(a)
+            }
+        "},
+        "foo",
+        "",
+    )
+    .unwrap();
+
+    // The missing arm is reported at the macro call site, with the match in the expanded code.
+    let lowering = lower_semantic_function(&db, test_function.function_id).unwrap();
+    assert_eq!(
+        lowering.main_lowering.diagnostics.format(&db),
+        indoc::indoc! {"
+            error: Missing match arm: `None` not covered.
+             --> lib.cairo:2:5
+                missing_none_arm!(a)
+                ^^^^^^^^^^^^^^^^^^^^
+            note: the match is in code expanded from a macro, at lib.cairo[missing_none_arm_macro]:1:1-3:1
+              match a {
+             _^
+            |     Option::Some(x) => x,
+            | }
+            |_^
+
+        "}
+    );
+}
+
+/// The name and the formatted fields of a span recorded by a [SpanRecorder].
+#[cfg(feature = "tracing")]
+struct RecordedSpan {
+    name: &'static str,
+    fields: Vec<String>,
+}
+
+/// A tracing subscriber recording the name and fields of every created span.
+#[cfg(feature = "tracing")]
+#[derive(Clone, Default)]
+struct SpanRecorder {
+    spans: Arc<std::sync::Mutex<Vec<RecordedSpan>>>,
+}
+
+/// Formats the visited fields of a span as `name=value`.
+#[cfg(feature = "tracing")]
+struct SpanFieldsVisitor<'a>(&'a mut Vec<String>);
+#[cfg(feature = "tracing")]
+impl tracing::field::Visit for SpanFieldsVisitor<'_> {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.0.push(format!("{}={value:?}", field.name()));
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl tracing::Subscriber for SpanRecorder {
+    fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, attrs: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        let mut spans = self.spans.lock().unwrap();
+        let mut fields = vec![];
+        attrs.record(&mut SpanFieldsVisitor(&mut fields));
+        spans.push(RecordedSpan { name: attrs.metadata().name(), fields });
+        tracing::span::Id::from_u64(spans.len() as u64)
+    }
+    fn record(&self, span: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        values.record(&mut SpanFieldsVisitor(&mut spans[span.into_u64() as usize - 1].fields));
+    }
+    fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+    fn event(&self, _event: &tracing::Event<'_>) {}
+    fn enter(&self, _span: &tracing::span::Id) {}
+    fn exit(&self, _span: &tracing::span::Id) {}
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn test_match_lowering_spans() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            #[match_strategy(jump_table)]
+            fn foo(a: felt252) -> felt252 {
+                match a {
+                    0 => 1,
+                    1 | 2 => 2,
+                    _ => 3,
+                }
+            }
+        "},
+        "foo",
+        "",
+    )
+    .unwrap();
+    let recorder = SpanRecorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        db.priv_function_with_body_multi_lowering(test_function.function_id).unwrap();
+    });
+    let spans = recorder
+        .spans
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|span| span.name.contains("match"))
+        .map(|RecordedSpan { name, fields }| format!("{name}: {}", fields.join(", ")))
+        .collect_vec();
+    assert_eq!(
+        spans,
+        [
+            "lower_expr_match: n_arms=3",
+            "lower_expr_match_felt252: n_arms=3, n_variants=3, strategy=jump_table",
+            "group_match_arms: n_arms=3, n_leaves=4",
+        ]
+    );
+}
+
+#[test]
+fn test_while_let_iterator_match_optimization() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            fn foo(mut it: Countdown) -> felt252 {
+                let mut sum = 0;
+                while let Some((x, rest)) = it.next() {
+                    sum += x;
+                    it = rest;
+                }
+                sum
+            }
+        "},
+        "foo",
+        indoc::indoc! {"
+            #[derive(Copy, Drop)]
+            struct Countdown {
+                n: felt252,
+            }
+            #[generate_trait]
+            impl CountdownImpl of CountdownTrait {
+                #[inline(always)]
+                fn next(self: Countdown) -> Option<(felt252, Countdown)> {
+                    if self.n == 0 {
+                        None
+                    } else {
+                        Some((self.n, Countdown { n: self.n - 1 }))
+                    }
+                }
+            }
+        "},
+    )
+    .unwrap();
+    let db = &*db;
+    let function_id =
+        ConcreteFunctionWithBodyId::from_semantic(db, test_function.concrete_function_id);
+    let [loop_function_id] = db
+        .final_concrete_function_with_body_lowered_direct_callees(function_id, DependencyType::Call)
+        .unwrap()
+        .into_iter()
+        .filter(|callee| {
+            matches!(callee.lookup_intern(db), ConcreteFunctionWithBodyLongId::Generated(_))
+        })
+        .collect_vec()[..]
+    else {
+        panic!("Expected a single generated loop function.");
+    };
+
+    // The `Option` returned by the inlined `next` is matched right after it is constructed, so
+    // the match optimizer dispatches each of its constructions directly to the arm it reaches.
+    let mut before =
+        db.concrete_function_with_body_postpanic_lowered(loop_function_id).unwrap().deref().clone();
+    for phase in [
+        OptimizationPhase::ApplyInlining,
+        OptimizationPhase::ReorganizeBlocks,
+        OptimizationPhase::ReorderStatements,
+    ] {
+        phase.apply(db, loop_function_id, &mut before).unwrap();
+    }
+    let mut after = before.clone();
+    for phase in [OptimizationPhase::OptimizeMatches, OptimizationPhase::ReorganizeBlocks] {
+        phase.apply(db, loop_function_id, &mut after).unwrap();
+    }
+    let n_option_matches = |lowered: &FlatLowered| {
+        lowered
+            .blocks
+            .iter()
+            .filter(|(_, block)| {
+                matches!(&block.end, FlatBlockEnd::Match { info: MatchInfo::Enum(info) }
+                    if info.concrete_enum_id.enum_id(db).name(db) == "Option")
+            })
+            .count()
+    };
+    assert_eq!(n_option_matches(&before), 1);
+    assert_eq!(n_option_matches(&after), 0);
+    assert!(
+        after.blocks.len() < before.blocks.len(),
+        "Expected fewer blocks after the match optimization: {} >= {}.",
+        after.blocks.len(),
+        before.blocks.len()
+    );
+}
+
+#[test]
+fn test_tuple_match_tree_depth() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            fn foo(a: Option<felt252>, b: Option<felt252>, c: bool) -> felt252 {
+                match (a, b, c) {
+                    (Some(x), Some(_), true) => x,
+                    (None, Some(y), _) => y,
+                    _ => 0,
+                }
+            }
+        "},
+        "foo",
+        "",
+    )
+    .unwrap();
+
+    let db = &*db;
+    let mut encapsulating_ctx =
+        EncapsulatingLoweringContext::new(db, test_function.function_id).unwrap();
+    let signature = db.function_with_body_signature(test_function.function_id).unwrap();
+    for param in &signature.params {
+        encapsulating_ctx
+            .semantic_defs
+            .insert(semantic::VarId::Param(param.id), semantic::Binding::Param(param.clone()));
+    }
+    let function_id = FunctionWithBodyLongId::Semantic(test_function.function_id).intern(db);
+    let body_expr = encapsulating_ctx.function_body.body_expr;
+    lower_function(
+        &mut encapsulating_ctx,
+        function_id,
+        Signature::from_semantic(db, signature),
+        body_expr,
+    )
+    .unwrap();
+
+    // A full tuple match has a level in its decision tree for each of the enums of the tuple.
+    assert_eq!(encapsulating_ctx.tuple_match_tree_depths.values().copied().collect_vec(), [3]);
+}
+
+#[test]
+fn test_crate_match_strategy_histogram() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let crate_id = setup_test_crate(
+        db,
+        indoc::indoc! {"
+            fn enum_match(a: Option<felt252>) -> felt252 {
+                match a {
+                    Option::Some(x) => x,
+                    Option::None => 0,
+                }
+            }
+            fn extern_match(a: u8, b: u8) -> u8 {
+                match core::integer::u8_overflowing_add(a, b) {
+                    Result::Ok(x) => x,
+                    Result::Err(x) => x,
+                }
+            }
+            fn if_chain_match(a: felt252) -> felt252 {
+                match a {
+                    0 => 1,
+                    _ => 2,
+                }
+            }
+            #[match_strategy(jump_table)]
+            fn jump_table_match(a: felt252) -> felt252 {
+                match a {
+                    0 => 1,
+                    1 => 2,
+                    _ => 3,
+                }
+            }
+            fn tuple_match(a: Option<felt252>, b: Option<felt252>) -> felt252 {
+                match (a, b) {
+                    (Option::Some(x), Option::Some(_)) => x,
+                    (_, _) => 0,
+                }
+            }
+            #[derive(Drop)]
+            struct Wrapper {
+                value: Option<felt252>,
+            }
+            #[generate_trait]
+            impl WrapperImpl of WrapperTrait {
+                fn unwrap_or_zero(self: Wrapper) -> felt252 {
+                    match self.value {
+                        Option::Some(x) => x,
+                        Option::None => 0,
+                    }
+                }
+            }
+        "},
+    );
+
+    let histogram = crate_match_strategy_histogram(db, crate_id).unwrap();
+    assert_eq!(histogram.count(MatchStrategy::Enum), 2);
+    assert_eq!(histogram.count(MatchStrategy::Extern), 1);
+    assert_eq!(histogram.count(MatchStrategy::Felt252IfChain), 1);
+    assert_eq!(histogram.count(MatchStrategy::Felt252JumpTable), 1);
+    assert_eq!(histogram.count(MatchStrategy::TupleTree), 1);
+    assert_eq!(histogram.total(), 6);
+}
+
+#[test]
+fn test_exhaustive_u8_match_unreachable_arm_traps() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let other_values = (1..=u8::MAX).join(" | ");
+    let test_function = setup_test_function(
+        db,
+        &format!(
+            indoc::indoc! {"
+                fn foo(a: u8) -> felt252 {{
+                    match a {{
+                        0 => 1,
+                        {} => 0,
+                    }}
+                }}
+            "},
+            other_values
+        ),
+        "foo",
+        "",
+    )
+    .unwrap();
+
+    let lowering = lower_semantic_function(db, test_function.function_id).unwrap();
+    let lowered = &lowering.main_lowering;
+    assert!(lowered.diagnostics.get_all().is_empty(), "{:?}", lowered.diagnostics.get_all());
+    let FlatBlockEnd::Match { info: MatchInfo::Extern(downcast_info) } =
+        &lowered.blocks.root_block().unwrap().end
+    else {
+        panic!("Expected a downcast of the matched value.");
+    };
+    // The values of a `u8` are always in the range of the jump table, so the failure of the
+    // downcast is unreachable, and traps instead of being handled as an uncovered value.
+    let none_block = &lowered.blocks[downcast_info.arms[1].block_id];
+    let trap_code = BigInt::from_bytes_be(Sign::Plus, b"Unreachable match arm");
+    assert!(
+        none_block.statements.iter().any(|statement| matches!(
+            statement,
+            Statement::Const(StatementConst { value: ConstValue::Int(value, _), .. })
+                if *value == trap_code
+        )),
+        "{}",
+        formatted_lowered(db, lowered)
+    );
+}
+
+#[test]
+fn test_match_arm_tail_calls() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            fn foo(x: MyEnum) -> felt252 {
+                match x {
+                    MyEnum::A => first(),
+                    MyEnum::B => second(),
+                    MyEnum::C => first() + 1,
+                }
+            }
+        "},
+        "foo",
+        indoc::indoc! {"
+            enum MyEnum {
+                A,
+                B,
+                C,
+            }
+            #[inline(never)]
+            fn first() -> felt252 {
+                1
+            }
+            #[inline(never)]
+            fn second() -> felt252 {
+                2
+            }
+        "},
+    )
+    .unwrap();
+    let function_id =
+        ConcreteFunctionWithBodyId::from_semantic(db, test_function.concrete_function_id);
+
+    let lowered = db.final_concrete_function_with_body_lowered(function_id).unwrap();
+    // The calls of the first two arms are tail calls, while the result of the call of the last arm
+    // is added to, so only the addition is a tail call.
+    let tail_calls = lowered
+        .blocks
+        .iter()
+        .filter_map(|(_, block)| block.tail_call())
+        .map(|call| call.function.semantic_full_path(db))
+        .sorted()
+        .collect_vec();
+    assert_eq!(
+        tail_calls,
+        ["core::felt252_add", "test::first", "test::second"],
+        "{}",
+        formatted_lowered(db, &lowered)
+    );
+}
+
+#[test]
+fn test_is_optimized_extern_match() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            #[feature(\"corelib-internal-use\")]
+            fn foo(a: u8, b: u8) -> u8 {
+                match core::integer::u8_overflowing_add(a, b) {
+                    Result::Ok(x) => x,
+                    Result::Err(x) => x,
+                }
+            }
+        "},
+        "foo",
+        "",
+    )
+    .unwrap();
+    let function_body = db.function_body(test_function.function_id).unwrap();
+    let body = extract_matches!(
+        &function_body.arenas.exprs[function_body.body_expr],
+        semantic::Expr::Block
+    );
+    let match_expr =
+        extract_matches!(&function_body.arenas.exprs[body.tail.unwrap()], semantic::Expr::Match);
+    assert_eq!(
+        is_optimized_extern_match(db, test_function.function_id, match_expr.stable_ptr),
+        Ok(Some(true))
+    );
+}
+
+#[test]
+fn test_is_optimized_extern_match_on_materialized_snapshot() {
+    let db = &mut LoweringDatabaseForTesting::default();
+    let test_function = setup_test_function(
+        db,
+        indoc::indoc! {"
+            #[feature(\"corelib-internal-use\")]
+            fn foo(a: u8, b: u8) -> u8 {
+                let result = core::integer::u8_overflowing_add(a, b);
+                match @result {
+                    Result::Ok(x) => *x,
+                    Result::Err(x) => *x,
+                }
+            }
+        "},
+        "foo",
+        "",
+    )
+    .unwrap();
+    let function_body = db.function_body(test_function.function_id).unwrap();
+    let body = extract_matches!(
+        &function_body.arenas.exprs[function_body.body_expr],
+        semantic::Expr::Block
+    );
+    let match_expr =
+        extract_matches!(&function_body.arenas.exprs[body.tail.unwrap()], semantic::Expr::Match);
+    // The snapshot is of the variable the result of the extern function was stored in, so the match
+    // is on the materialized enum.
+    assert_eq!(
+        is_optimized_extern_match(db, test_function.function_id, match_expr.stable_ptr),
+        Ok(Some(false))
+    );
+}
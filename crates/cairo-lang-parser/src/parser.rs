@@ -1940,7 +1940,7 @@ impl<'a> Parser<'a> {
         let pattern_list = self
             .parse_separated_list_inner::<Pattern, TerminalOr, PatternListOrElementOrSeparatorGreen>(
                 Self::try_parse_pattern,
-                is_of_kind!(match_arrow, rparen, block, rbrace, module_item_kw),
+                is_of_kind!(match_arrow, if_kw, rparen, block, rbrace, module_item_kw),
                 "pattern",
                 Some(ParserDiagnosticKind::DisallowedTrailingSeparatorOr),
             );
@@ -1950,9 +1950,16 @@ impl<'a> Parser<'a> {
 
         let pattern_list_green = PatternListOr::new_green(self.db, pattern_list);
 
+        let guard = if self.peek().kind == SyntaxKind::TerminalIf {
+            let if_kw = self.take::<TerminalIf>();
+            let condition = self.parse_expr();
+            MatchArmGuard::new_green(self.db, if_kw, condition).into()
+        } else {
+            OptionMatchArmGuardEmpty::new_green(self.db).into()
+        };
         let arrow = self.parse_token::<TerminalMatchArrow>();
         let expr = self.parse_expr();
-        Ok(MatchArm::new_green(self.db, pattern_list_green, arrow, expr))
+        Ok(MatchArm::new_green(self.db, pattern_list_green, guard, arrow, expr))
     }
 
     /// Returns a GreenId of a node with some Pattern kind (see
@@ -1962,12 +1969,28 @@ impl<'a> Parser<'a> {
         if !modifier_list.is_empty() {
             let modifiers = ModifierList::new_green(self.db, modifier_list);
             let name = self.parse_identifier();
-            return Ok(PatternIdentifier::new_green(self.db, modifiers, name).into());
+            let binding = PatternIdentifier::new_green(self.db, modifiers, name);
+            if self.peek().kind == SyntaxKind::TerminalAt {
+                return Ok(self.expect_pattern_binding(binding));
+            }
+            return Ok(binding.into());
         };
 
         // TODO(yuval): Support "Or" patterns.
         Ok(match self.peek().kind {
-            SyntaxKind::TerminalLiteralNumber => self.take_terminal_literal_number().into(),
+            SyntaxKind::TerminalLiteralNumber => {
+                let start = self.take_terminal_literal_number();
+                let op: PatternRangeOperatorGreen = match self.peek().kind {
+                    SyntaxKind::TerminalDotDot => self.take::<TerminalDotDot>().into(),
+                    SyntaxKind::TerminalDotDotEq => self.take::<TerminalDotDotEq>().into(),
+                    _ => return Ok(start.into()),
+                };
+                let end = match self.peek().kind {
+                    SyntaxKind::TerminalLiteralNumber => self.take_terminal_literal_number(),
+                    _ => self.parse_token::<TerminalLiteralNumber>(),
+                };
+                PatternRange::new_green(self.db, start, op, end).into()
+            }
             SyntaxKind::TerminalShortString => self.take_terminal_short_string().into(),
             SyntaxKind::TerminalTrue => self.take::<TerminalTrue>().into(),
             SyntaxKind::TerminalFalse => self.take::<TerminalFalse>().into(),
@@ -1997,7 +2020,18 @@ impl<'a> Parser<'a> {
                     SyntaxKind::TerminalLParen => {
                         // Enum pattern.
                         let lparen = self.take::<TerminalLParen>();
-                        let pattern = self.parse_pattern();
+                        let mut pattern = self.parse_pattern();
+                        // An inner pattern may be an or-list of patterns, e.g. `A::B(1 | 2)`.
+                        if self.peek().kind == SyntaxKind::TerminalOr {
+                            let mut patterns: Vec<PatternListOrElementOrSeparatorGreen> =
+                                vec![pattern.into()];
+                            while self.peek().kind == SyntaxKind::TerminalOr {
+                                patterns.push(self.take::<TerminalOr>().into());
+                                patterns.push(self.parse_pattern().into());
+                            }
+                            let patterns = PatternListOr::new_green(self.db, patterns);
+                            pattern = PatternOr::new_green(self.db, patterns).into();
+                        }
                         let rparen = self.parse_token::<TerminalRParen>();
                         let inner_pattern =
                             PatternEnumInnerPattern::new_green(self.db, lparen, pattern, rparen);
@@ -2015,6 +2049,24 @@ impl<'a> Parser<'a> {
                         // sufficient.
                         match children.len() {
                             // 0 => return None, - unreachable
+                            1 if self.peek().kind == SyntaxKind::TerminalAt => {
+                                // A binding of the whole value along with a sub-pattern, e.g.
+                                // `x @ MyEnum::A(_)`.
+                                let segment = children[0].lookup_intern(self.db);
+                                let GreenNodeDetails::Node { children, width: _ } =
+                                    &segment.details
+                                else {
+                                    return Err(TryParseFailure::SkipToken);
+                                };
+                                if segment.kind != SyntaxKind::PathSegmentSimple {
+                                    return Err(TryParseFailure::SkipToken);
+                                }
+                                let name = TerminalIdentifierGreen(children[0]);
+                                let modifiers = ModifierList::new_green(self.db, vec![]);
+                                let binding =
+                                    PatternIdentifier::new_green(self.db, modifiers, name);
+                                self.expect_pattern_binding(binding)
+                            }
                             1 => path.into(),
                             _ => PatternEnum::new_green(
                                 self.db,
@@ -2054,9 +2106,32 @@ impl<'a> Parser<'a> {
                 let rbrack = self.parse_token::<TerminalRBrack>();
                 PatternFixedSizeArray::new_green(self.db, lbrack, patterns, rbrack).into()
             }
+            SyntaxKind::TerminalDotDot => {
+                // The rest of a span pattern, optionally bound to a name, e.g. `.. tail`.
+                let dotdot = self.take::<TerminalDotDot>();
+                let modifier_list = self.parse_modifier_list();
+                let binding: OptionPatternIdentifierGreen = if !modifier_list.is_empty()
+                    || self.peek().kind == SyntaxKind::TerminalIdentifier
+                {
+                    let modifiers = ModifierList::new_green(self.db, modifier_list);
+                    let name = self.parse_identifier();
+                    PatternIdentifier::new_green(self.db, modifiers, name).into()
+                } else {
+                    OptionPatternIdentifierEmpty::new_green(self.db).into()
+                };
+                PatternRest::new_green(self.db, dotdot, binding).into()
+            }
             _ => return Err(TryParseFailure::SkipToken),
         })
     }
+    /// Assumes the current token is `@`. Returns a GreenId of a binding of the value matched by a
+    /// pattern to `binding`, e.g. `x @ MyEnum::A(_)`.
+    fn expect_pattern_binding(&mut self, binding: PatternIdentifierGreen) -> PatternGreen {
+        let at = self.take::<TerminalAt>();
+        let pattern = self.parse_pattern();
+        PatternBinding::new_green(self.db, binding, at, pattern).into()
+    }
+
     /// Returns a GreenId of a node with some Pattern kind (see
     /// [syntax::node::ast::Pattern]).
     fn parse_pattern(&mut self) -> PatternGreen {
@@ -83,6 +83,13 @@ macro_rules! eq {
 }
 pub(crate) use eq;
 
+macro_rules! if_kw {
+    () => {
+        SyntaxKind::TerminalIf
+    };
+}
+pub(crate) use if_kw;
+
 macro_rules! module_item_kw {
     () => {
         SyntaxKind::TerminalConst
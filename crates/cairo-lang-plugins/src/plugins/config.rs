@@ -170,6 +170,27 @@ fn handle_undropped_item<'a>(
             builder.add_node(body.rbrace(db).as_syntax_node());
             Some(builder)
         }
+        ast::ModuleItem::Enum(enum_item) => {
+            let variants = get_kept_items_nodes(
+                db,
+                cfg_set,
+                &enum_item.variants(db).elements(db),
+                diagnostics,
+            )?;
+            let mut builder = PatchBuilder::new(db, &enum_item);
+            builder.add_node(enum_item.attributes(db).as_syntax_node());
+            builder.add_node(enum_item.visibility(db).as_syntax_node());
+            builder.add_node(enum_item.enum_kw(db).as_syntax_node());
+            builder.add_node(enum_item.name(db).as_syntax_node());
+            builder.add_node(enum_item.generic_params(db).as_syntax_node());
+            builder.add_node(enum_item.lbrace(db).as_syntax_node());
+            for variant in variants {
+                builder.add_node(variant);
+                builder.add_str(",\n");
+            }
+            builder.add_node(enum_item.rbrace(db).as_syntax_node());
+            Some(builder)
+        }
         _ => None,
     }
 }
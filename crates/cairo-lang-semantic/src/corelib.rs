@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
 use cairo_lang_defs::ids::{
-    EnumId, GenericTypeId, ImplDefId, ModuleId, ModuleItemId, NamedLanguageElementId,
-    TraitFunctionId, TraitId,
+    EnumId, GenericTypeId, ImplDefId, LanguageElementId, ModuleId, ModuleItemId,
+    NamedLanguageElementId, TraitFunctionId, TraitId,
 };
 use cairo_lang_diagnostics::{Maybe, ToOption};
 use cairo_lang_filesystem::ids::CrateId;
@@ -30,7 +30,7 @@ use crate::items::trt::{
 };
 use crate::items::us::SemanticUseEx;
 use crate::resolve::ResolvedGenericItem;
-use crate::types::{ConcreteEnumLongId, ConcreteExternTypeLongId};
+use crate::types::{ConcreteEnumLongId, ConcreteExternTypeLongId, ConcreteStructLongId};
 use crate::{
     ConcreteEnumId, ConcreteFunction, ConcreteImplLongId, ConcreteTypeId, ConcreteVariant, Expr,
     ExprId, ExprTuple, FunctionId, FunctionLongId, GenericArgumentId, TypeId, TypeLongId, semantic,
@@ -596,6 +596,14 @@ pub fn core_withdraw_gas_fns(db: &dyn SemanticGroup) -> [FunctionId; 2] {
 pub fn internal_require_implicit(db: &dyn SemanticGroup) -> GenericFunctionId {
     get_generic_function_id(db, core_submodule(db, "internal"), "require_implicit".into())
 }
+/// The function `match_arm_index` from the `internal` submodule.
+pub fn internal_match_arm_index(db: &dyn SemanticGroup) -> FunctionId {
+    get_function_id(db, core_submodule(db, "internal"), "match_arm_index".into(), vec![])
+}
+/// The function `catch_all_reached` from the `internal` submodule.
+pub fn internal_catch_all_reached(db: &dyn SemanticGroup) -> FunctionId {
+    get_function_id(db, core_submodule(db, "internal"), "catch_all_reached".into(), vec![])
+}
 /// The function `downcast` from the `integer` submodule.
 pub fn core_downcast(db: &dyn SemanticGroup, input: TypeId, output: TypeId) -> FunctionId {
     let internal = core_submodule(db, "integer");
@@ -607,6 +615,15 @@ pub fn core_downcast(db: &dyn SemanticGroup, input: TypeId, output: TypeId) -> F
         vec![GenericArgumentId::Type(input), GenericArgumentId::Type(output)],
     )
 }
+/// The impl `DowncastableIntTryInto` from the `integer` submodule, implementing `TryInto` between
+/// integer types using `downcast`.
+pub fn core_downcastable_int_try_into_impl(db: &dyn SemanticGroup) -> ImplDefId {
+    let module_item_id = db
+        .module_item_by_name(core_submodule(db, "integer"), "DowncastableIntTryInto".into())
+        .expect("Failed to load core lib.")
+        .expect("Impl 'DowncastableIntTryInto' was not found in core lib.");
+    extract_matches!(module_item_id, ModuleItemId::Impl)
+}
 /// Given a core library function name and its generic arguments, returns [FunctionId].
 pub fn get_core_function_id(
     db: &dyn SemanticGroup,
@@ -830,8 +847,69 @@ pub fn try_extract_nz_wrapped_type(db: &dyn SemanticGroup, ty: TypeId) -> Option
     (extern_type_id.name(db.upcast()) == "NonZero").then_some(inner)
 }
 
+/// Returns the type of the inner value of a `Nullable` type, if it is wrapped in one.
+pub fn try_extract_nullable_wrapped_type(db: &dyn SemanticGroup, ty: TypeId) -> Option<TypeId> {
+    let concrete_ty = try_extract_matches!(ty.lookup_intern(db), TypeLongId::Concrete)?;
+    let extern_ty = try_extract_matches!(concrete_ty, ConcreteTypeId::Extern)?;
+    let ConcreteExternTypeLongId { extern_type_id, generic_args } = extern_ty.lookup_intern(db);
+    let [GenericArgumentId::Type(inner)] = generic_args[..] else { return None };
+    (extern_type_id.name(db.upcast()) == "Nullable").then_some(inner)
+}
+
+/// Returns the type of the elements of a `Span` type, if it is one.
+pub fn try_extract_span_element_type(db: &dyn SemanticGroup, ty: TypeId) -> Option<TypeId> {
+    let concrete_ty = try_extract_matches!(ty.lookup_intern(db), TypeLongId::Concrete)?;
+    let struct_ty = try_extract_matches!(concrete_ty, ConcreteTypeId::Struct)?;
+    let ConcreteStructLongId { struct_id, generic_args } = struct_ty.lookup_intern(db);
+    let [GenericArgumentId::Type(inner)] = generic_args[..] else { return None };
+    (struct_id.name(db.upcast()) == "Span"
+        && struct_id.parent_module(db.upcast()) == core_submodule(db, "array"))
+    .then_some(inner)
+}
+
+/// The `Array` type of elements of type `inner_ty`.
+pub fn core_array_ty(db: &dyn SemanticGroup, inner_ty: TypeId) -> TypeId {
+    get_ty_by_name(
+        db,
+        core_submodule(db, "array"),
+        "Array".into(),
+        vec![GenericArgumentId::Type(inner_ty)],
+    )
+}
+
+/// The function `array_snapshot_pop_front` from the `array` submodule, popping the first element
+/// of a snapshot of an `Array` of `inner_ty`.
+pub fn core_array_snapshot_pop_front(db: &dyn SemanticGroup, inner_ty: TypeId) -> FunctionId {
+    get_function_id(
+        db,
+        core_submodule(db, "array"),
+        "array_snapshot_pop_front".into(),
+        vec![GenericArgumentId::Type(inner_ty)],
+    )
+}
+
+/// The function `unbox` from the `box` submodule, for a `Box` of `inner_ty`.
+pub fn core_unbox(db: &dyn SemanticGroup, inner_ty: TypeId) -> FunctionId {
+    get_function_id(
+        db,
+        core_submodule(db, "box"),
+        "unbox".into(),
+        vec![GenericArgumentId::Type(inner_ty)],
+    )
+}
+
+/// The function `match_nullable` from the `nullable` submodule, for a `Nullable` of `inner_ty`.
+pub fn core_match_nullable(db: &dyn SemanticGroup, inner_ty: TypeId) -> FunctionId {
+    get_function_id(
+        db,
+        core_submodule(db, "nullable"),
+        "match_nullable".into(),
+        vec![GenericArgumentId::Type(inner_ty)],
+    )
+}
+
 /// Returns the ranges of a BoundedInt if it is a BoundedInt type.
-fn try_extract_bounded_int_type_ranges(
+pub fn try_extract_bounded_int_type_ranges(
     db: &dyn SemanticGroup,
     ty: TypeId,
 ) -> Option<(BigInt, BigInt)> {
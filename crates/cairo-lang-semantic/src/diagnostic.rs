@@ -393,6 +393,12 @@ impl DiagnosticEntry for SemanticDiagnostic {
             SemanticDiagnosticKind::MissingVariableInPattern => {
                 "Missing variable in pattern.".into()
             }
+            SemanticDiagnosticKind::EmptyRangePattern => {
+                "Range pattern does not match any value.".into()
+            }
+            SemanticDiagnosticKind::DuplicateBindingInMatchArm(name) => {
+                format!(r#"Variable "{name}" is bound more than once in the same pattern."#)
+            }
             SemanticDiagnosticKind::StructMemberRedefinition { struct_id, member_name } => {
                 format!(
                     r#"Redefinition of member "{member_name}" on struct "{}"."#,
@@ -483,6 +489,9 @@ impl DiagnosticEntry for SemanticDiagnostic {
             SemanticDiagnosticKind::UnhandledMustUseFunction => {
                 "Unhandled `#[must_use]` function.".into()
             }
+            SemanticDiagnosticKind::UnusedMatchResult => {
+                "Unused result of a `#[must_use]` match.".into()
+            }
             SemanticDiagnosticKind::UnstableFeature { feature_name, note } => {
                 format!(
                     "Usage of unstable feature `{feature_name}` with no \
@@ -630,6 +639,13 @@ impl DiagnosticEntry for SemanticDiagnostic {
             SemanticDiagnosticKind::UnexpectedEnumPattern(ty) => {
                 format!(r#"Unexpected type for enum pattern. "{}" is not an enum."#, ty.format(db),)
             }
+            SemanticDiagnosticKind::AmbiguousMatchDeref { ty, enum_targets } => {
+                format!(
+                    "Ambiguous match. More than one type `{}` derefs to is an enum: {}.",
+                    ty.format(db),
+                    enum_targets.iter().map(|target| format!("`{}`", target.format(db))).join(", ")
+                )
+            }
             SemanticDiagnosticKind::UnexpectedStructPattern(ty) => {
                 format!(
                     r#"Unexpected type for struct pattern. "{}" is not a struct."#,
@@ -656,6 +672,9 @@ impl DiagnosticEntry for SemanticDiagnostic {
                     expected, actual
                 )
             }
+            SemanticDiagnosticKind::UnexpectedRestPattern => {
+                "A rest pattern is only supported as the last element of a span pattern.".into()
+            }
             SemanticDiagnosticKind::WrongEnum { expected_enum, actual_enum } => {
                 format!(
                     r#"Wrong enum in pattern. Expected: "{}". Got: "{}"."#,
@@ -1045,6 +1064,7 @@ impl DiagnosticEntry for SemanticDiagnostic {
             SemanticDiagnosticKind::UnusedVariable
             | SemanticDiagnosticKind::UnhandledMustUseType { .. }
             | SemanticDiagnosticKind::UnhandledMustUseFunction
+            | SemanticDiagnosticKind::UnusedMatchResult
             | SemanticDiagnosticKind::TraitInTraitMustBeExplicit
             | SemanticDiagnosticKind::ImplInImplMustBeExplicit
             | SemanticDiagnosticKind::TraitItemForbiddenInTheTrait
@@ -1213,6 +1233,8 @@ pub enum SemanticDiagnosticKind {
     },
     VariableNotFound(SmolStr),
     MissingVariableInPattern,
+    EmptyRangePattern,
+    DuplicateBindingInMatchArm(SmolStr),
     StructMemberRedefinition {
         struct_id: StructId,
         member_name: SmolStr,
@@ -1279,6 +1301,7 @@ pub enum SemanticDiagnosticKind {
     },
     FeatureMarkerDiagnostic(FeatureMarkerDiagnostic),
     UnhandledMustUseFunction,
+    UnusedMatchResult,
     UnusedVariable,
     UnusedConstant,
     UnusedUse,
@@ -1315,6 +1338,10 @@ pub enum SemanticDiagnosticKind {
     },
     ReferenceLocalVariable,
     UnexpectedEnumPattern(semantic::TypeId),
+    AmbiguousMatchDeref {
+        ty: semantic::TypeId,
+        enum_targets: Vec<semantic::TypeId>,
+    },
     UnexpectedStructPattern(semantic::TypeId),
     UnexpectedTuplePattern(semantic::TypeId),
     UnexpectedFixedSizeArrayPattern(semantic::TypeId),
@@ -1326,6 +1353,7 @@ pub enum SemanticDiagnosticKind {
         expected: usize,
         actual: usize,
     },
+    UnexpectedRestPattern,
     WrongEnum {
         expected_enum: EnumId,
         actual_enum: EnumId,
@@ -20,12 +20,13 @@ use cairo_lang_diagnostics::{Maybe, ToOption, skip_diagnostic};
 use cairo_lang_filesystem::cfg::CfgSet;
 use cairo_lang_filesystem::ids::{FileKind, FileLongId, VirtualFile};
 use cairo_lang_proc_macros::DebugWithDb;
+use cairo_lang_syntax::attribute::consts::MUST_USE_ATTR;
 use cairo_lang_syntax::node::ast::{
     BinaryOperator, BlockOrIf, ClosureParamWrapper, ExprPtr, OptionReturnTypeClause, PatternListOr,
     PatternStructParam, UnaryOperator,
 };
 use cairo_lang_syntax::node::db::SyntaxGroup;
-use cairo_lang_syntax::node::helpers::{GetIdentifier, PathSegmentEx};
+use cairo_lang_syntax::node::helpers::{GetIdentifier, PathSegmentEx, QueryAttrs};
 use cairo_lang_syntax::node::ids::SyntaxStablePtrId;
 use cairo_lang_syntax::node::kind::SyntaxKind;
 use cairo_lang_syntax::node::{Terminal, TypedStablePtr, TypedSyntaxNode, ast};
@@ -46,13 +47,15 @@ use super::inference::infers::InferenceEmbeddings;
 use super::inference::{Inference, InferenceData, InferenceError};
 use super::objects::*;
 use super::pattern::{
-    Pattern, PatternEnumVariant, PatternFixedSizeArray, PatternLiteral, PatternMissing,
-    PatternOtherwise, PatternTuple, PatternVariable,
+    Pattern, PatternBinding, PatternEnumVariant, PatternFixedSizeArray, PatternLiteral,
+    PatternMissing, PatternOr, PatternOtherwise, PatternRange, PatternRest, PatternTuple,
+    PatternVariable,
 };
 use crate::corelib::{
-    core_binary_operator, core_bool_ty, core_unary_operator, false_literal_expr, get_usize_ty,
-    never_ty, true_literal_expr, try_get_core_ty_by_name, unit_expr, unit_ty,
-    unwrap_error_propagation_type, validate_literal,
+    core_binary_operator, core_bool_ty, core_match_nullable, core_unary_operator,
+    false_literal_expr, get_usize_ty, never_ty, true_literal_expr,
+    try_extract_nullable_wrapped_type, try_extract_span_element_type, try_get_core_ty_by_name,
+    unit_expr, unit_ty, unwrap_error_propagation_type, validate_literal,
 };
 use crate::db::SemanticGroup;
 use crate::diagnostic::SemanticDiagnosticKind::{self, *};
@@ -1264,9 +1267,10 @@ fn compute_arm_semantic(
     expr: &Expr,
     arm_expr_syntax: ast::Expr,
     patterns_syntax: &PatternListOr,
+    guard_syntax: Option<ast::Expr>,
     // Whether the arm is a while let arm. This case is handled a little differently.
     is_while_let_arm: bool,
-) -> (Vec<PatternAndId>, ExprAndId) {
+) -> (Vec<PatternAndId>, Option<ExprAndId>, ExprAndId) {
     let db = ctx.db;
     let syntax_db = db.upcast();
     ctx.run_in_subscope(|new_ctx| {
@@ -1332,7 +1336,13 @@ fn compute_arm_semantic(
         {
             let variables = pattern.variables(&new_ctx.arenas.patterns);
 
-            if variables.len() != arm_patterns_variables.len() {
+            let mut names = UnorderedHashSet::<_>::default();
+            if let Some(duplicate) = variables.iter().find(|v| !names.insert(v.name.clone())) {
+                new_ctx.diagnostics.report(
+                    duplicate.stable_ptr,
+                    DuplicateBindingInMatchArm(duplicate.name.clone()),
+                );
+            } else if variables.len() != arm_patterns_variables.len() {
                 new_ctx.diagnostics.report(pattern_syntax, MissingVariableInPattern);
             }
 
@@ -1344,6 +1354,9 @@ fn compute_arm_semantic(
                 new_ctx.semantic_defs.insert(var_def.id(), var_def);
             }
         }
+        // The guard is computed after the patterns, as it may use their bindings.
+        let guard = guard_syntax
+            .map(|guard_syntax| compute_bool_condition_semantic(new_ctx, &guard_syntax));
         let arm_expr = if is_while_let_arm {
             let ast::Expr::Block(arm_expr_syntax) = arm_expr_syntax else {
                 unreachable!("Expected a block expression for a loop arm.");
@@ -1356,7 +1369,7 @@ fn compute_arm_semantic(
         } else {
             compute_expr_semantic(new_ctx, &arm_expr_syntax)
         };
-        (patterns, arm_expr)
+        (patterns, guard, arm_expr)
     })
 }
 
@@ -1370,7 +1383,9 @@ fn compute_expr_match_semantic(
     let syntax_db = db.upcast();
 
     let syntax_arms = syntax.arms(syntax_db).elements(syntax_db);
-    let expr = compute_expr_semantic(ctx, &syntax.expr(syntax_db));
+    let syntax_expr = syntax.expr(syntax_db);
+    let expr = compute_expr_semantic(ctx, &syntax_expr);
+    let expr = auto_deref_matched_expr(ctx, expr, &syntax_expr)?;
     // Run compute_pattern_semantic on every arm, even if other arms failed, to get as many
     // diagnostics as possible.
     let patterns_and_exprs: Vec<_> = syntax_arms
@@ -1381,13 +1396,19 @@ fn compute_expr_match_semantic(
                 &expr,
                 syntax_arm.expression(syntax_db),
                 &syntax_arm.patterns(syntax_db),
+                match syntax_arm.guard(syntax_db) {
+                    ast::OptionMatchArmGuard::Empty(_) => None,
+                    ast::OptionMatchArmGuard::MatchArmGuard(guard) => {
+                        Some(guard.condition(syntax_db))
+                    }
+                },
                 false,
             )
         })
         .collect();
     // Unify arm types.
     let mut helper = FlowMergeTypeHelper::new(ctx.db, MultiArmExprKind::Match);
-    for (_, expr) in patterns_and_exprs.iter() {
+    for (_, _, expr) in patterns_and_exprs.iter() {
         let expr_ty = ctx.reduce_ty(expr.ty());
         if !helper.try_merge_types(
             ctx.db,
@@ -1402,8 +1423,9 @@ fn compute_expr_match_semantic(
     // Compute semantic representation of the match arms.
     let semantic_arms = patterns_and_exprs
         .into_iter()
-        .map(|(patterns, arm_expr)| MatchArm {
+        .map(|(patterns, guard, arm_expr)| MatchArm {
             patterns: patterns.iter().map(|pattern| pattern.id).collect(),
+            guard: guard.map(|guard| guard.id),
             expression: arm_expr.id,
         })
         .collect();
@@ -1415,6 +1437,78 @@ fn compute_expr_match_semantic(
     }))
 }
 
+/// Derefs the matched expression of a match into an enum, if the expression is not an enum or a
+/// tuple itself, and exactly one of the types in its deref chain is an enum.
+///
+/// A `Nullable` is instead matched through `match_nullable`, so that its arms dispatch on whether
+/// it is null rather than panicking on null.
+fn auto_deref_matched_expr(
+    ctx: &mut ComputationContext<'_>,
+    expr: ExprAndId,
+    syntax_expr: &ast::Expr,
+) -> Maybe<ExprAndId> {
+    let ty = ctx.reduce_ty(expr.ty());
+    if matches!(
+        peel_snapshots(ctx.db, ty).1,
+        TypeLongId::Concrete(ConcreteTypeId::Enum(_))
+            | TypeLongId::Tuple(_)
+            | TypeLongId::Missing(_)
+    ) {
+        return Ok(expr);
+    }
+    if let Some(inner_ty) = try_extract_nullable_wrapped_type(ctx.db, ty) {
+        let stable_ptr = syntax_expr.stable_ptr();
+        let expr = expr_function_call(
+            ctx,
+            core_match_nullable(ctx.db, inner_ty),
+            vec![NamedArg(expr, None, Mutability::Immutable)],
+            stable_ptr,
+            stable_ptr,
+        )?;
+        return Ok(ExprAndId { expr: expr.clone(), id: ctx.arenas.exprs.alloc(expr) });
+    }
+    let Ok(deref_chain) = ctx.db.deref_chain(ty, false) else {
+        return Ok(expr);
+    };
+    let enum_positions = deref_chain
+        .derefs
+        .iter()
+        .positions(|deref_info| {
+            matches!(
+                peel_snapshots(ctx.db, deref_info.target_ty).1,
+                TypeLongId::Concrete(ConcreteTypeId::Enum(_))
+            )
+        })
+        .collect_vec();
+    let n_derefs = match enum_positions[..] {
+        [] => return Ok(expr),
+        [position] => position + 1,
+        _ => {
+            return Err(ctx.diagnostics.report(
+                syntax_expr,
+                AmbiguousMatchDeref {
+                    ty,
+                    enum_targets: enum_positions
+                        .into_iter()
+                        .map(|position| deref_chain.derefs[position].target_ty)
+                        .collect(),
+                },
+            ));
+        }
+    };
+    let stable_ptr = syntax_expr.stable_ptr();
+    deref_chain.derefs.iter().take(n_derefs).try_fold(expr, |derefed_expr, deref_info| {
+        let expr = expr_function_call(
+            ctx,
+            deref_info.function_id,
+            vec![NamedArg(derefed_expr, None, deref_info.self_mutability)],
+            stable_ptr,
+            stable_ptr,
+        )?;
+        Ok(ExprAndId { expr: expr.clone(), id: ctx.arenas.exprs.alloc(expr) })
+    })
+}
+
 /// Computes the semantic model of an expression of type [ast::ExprIf].
 fn compute_expr_if_semantic(ctx: &mut ComputationContext<'_>, syntax: &ast::ExprIf) -> Maybe<Expr> {
     let syntax_db = ctx.db.upcast();
@@ -1426,11 +1520,12 @@ fn compute_expr_if_semantic(ctx: &mut ComputationContext<'_>, syntax: &ast::Expr
                     .report(&condition.expr(syntax_db), LogicalOperatorNotAllowedInIfLet);
             }
 
-            let (patterns, if_block) = compute_arm_semantic(
+            let (patterns, _, if_block) = compute_arm_semantic(
                 ctx,
                 &expr,
                 ast::Expr::Block(syntax.if_block(syntax_db)),
                 &condition.patterns(syntax_db),
+                None,
                 false,
             );
             (Condition::Let(expr.id, patterns.iter().map(|pattern| pattern.id).collect()), if_block)
@@ -1517,11 +1612,12 @@ fn compute_expr_while_semantic(
                     .report(&condition.expr(syntax_db), LogicalOperatorNotAllowedInWhileLet);
             }
 
-            let (patterns, body) = compute_arm_semantic(
+            let (patterns, _, body) = compute_arm_semantic(
                 ctx,
                 &expr,
                 ast::Expr::Block(syntax.body(syntax_db)),
                 &condition.patterns(syntax_db),
+                None,
                 true,
             );
             (Condition::Let(expr.id, patterns.iter().map(|pattern| pattern.id).collect()), body.id)
@@ -2175,6 +2271,25 @@ fn maybe_compute_pattern_semantic(
                 stable_ptr: literal_pattern.stable_ptr().into(),
             })
         }
+        ast::Pattern::Range(range_pattern) => {
+            let start = literal_to_semantic(ctx, &range_pattern.start(syntax_db))?;
+            let end = literal_to_semantic(ctx, &range_pattern.end(syntax_db))?;
+            let inference = &mut ctx.resolver.inference();
+            inference.conform_ty(end.ty, start.ty).map_err(|err_set| {
+                inference.report_on_pending_error(err_set, ctx.diagnostics, stable_ptr)
+            })?;
+            let is_inclusive =
+                matches!(range_pattern.op(syntax_db), ast::PatternRangeOperator::DotDotEq(_));
+            if end.value < start.value || (!is_inclusive && end.value == start.value) {
+                return Err(ctx.diagnostics.report(range_pattern, EmptyRangePattern));
+            }
+            Pattern::Range(PatternRange {
+                start,
+                end,
+                is_inclusive,
+                stable_ptr: range_pattern.stable_ptr(),
+            })
+        }
         ast::Pattern::ShortString(short_string_pattern) => {
             let literal = short_string_to_semantic(ctx, short_string_pattern)?;
             Pattern::Literal(PatternLiteral {
@@ -2293,109 +2408,116 @@ fn maybe_compute_pattern_semantic(
             or_pattern_variables_map,
         ),
         ast::Pattern::Struct(pattern_struct) => {
+            let path = pattern_struct.path(syntax_db);
+            let item_result = ctx.resolver.resolve_generic_path(
+                &mut Default::default(),
+                &path,
+                NotFoundItemType::Identifier,
+                Some(&mut ctx.environment),
+            );
+            if let Ok(ResolvedGenericItem::Variant(generic_variant)) = item_result {
+                // A variant whose payload is a struct, matched by the names of the payload members.
+                let (concrete_enum, n_snapshots) = extract_concrete_enum_from_pattern_and_validate(
+                    ctx,
+                    pattern_syntax,
+                    ty,
+                    generic_variant.enum_id,
+                )?;
+                let concrete_variant = ctx
+                    .db
+                    .concrete_enum_variant(concrete_enum, &generic_variant)
+                    .map_err(|_| ctx.diagnostics.report(&path, UnknownEnum))?;
+                let inner_ty = wrap_in_snapshots(ctx.db, concrete_variant.ty, n_snapshots);
+                let inner_pattern = compute_struct_pattern_semantic(
+                    ctx,
+                    pattern_struct,
+                    inner_ty,
+                    or_pattern_variables_map,
+                )?;
+                return Ok(Pattern::EnumVariant(PatternEnumVariant {
+                    variant: concrete_variant,
+                    inner_pattern: Some(ctx.arenas.patterns.alloc(inner_pattern)),
+                    ty,
+                    stable_ptr: pattern_struct.stable_ptr().into(),
+                }));
+            }
             let pattern_ty = try_extract_matches!(
                 ctx.resolver.resolve_concrete_path_ex(
                     ctx.diagnostics,
-                    &pattern_struct.path(syntax_db),
+                    &path,
                     NotFoundItemType::Type,
                     Some(&mut ctx.environment)
                 )?,
                 ResolvedConcreteItem::Type
             )
-            .ok_or_else(|| ctx.diagnostics.report(&pattern_struct.path(syntax_db), NotAType))?;
+            .ok_or_else(|| ctx.diagnostics.report(&path, NotAType))?;
             let inference = &mut ctx.resolver.inference();
             inference.conform_ty(pattern_ty, peel_snapshots(ctx.db, ty).1.intern(ctx.db)).map_err(
                 |err_set| inference.report_on_pending_error(err_set, ctx.diagnostics, stable_ptr),
             )?;
-            let ty = ctx.reduce_ty(ty);
-            // Peel all snapshot wrappers.
-            let (n_snapshots, long_ty) = peel_snapshots(ctx.db, ty);
-
-            // Check that type is an struct, and get the concrete struct from it.
-            let concrete_struct_id = try_extract_matches!(long_ty, TypeLongId::Concrete)
-                .and_then(|c| try_extract_matches!(c, ConcreteTypeId::Struct))
-                .ok_or(())
-                .or_else(|_| {
-                    // Don't add a diagnostic if the type is missing.
-                    // A diagnostic should've already been added.
-                    ty.check_not_missing(ctx.db)?;
-                    Err(ctx.diagnostics.report(pattern_struct, UnexpectedStructPattern(ty)))
-                })?;
-            let pattern_param_asts = pattern_struct.params(syntax_db).elements(syntax_db);
-            let struct_id = concrete_struct_id.struct_id(ctx.db);
-            let mut members = ctx.db.concrete_struct_members(concrete_struct_id)?.as_ref().clone();
-            let mut used_members = UnorderedHashSet::<_>::default();
-            let mut get_member = |ctx: &mut ComputationContext<'_>,
-                                  member_name: SmolStr,
-                                  stable_ptr: SyntaxStablePtrId| {
-                let member = members.swap_remove(&member_name).on_none(|| {
-                    ctx.diagnostics.report(
-                        stable_ptr,
-                        if used_members.contains(&member_name) {
-                            StructMemberRedefinition { struct_id, member_name: member_name.clone() }
-                        } else {
-                            NoSuchStructMember { struct_id, member_name: member_name.clone() }
-                        },
+            compute_struct_pattern_semantic(ctx, pattern_struct, ty, or_pattern_variables_map)?
+        }
+        ast::Pattern::Or(or_pattern) => {
+            let alternatives_syntax = or_pattern.patterns(syntax_db).elements(syntax_db);
+            let alternatives = alternatives_syntax
+                .iter()
+                .map(|alternative_syntax| {
+                    let alternative = compute_pattern_semantic(
+                        ctx,
+                        alternative_syntax,
+                        ty,
+                        or_pattern_variables_map,
                     );
-                })?;
-                check_struct_member_is_visible(ctx, &member, stable_ptr, &member_name);
-                used_members.insert(member_name);
-                Some(member)
-            };
-            let mut field_patterns = vec![];
-            let mut has_tail = false;
-            for pattern_param_ast in pattern_param_asts {
-                match pattern_param_ast {
-                    PatternStructParam::Single(single) => {
-                        let name = single.name(syntax_db);
-                        let Some(member) =
-                            get_member(ctx, name.text(syntax_db), name.stable_ptr().untyped())
-                        else {
-                            continue;
-                        };
-                        let ty = wrap_in_snapshots(ctx.db, member.ty, n_snapshots);
-                        let pattern = create_variable_pattern(
-                            ctx,
-                            name,
-                            &single.modifiers(syntax_db).elements(syntax_db),
-                            ty,
-                            single.stable_ptr().into(),
-                            or_pattern_variables_map,
-                        );
-                        field_patterns.push((member, ctx.arenas.patterns.alloc(pattern)));
-                    }
-                    PatternStructParam::WithExpr(with_expr) => {
-                        let name = with_expr.name(syntax_db);
-                        let Some(member) =
-                            get_member(ctx, name.text(syntax_db), name.stable_ptr().untyped())
-                        else {
-                            continue;
-                        };
-                        let ty = wrap_in_snapshots(ctx.db, member.ty, n_snapshots);
-                        let pattern = compute_pattern_semantic(
-                            ctx,
-                            &with_expr.pattern(syntax_db),
-                            ty,
-                            or_pattern_variables_map,
-                        );
-                        field_patterns.push((member, pattern.id));
-                    }
-                    PatternStructParam::Tail(_) => {
-                        has_tail = true;
+                    // The variables of the next alternatives with the same names are the same
+                    // variables.
+                    for variable in alternative.variables(&ctx.arenas.patterns) {
+                        or_pattern_variables_map.entry(variable.name).or_insert(variable.var);
                     }
+                    alternative
+                })
+                .collect_vec();
+            // The variables of the pattern are those of the alternative that matched, so all the
+            // alternatives must bind the same variables.
+            let mut alternatives_variables = alternatives.iter().map(|alternative| {
+                alternative.variables(&ctx.arenas.patterns).into_iter().map(|v| v.name).sorted()
+            });
+            let first_variables = alternatives_variables.next().into_iter().flatten().collect_vec();
+            for (alternative_syntax, variables) in
+                alternatives_syntax.iter().skip(1).zip(alternatives_variables)
+            {
+                if !variables.eq(first_variables.iter().cloned()) {
+                    ctx.diagnostics.report(alternative_syntax, MissingVariableInPattern);
                 }
             }
-            if !has_tail {
-                for (member_name, _) in members.iter() {
-                    ctx.diagnostics.report(pattern_struct, MissingMember(member_name.clone()));
-                }
-            }
-            Pattern::Struct(PatternStruct {
-                concrete_struct_id,
-                field_patterns,
+            Pattern::Or(PatternOr {
+                alternatives: alternatives.iter().map(|alternative| alternative.id).collect(),
                 ty,
-                n_snapshots,
-                stable_ptr: pattern_struct.stable_ptr(),
+                stable_ptr: or_pattern.stable_ptr(),
+            })
+        }
+        ast::Pattern::Binding(binding_pattern) => {
+            let inner_pattern = compute_pattern_semantic(
+                ctx,
+                &binding_pattern.pattern(syntax_db),
+                ty,
+                or_pattern_variables_map,
+            );
+            let binding = binding_pattern.binding(syntax_db);
+            let binding = extract_matches!(
+                create_variable_pattern(
+                    ctx,
+                    binding.name(syntax_db),
+                    &binding.modifiers(syntax_db).elements(syntax_db),
+                    ty,
+                    binding.stable_ptr().into(),
+                    or_pattern_variables_map,
+                ),
+                Pattern::Variable
+            );
+            Pattern::Binding(PatternBinding {
+                binding,
+                inner_pattern: inner_pattern.id,
+                stable_ptr: binding_pattern.stable_ptr(),
             })
         }
         ast::Pattern::Tuple(_) => maybe_compute_tuple_like_pattern_semantic(
@@ -2406,14 +2528,30 @@ fn maybe_compute_pattern_semantic(
             |ty: TypeId| UnexpectedTuplePattern(ty),
             |expected, actual| WrongNumberOfTupleElements { expected, actual },
         )?,
-        ast::Pattern::FixedSizeArray(_) => maybe_compute_tuple_like_pattern_semantic(
-            ctx,
-            pattern_syntax,
-            ty,
-            or_pattern_variables_map,
-            |ty: TypeId| UnexpectedFixedSizeArrayPattern(ty),
-            |expected, actual| WrongNumberOfFixedSizeArrayElements { expected, actual },
-        )?,
+        ast::Pattern::FixedSizeArray(pattern_fixed_size_array) => {
+            let (n_snapshots, long_ty) = finalized_snapshot_peeled_ty(ctx, ty, pattern_syntax)?;
+            match try_extract_span_element_type(ctx.db, long_ty.intern(ctx.db)) {
+                Some(element_ty) if n_snapshots == 0 => compute_span_pattern_semantic(
+                    ctx,
+                    pattern_fixed_size_array,
+                    ty,
+                    element_ty,
+                    or_pattern_variables_map,
+                ),
+                _ => maybe_compute_tuple_like_pattern_semantic(
+                    ctx,
+                    pattern_syntax,
+                    ty,
+                    or_pattern_variables_map,
+                    |ty: TypeId| UnexpectedFixedSizeArrayPattern(ty),
+                    |expected, actual| WrongNumberOfFixedSizeArrayElements { expected, actual },
+                )?,
+            }
+        }
+        // A rest pattern is only computed as the last element of a span pattern.
+        ast::Pattern::Rest(_) => {
+            return Err(ctx.diagnostics.report(pattern_syntax, UnexpectedRestPattern));
+        }
         ast::Pattern::False(pattern_false) => {
             let enum_expr = extract_matches!(
                 false_literal_expr(ctx, pattern_false.stable_ptr().into()),
@@ -2461,6 +2599,59 @@ fn maybe_compute_pattern_semantic(
     Ok(pattern)
 }
 
+/// Computes the semantic model of a pattern of a span of `element_ty`, e.g. `[head, .. tail]`.
+/// The elements are matched as snapshots, and the last element may be a rest pattern, matching the
+/// remaining elements of the span.
+fn compute_span_pattern_semantic(
+    ctx: &mut ComputationContext<'_>,
+    pattern_syntax: &ast::PatternFixedSizeArray,
+    ty: TypeId,
+    element_ty: TypeId,
+    or_pattern_variables_map: &mut UnorderedHashMap<SmolStr, LocalVariable>,
+) -> Pattern {
+    let syntax_db = ctx.db.upcast();
+    let patterns_syntax = pattern_syntax.patterns(syntax_db).elements(syntax_db);
+    let element_ty = TypeLongId::Snapshot(element_ty).intern(ctx.db);
+    let mut elements_patterns = vec![];
+    for (index, element_syntax) in patterns_syntax.iter().enumerate() {
+        let element_pattern = match element_syntax {
+            ast::Pattern::Rest(rest_syntax) if index + 1 == patterns_syntax.len() => {
+                let binding = match rest_syntax.binding(syntax_db) {
+                    ast::OptionPatternIdentifier::Empty(_) => None,
+                    ast::OptionPatternIdentifier::PatternIdentifier(binding) => {
+                        Some(extract_matches!(
+                            create_variable_pattern(
+                                ctx,
+                                binding.name(syntax_db),
+                                &binding.modifiers(syntax_db).elements(syntax_db),
+                                ty,
+                                binding.stable_ptr().into(),
+                                or_pattern_variables_map,
+                            ),
+                            Pattern::Variable
+                        ))
+                    }
+                };
+                ctx.arenas.patterns.alloc(Pattern::Rest(PatternRest {
+                    binding,
+                    ty,
+                    stable_ptr: rest_syntax.stable_ptr(),
+                }))
+            }
+            _ => {
+                compute_pattern_semantic(ctx, element_syntax, element_ty, or_pattern_variables_map)
+                    .id
+            }
+        };
+        elements_patterns.push(element_pattern);
+    }
+    Pattern::FixedSizeArray(PatternFixedSizeArray {
+        elements_patterns,
+        ty,
+        stable_ptr: pattern_syntax.stable_ptr(),
+    })
+}
+
 /// Computes the semantic model of a pattern of a tuple or a fixed size array. Assumes that the
 /// pattern is one of these types.
 fn maybe_compute_tuple_like_pattern_semantic(
@@ -2567,6 +2758,105 @@ fn maybe_compute_tuple_like_pattern_semantic(
     })
 }
 
+/// Computes the semantic model of a struct pattern, matching a value of type `ty`.
+fn compute_struct_pattern_semantic(
+    ctx: &mut ComputationContext<'_>,
+    pattern_struct: &ast::PatternStruct,
+    ty: TypeId,
+    or_pattern_variables_map: &mut UnorderedHashMap<SmolStr, LocalVariable>,
+) -> Maybe<Pattern> {
+    let syntax_db = ctx.db.upcast();
+    let ty = ctx.reduce_ty(ty);
+    // Peel all snapshot wrappers.
+    let (n_snapshots, long_ty) = peel_snapshots(ctx.db, ty);
+
+    // Check that type is an struct, and get the concrete struct from it.
+    let concrete_struct_id = try_extract_matches!(long_ty, TypeLongId::Concrete)
+        .and_then(|c| try_extract_matches!(c, ConcreteTypeId::Struct))
+        .ok_or(())
+        .or_else(|_| {
+            // Don't add a diagnostic if the type is missing.
+            // A diagnostic should've already been added.
+            ty.check_not_missing(ctx.db)?;
+            Err(ctx.diagnostics.report(pattern_struct, UnexpectedStructPattern(ty)))
+        })?;
+    let pattern_param_asts = pattern_struct.params(syntax_db).elements(syntax_db);
+    let struct_id = concrete_struct_id.struct_id(ctx.db);
+    let mut members = ctx.db.concrete_struct_members(concrete_struct_id)?.as_ref().clone();
+    let mut used_members = UnorderedHashSet::<_>::default();
+    let mut get_member =
+        |ctx: &mut ComputationContext<'_>, member_name: SmolStr, stable_ptr: SyntaxStablePtrId| {
+            let member = members.swap_remove(&member_name).on_none(|| {
+                ctx.diagnostics.report(
+                    stable_ptr,
+                    if used_members.contains(&member_name) {
+                        StructMemberRedefinition { struct_id, member_name: member_name.clone() }
+                    } else {
+                        NoSuchStructMember { struct_id, member_name: member_name.clone() }
+                    },
+                );
+            })?;
+            check_struct_member_is_visible(ctx, &member, stable_ptr, &member_name);
+            used_members.insert(member_name);
+            Some(member)
+        };
+    let mut field_patterns = vec![];
+    let mut has_tail = false;
+    for pattern_param_ast in pattern_param_asts {
+        match pattern_param_ast {
+            PatternStructParam::Single(single) => {
+                let name = single.name(syntax_db);
+                let Some(member) =
+                    get_member(ctx, name.text(syntax_db), name.stable_ptr().untyped())
+                else {
+                    continue;
+                };
+                let ty = wrap_in_snapshots(ctx.db, member.ty, n_snapshots);
+                let pattern = create_variable_pattern(
+                    ctx,
+                    name,
+                    &single.modifiers(syntax_db).elements(syntax_db),
+                    ty,
+                    single.stable_ptr().into(),
+                    or_pattern_variables_map,
+                );
+                field_patterns.push((member, ctx.arenas.patterns.alloc(pattern)));
+            }
+            PatternStructParam::WithExpr(with_expr) => {
+                let name = with_expr.name(syntax_db);
+                let Some(member) =
+                    get_member(ctx, name.text(syntax_db), name.stable_ptr().untyped())
+                else {
+                    continue;
+                };
+                let ty = wrap_in_snapshots(ctx.db, member.ty, n_snapshots);
+                let pattern = compute_pattern_semantic(
+                    ctx,
+                    &with_expr.pattern(syntax_db),
+                    ty,
+                    or_pattern_variables_map,
+                );
+                field_patterns.push((member, pattern.id));
+            }
+            PatternStructParam::Tail(_) => {
+                has_tail = true;
+            }
+        }
+    }
+    if !has_tail {
+        for (member_name, _) in members.iter() {
+            ctx.diagnostics.report(pattern_struct, MissingMember(member_name.clone()));
+        }
+    }
+    Ok(Pattern::Struct(PatternStruct {
+        concrete_struct_id,
+        field_patterns,
+        ty,
+        n_snapshots,
+        stable_ptr: pattern_struct.stable_ptr(),
+    }))
+}
+
 /// Validates that the semantic type of an enum pattern is an enum, and returns the concrete enum.
 fn extract_concrete_enum_from_pattern_and_validate(
     ctx: &mut ComputationContext<'_>,
@@ -3572,8 +3862,8 @@ pub fn compute_statement_semantic(
 
     let crate_id = ctx.resolver.owning_crate_id;
 
-    // As for now, statement attributes does not have any semantic affect, so we only validate they
-    // are allowed.
+    // Apart from `#[must_use]` on match statements, statement attributes do not have any semantic
+    // affect, so we only validate they are allowed.
     validate_statement_attributes(ctx, &syntax);
     let feature_restore = ctx
         .resolver
@@ -3674,6 +3964,12 @@ pub fn compute_statement_semantic(
                     ctx.diagnostics.report(&expr_syntax, UnhandledMustUseFunction);
                 }
             }
+            if matches!(expr_syntax, ast::Expr::Match(_))
+                && stmt_expr_syntax.has_attr(syntax_db, MUST_USE_ATTR)
+                && !(ty.is_unit(db) || ty.is_missing(db) || ty == never_ty(db))
+            {
+                ctx.diagnostics.report(&expr_syntax, UnusedMatchResult);
+            }
             semantic::Statement::Expr(semantic::StatementExpr {
                 expr: expr.id,
                 stable_ptr: syntax.stable_ptr(),
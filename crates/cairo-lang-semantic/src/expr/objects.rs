@@ -440,6 +440,8 @@ pub enum Condition {
 #[debug_db(ExprFormatter<'a>)]
 pub struct MatchArm {
     pub patterns: Vec<PatternId>,
+    /// A condition on the bindings of the patterns, which must hold for the arm to be taken.
+    pub guard: Option<ExprId>,
     pub expression: ExprId,
 }
 
@@ -23,12 +23,16 @@ use crate::{ConcreteStructId, ExprLiteral, ExprStringLiteral, LocalVariable, Pat
 #[debug_db(ExprFormatter<'a>)]
 pub enum Pattern {
     Literal(PatternLiteral),
+    Range(PatternRange),
     StringLiteral(PatternStringLiteral),
     Variable(PatternVariable),
     Struct(PatternStruct),
     Tuple(PatternTuple),
     FixedSizeArray(PatternFixedSizeArray),
     EnumVariant(PatternEnumVariant),
+    Or(PatternOr),
+    Binding(PatternBinding),
+    Rest(PatternRest),
     Otherwise(PatternOtherwise),
     Missing(PatternMissing),
 }
@@ -36,12 +40,16 @@ impl Pattern {
     pub fn ty(&self) -> semantic::TypeId {
         match self {
             Pattern::Literal(literal) => literal.literal.ty,
+            Pattern::Range(range) => range.start.ty,
             Pattern::StringLiteral(string_literal) => string_literal.string_literal.ty,
             Pattern::Variable(variable) => variable.var.ty,
             Pattern::Struct(pattern_struct) => pattern_struct.ty,
             Pattern::Tuple(pattern_tuple) => pattern_tuple.ty,
             Pattern::FixedSizeArray(pattern_fixed_size_array) => pattern_fixed_size_array.ty,
             Pattern::EnumVariant(pattern_enum_variant) => pattern_enum_variant.ty,
+            Pattern::Or(pattern_or) => pattern_or.ty,
+            Pattern::Binding(pattern_binding) => pattern_binding.binding.var.ty,
+            Pattern::Rest(pattern_rest) => pattern_rest.ty,
             Pattern::Otherwise(pattern_otherwise) => pattern_otherwise.ty,
             Pattern::Missing(pattern_missing) => pattern_missing.ty,
         }
@@ -71,7 +79,19 @@ impl Pattern {
                     None => vec![],
                 }
             }
+            // All the alternatives bind the same variables.
+            Pattern::Or(pattern_or) => match pattern_or.alternatives.first() {
+                Some(pattern) => queryable.query(*pattern),
+                None => vec![],
+            },
+            Pattern::Binding(pattern_binding) => {
+                let mut variables = vec![pattern_binding.binding.clone()];
+                variables.extend(queryable.query(pattern_binding.inner_pattern));
+                variables
+            }
+            Pattern::Rest(pattern_rest) => pattern_rest.binding.iter().cloned().collect(),
             Pattern::Literal(_)
+            | Pattern::Range(_)
             | Pattern::StringLiteral(_)
             | Pattern::Otherwise(_)
             | Pattern::Missing(_) => vec![],
@@ -81,12 +101,16 @@ impl Pattern {
     pub fn stable_ptr(&self) -> ast::PatternPtr {
         match self {
             Pattern::Literal(pattern) => pattern.stable_ptr,
+            Pattern::Range(pattern) => pattern.stable_ptr.into(),
             Pattern::StringLiteral(pattern) => pattern.stable_ptr,
             Pattern::Variable(pattern) => pattern.stable_ptr,
             Pattern::Struct(pattern) => pattern.stable_ptr.into(),
             Pattern::Tuple(pattern) => pattern.stable_ptr.into(),
             Pattern::FixedSizeArray(pattern) => pattern.stable_ptr.into(),
             Pattern::EnumVariant(pattern) => pattern.stable_ptr,
+            Pattern::Or(pattern) => pattern.stable_ptr.into(),
+            Pattern::Binding(pattern) => pattern.stable_ptr.into(),
+            Pattern::Rest(pattern) => pattern.stable_ptr.into(),
             Pattern::Otherwise(pattern) => pattern.stable_ptr.into(),
             Pattern::Missing(pattern) => pattern.stable_ptr,
         }
@@ -135,6 +159,20 @@ pub struct PatternLiteral {
     pub stable_ptr: ast::PatternPtr,
 }
 
+/// A pattern matching the numeric values from `start` to `end`, including `end` only if
+/// `is_inclusive` is set.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DebugWithDb, SemanticObject)]
+#[debug_db(ExprFormatter<'a>)]
+pub struct PatternRange {
+    pub start: ExprLiteral,
+    pub end: ExprLiteral,
+    #[dont_rewrite]
+    pub is_inclusive: bool,
+    #[hide_field_debug_with_db]
+    #[dont_rewrite]
+    pub stable_ptr: ast::PatternRangePtr,
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq, DebugWithDb, SemanticObject)]
 #[debug_db(ExprFormatter<'a>)]
 pub struct PatternStringLiteral {
@@ -208,6 +246,41 @@ pub struct PatternEnumVariant {
     pub stable_ptr: ast::PatternPtr,
 }
 
+/// A pattern matching any of its alternatives, which bind the same variables.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DebugWithDb, SemanticObject)]
+#[debug_db(ExprFormatter<'a>)]
+pub struct PatternOr {
+    pub alternatives: Vec<PatternId>,
+    pub ty: semantic::TypeId,
+    #[hide_field_debug_with_db]
+    #[dont_rewrite]
+    pub stable_ptr: ast::PatternOrPtr,
+}
+
+/// A pattern binding the whole matched value to a variable, while also matching it by an inner
+/// pattern, e.g. `x @ MyEnum::A(_)`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DebugWithDb, SemanticObject)]
+#[debug_db(ExprFormatter<'a>)]
+pub struct PatternBinding {
+    pub binding: PatternVariable,
+    pub inner_pattern: PatternId,
+    #[hide_field_debug_with_db]
+    #[dont_rewrite]
+    pub stable_ptr: ast::PatternBindingPtr,
+}
+
+/// A pattern matching the remaining elements of a span, as the last element of a span pattern,
+/// optionally binding them to a variable, e.g. `.. tail`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DebugWithDb, SemanticObject)]
+#[debug_db(ExprFormatter<'a>)]
+pub struct PatternRest {
+    pub binding: Option<PatternVariable>,
+    pub ty: semantic::TypeId,
+    #[hide_field_debug_with_db]
+    #[dont_rewrite]
+    pub stable_ptr: ast::PatternRestPtr,
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq, DebugWithDb, SemanticObject)]
 #[debug_db(ExprFormatter<'a>)]
 pub struct PatternOtherwise {
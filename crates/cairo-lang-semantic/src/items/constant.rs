@@ -43,9 +43,9 @@ use crate::substitution::{GenericSubstitution, SemanticRewriter};
 use crate::types::resolve_type;
 use crate::{
     Arenas, ConcreteFunction, ConcreteTypeId, ConcreteVariant, Condition, Expr, ExprBlock,
-    ExprConstant, ExprFunctionCall, ExprFunctionCallArg, ExprId, ExprMemberAccess, ExprStructCtor,
-    FunctionId, GenericParam, LogicalOperator, Pattern, PatternId, SemanticDiagnostic, Statement,
-    TypeId, TypeLongId, semantic_object_for_id,
+    ExprConstant, ExprFunctionCall, ExprFunctionCallArg, ExprId, ExprMatch, ExprMemberAccess,
+    ExprStructCtor, FunctionId, GenericParam, LogicalOperator, MatchArm, Pattern, PatternId,
+    SemanticDiagnostic, Statement, TypeId, TypeLongId, semantic_object_for_id,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, DebugWithDb)]
@@ -428,6 +428,37 @@ pub fn resolve_const_expr_and_evaluate(
     }
 }
 
+/// Evaluates the given expression of a function body, if it is a valid constant expression, without
+/// reporting diagnostics.
+///
+/// Variables are not evaluated, so the expression should not use variables defined outside of it.
+pub fn try_evaluate_const_expr(
+    db: &dyn SemanticGroup,
+    arenas: &Arenas,
+    expr_id: ExprId,
+) -> Option<ConstValue> {
+    let info = db.const_calc_info();
+    let mut diagnostics = SemanticDiagnostics::default();
+    let mut eval_ctx = ConstantEvaluateContext {
+        db,
+        info: info.as_ref(),
+        arenas,
+        vars: Default::default(),
+        generic_substitution: Default::default(),
+        depth: 0,
+        diagnostics: &mut diagnostics,
+    };
+    eval_ctx.validate(expr_id);
+    if eval_ctx.diagnostics.error_count > 0 {
+        return None;
+    }
+    let value = eval_ctx.evaluate(expr_id);
+    if eval_ctx.diagnostics.error_count > 0 || matches!(value, ConstValue::Missing(_)) {
+        return None;
+    }
+    Some(value)
+}
+
 /// creates a [ConstValue] from a [BigInt] value.
 pub fn value_as_const_value(
     db: &dyn SemanticGroup,
@@ -554,6 +585,9 @@ impl ConstantEvaluateContext<'_> {
             Expr::Match(expr) => {
                 self.validate(expr.matched_expr);
                 for arm in &expr.arms {
+                    if let Some(guard) = arm.guard {
+                        self.validate(guard);
+                    }
                     self.validate(arm.expression);
                 }
             }
@@ -720,6 +754,9 @@ impl ConstantEvaluateContext<'_> {
             }
             Expr::Match(expr) => {
                 let value = self.evaluate(expr.matched_expr);
+                if let ConstValue::Int(value, _) = &value {
+                    return self.evaluate_numeric_match(expr, value);
+                }
                 let ConstValue::Enum(variant, value) = value else {
                     return ConstValue::Missing(skip_diagnostic());
                 };
@@ -727,7 +764,11 @@ impl ConstantEvaluateContext<'_> {
                     for pattern_id in &arm.patterns {
                         let pattern = &self.arenas.patterns[*pattern_id];
                         if matches!(pattern, Pattern::Otherwise(_)) {
-                            return self.evaluate(arm.expression);
+                            match self.evaluate_guard(arm) {
+                                Some(true) => return self.evaluate(arm.expression),
+                                Some(false) => break,
+                                None => return ConstValue::Missing(skip_diagnostic()),
+                            }
                         }
                         let Pattern::EnumVariant(pattern) = pattern else {
                             continue;
@@ -736,9 +777,16 @@ impl ConstantEvaluateContext<'_> {
                             continue;
                         }
                         if let Some(inner_pattern) = pattern.inner_pattern {
-                            self.destructure_pattern(inner_pattern, *value);
+                            if !self.is_pattern_match(inner_pattern, &value) {
+                                continue;
+                            }
+                            self.destructure_pattern(inner_pattern, (*value).clone());
+                        }
+                        match self.evaluate_guard(arm) {
+                            Some(true) => return self.evaluate(arm.expression),
+                            Some(false) => break,
+                            None => return ConstValue::Missing(skip_diagnostic()),
                         }
-                        return self.evaluate(arm.expression);
                     }
                 }
                 ConstValue::Missing(
@@ -791,6 +839,74 @@ impl ConstantEvaluateContext<'_> {
         }
     }
 
+    /// Evaluates the guard of a match arm whose pattern matched, returning whether the arm is
+    /// taken, or `None` if the guard could not be evaluated.
+    fn evaluate_guard(&mut self, arm: &MatchArm) -> Option<bool> {
+        let Some(guard) = arm.guard else {
+            return Some(true);
+        };
+        let ConstValue::Enum(variant, _) = self.evaluate(guard) else {
+            return None;
+        };
+        Some(variant == true_variant(self.db))
+    }
+
+    /// Returns whether `value` matches the pattern, checking the patterns which may be refuted
+    /// when nested in another pattern.
+    fn is_pattern_match(&self, pattern_id: PatternId, value: &ConstValue) -> bool {
+        match (&self.arenas.patterns[pattern_id], value) {
+            (Pattern::Or(pattern), _) => pattern
+                .alternatives
+                .iter()
+                .any(|alternative| self.is_pattern_match(*alternative, value)),
+            (Pattern::Binding(pattern), _) => self.is_pattern_match(pattern.inner_pattern, value),
+            (Pattern::EnumVariant(pattern), ConstValue::Enum(variant, inner_value)) => {
+                pattern.variant.idx == variant.idx
+                    && pattern.inner_pattern.is_none_or(|inner_pattern| {
+                        self.is_pattern_match(inner_pattern, inner_value)
+                    })
+            }
+            (Pattern::Literal(pattern), ConstValue::Int(value, _)) => {
+                pattern.literal.value == *value
+            }
+            _ => true,
+        }
+    }
+
+    /// Evaluates a match on a numeric value, by selecting the arm whose literal pattern equals
+    /// the value, or whose range pattern contains it, or the wildcard arm.
+    fn evaluate_numeric_match(&mut self, expr: &ExprMatch, value: &BigInt) -> ConstValue {
+        for arm in &expr.arms {
+            for pattern_id in &arm.patterns {
+                let is_match = match &self.arenas.patterns[*pattern_id] {
+                    Pattern::Literal(pattern) => pattern.literal.value == *value,
+                    Pattern::Range(pattern) => {
+                        pattern.start.value <= *value
+                            && if pattern.is_inclusive {
+                                *value <= pattern.end.value
+                            } else {
+                                *value < pattern.end.value
+                            }
+                    }
+                    Pattern::Otherwise(_) => true,
+                    _ => false,
+                };
+                if !is_match {
+                    continue;
+                }
+                match self.evaluate_guard(arm) {
+                    Some(true) => return self.evaluate(arm.expression),
+                    Some(false) => break,
+                    None => return ConstValue::Missing(skip_diagnostic()),
+                }
+            }
+        }
+        ConstValue::Missing(
+            self.diagnostics
+                .report(expr.stable_ptr.untyped(), SemanticDiagnosticKind::UnsupportedConstant),
+        )
+    }
+
     /// Attempts to evaluate constants from a const function call.
     fn evaluate_function_call(&mut self, expr: &ExprFunctionCall) -> ConstValue {
         let db = self.db;
@@ -1009,12 +1125,18 @@ impl ConstantEvaluateContext<'_> {
         let pattern = &self.arenas.patterns[pattern_id];
         match pattern {
             Pattern::Literal(_)
+            | Pattern::Range(_)
             | Pattern::StringLiteral(_)
+            | Pattern::Rest(_)
             | Pattern::Otherwise(_)
             | Pattern::Missing(_) => {}
             Pattern::Variable(pattern) => {
                 self.vars.insert(VarId::Local(pattern.var.id), value);
             }
+            Pattern::Binding(pattern) => {
+                self.vars.insert(VarId::Local(pattern.binding.var.id), value.clone());
+                self.destructure_pattern(pattern.inner_pattern, value);
+            }
             Pattern::Struct(pattern) => {
                 if let ConstValue::Struct(inner_values, _) = value {
                     let member_order =
@@ -1056,6 +1178,15 @@ impl ConstantEvaluateContext<'_> {
                     }
                 }
             }
+            Pattern::Or(pattern) => {
+                if let Some(alternative) = pattern
+                    .alternatives
+                    .iter()
+                    .find(|alternative| self.is_pattern_match(**alternative, &value))
+                {
+                    self.destructure_pattern(*alternative, value);
+                }
+            }
         }
     }
 }
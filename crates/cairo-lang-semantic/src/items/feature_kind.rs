@@ -220,6 +220,8 @@ pub fn extract_item_feature_config(
                 config.allow_unused_imports = true;
                 true
             }
+            // Checked by the lowering of matches.
+            "duplicate_match_arm_body" => true,
             other => db.declared_allows(crate_id).contains(other),
         },
     );
@@ -53,12 +53,13 @@ fn test_expr_lookup() {
              [Value(Literal(ExprLiteral { value: 1, ty: core::felt252 })), \
              Value(Literal(ExprLiteral { value: 1, ty: core::felt252 }))], coupon_arg: None, ty: \
              core::felt252 }), arms: [MatchArm { patterns: [Literal(PatternLiteral { literal: \
-             ExprLiteral { value: 0, ty: core::felt252 } })], expression: Block(ExprBlock { \
-             statements: [], tail: Some(Literal(ExprLiteral { value: 5, ty: core::felt252 })), \
-             ty: core::felt252 }) }, MatchArm { patterns: [Otherwise(PatternOtherwise { ty: \
-             core::felt252 })], expression: Block(ExprBlock { statements: [], tail: \
-             Some(Literal(ExprLiteral { value: 6, ty: core::felt252 })), ty: core::felt252 }) }], \
-             ty: core::felt252 })), ty: core::felt252 })",
+             ExprLiteral { value: 0, ty: core::felt252 } })], guard: None, expression: \
+             Block(ExprBlock { statements: [], tail: Some(Literal(ExprLiteral { value: 5, ty: \
+             core::felt252 })), ty: core::felt252 }) }, MatchArm { patterns: \
+             [Otherwise(PatternOtherwise { ty: core::felt252 })], guard: None, expression: \
+             Block(ExprBlock { statements: [], tail: Some(Literal(ExprLiteral { value: 6, ty: \
+             core::felt252 })), ty: core::felt252 }) }], ty: core::felt252 })), ty: core::felt252 \
+             })",
             "Block(ExprBlock { statements: [], tail: Some(Literal(ExprLiteral { value: 5, ty: \
              core::felt252 })), ty: core::felt252 })",
             "Block(ExprBlock { statements: [], tail: Some(Literal(ExprLiteral { value: 6, ty: \
@@ -82,11 +83,11 @@ fn test_expr_lookup() {
              core::felt252 })), Value(Literal(ExprLiteral { value: 1, ty: core::felt252 }))], \
              coupon_arg: None, ty: core::felt252 }), arms: [MatchArm { patterns: \
              [Literal(PatternLiteral { literal: ExprLiteral { value: 0, ty: core::felt252 } })], \
-             expression: Block(ExprBlock { statements: [], tail: Some(Literal(ExprLiteral { \
-             value: 5, ty: core::felt252 })), ty: core::felt252 }) }, MatchArm { patterns: \
-             [Otherwise(PatternOtherwise { ty: core::felt252 })], expression: Block(ExprBlock { \
-             statements: [], tail: Some(Literal(ExprLiteral { value: 6, ty: core::felt252 })), \
-             ty: core::felt252 }) }], ty: core::felt252 })",
+             guard: None, expression: Block(ExprBlock { statements: [], tail: \
+             Some(Literal(ExprLiteral { value: 5, ty: core::felt252 })), ty: core::felt252 }) }, \
+             MatchArm { patterns: [Otherwise(PatternOtherwise { ty: core::felt252 })], guard: \
+             None, expression: Block(ExprBlock { statements: [], tail: Some(Literal(ExprLiteral { \
+             value: 6, ty: core::felt252 })), ty: core::felt252 }) }], ty: core::felt252 })",
         ]
     );
 
@@ -10,8 +10,9 @@ use smol_str::SmolStr;
 pub use super::expr::objects::*;
 use crate::db::SemanticGroup;
 pub use crate::expr::pattern::{
-    Pattern, PatternEnumVariant, PatternFixedSizeArray, PatternLiteral, PatternOtherwise,
-    PatternStringLiteral, PatternStruct, PatternTuple, PatternVariable,
+    Pattern, PatternBinding, PatternEnumVariant, PatternFixedSizeArray, PatternLiteral, PatternOr,
+    PatternOtherwise, PatternRange, PatternRest, PatternStringLiteral, PatternStruct, PatternTuple,
+    PatternVariable,
 };
 use crate::items::constant::ConstValueId;
 pub use crate::items::enm::{ConcreteVariant, MatchArmSelector, ValueSelectorArm, Variant};
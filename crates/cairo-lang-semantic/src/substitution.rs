@@ -429,6 +429,10 @@ macro_rules! add_expr_rewrites {
         $crate::prune_single!(__regular_helper, StatementItem, $($exclude)*);
         $crate::prune_single!(__regular_helper, Pattern, $($exclude)*);
         $crate::prune_single!(__regular_helper, PatternLiteral, $($exclude)*);
+        $crate::prune_single!(__regular_helper, PatternRange, $($exclude)*);
+        $crate::prune_single!(__regular_helper, PatternOr, $($exclude)*);
+        $crate::prune_single!(__regular_helper, PatternBinding, $($exclude)*);
+        $crate::prune_single!(__regular_helper, PatternRest, $($exclude)*);
         $crate::prune_single!(__regular_helper, PatternStringLiteral, $($exclude)*);
         $crate::prune_single!(__regular_helper, PatternVariable, $($exclude)*);
         $crate::prune_single!(__regular_helper, PatternStruct, $($exclude)*);
@@ -7,13 +7,14 @@ use cairo_lang_filesystem::ids::{CodeMapping, CodeOrigin};
 use cairo_lang_filesystem::span::{TextSpan, TextWidth};
 use cairo_lang_syntax::node::db::SyntaxGroup;
 use cairo_lang_syntax::node::{TypedSyntaxNode, ast};
-use cairo_lang_utils::extract_matches;
+use cairo_lang_utils::{LookupIntern, extract_matches};
 use indoc::indoc;
 use itertools::Itertools;
 
 use crate::db::SemanticGroup;
 use crate::inline_macros::get_default_plugin_suite;
 use crate::test_utils::{SemanticDatabaseForTesting, setup_test_module};
+use crate::types::{peel_snapshots, wrap_in_snapshots};
 
 #[test]
 fn test_resolve() {
@@ -170,3 +171,13 @@ fn test_mapping_translate_consecutive_spans() {
     "#}
     );
 }
+
+#[test]
+fn test_wrap_in_snapshots() {
+    let db = &SemanticDatabaseForTesting::default();
+    let felt252_ty = db.core_info().felt252;
+    // Wrapping with no snapshots returns the type itself.
+    assert_eq!(wrap_in_snapshots(db, felt252_ty, 0), felt252_ty);
+    let snapshot_ty = wrap_in_snapshots(db, felt252_ty, 2);
+    assert_eq!(peel_snapshots(db, snapshot_ty), (2, felt252_ty.lookup_intern(db)));
+}
@@ -182,6 +182,15 @@ impl Usages {
         usage
     }
 
+    /// Returns the usage of an expression that is lowered as a scope of its own, such as an
+    /// outlined match arm body.
+    pub fn scope_expr_usage(arenas: &Arenas, expr_id: ExprId) -> Usage {
+        let mut usage: Usage = Default::default();
+        Self { usages: Default::default() }.handle_expr(arenas, expr_id, &mut usage);
+        usage.finalize_as_scope();
+        usage
+    }
+
     fn handle_expr(&mut self, arenas: &Arenas, expr_id: ExprId, current: &mut Usage) {
         match &arenas.exprs[expr_id] {
             Expr::Tuple(expr) => {
@@ -329,6 +338,9 @@ impl Usages {
                     for pattern in &arm.patterns {
                         Self::handle_pattern(&arenas.patterns, *pattern, current);
                     }
+                    if let Some(guard) = arm.guard {
+                        self.handle_expr(arenas, guard, current);
+                    }
                     self.handle_expr(arenas, arm.expression, current);
                 }
             }
@@ -384,7 +396,7 @@ impl Usages {
     fn handle_pattern(arena: &Arena<Pattern>, pattern: PatternId, current: &mut Usage) {
         let pattern = &arena[pattern];
         match pattern {
-            Pattern::Literal(_) | Pattern::StringLiteral(_) => {}
+            Pattern::Literal(_) | Pattern::Range(_) | Pattern::StringLiteral(_) => {}
             Pattern::Variable(pattern) => {
                 current.introductions.insert(VarId::Local(pattern.var.id));
             }
@@ -408,6 +420,20 @@ impl Usages {
                     Self::handle_pattern(arena, *inner_pattern, current);
                 }
             }
+            Pattern::Or(pattern) => {
+                for pattern in &pattern.alternatives {
+                    Self::handle_pattern(arena, *pattern, current);
+                }
+            }
+            Pattern::Binding(pattern) => {
+                current.introductions.insert(VarId::Local(pattern.binding.var.id));
+                Self::handle_pattern(arena, pattern.inner_pattern, current);
+            }
+            Pattern::Rest(pattern) => {
+                if let Some(binding) = &pattern.binding {
+                    current.introductions.insert(VarId::Local(binding.var.id));
+                }
+            }
             Pattern::Otherwise(_) => {}
             Pattern::Missing(_) => {}
         }
@@ -9,6 +9,7 @@ pub mod multi_component;
 mod new_syntax_test_contract;
 mod ownable_erc20;
 mod storage_accesses;
+mod stored_enum_match;
 pub mod test_contract;
 mod token_bridge;
 mod upgradable_counter;
@@ -0,0 +1,32 @@
+#[derive(Copy, Drop, Serde, starknet::Store)]
+pub enum Status {
+    #[default]
+    Idle,
+    Running: u32,
+    Done,
+}
+
+#[starknet::contract]
+mod stored_enum_match {
+    use starknet::storage::{StoragePointerReadAccess, StoragePointerWriteAccess};
+    use super::Status;
+
+    #[storage]
+    struct Storage {
+        status: Status,
+    }
+
+    #[external(v0)]
+    fn set_status(ref self: ContractState, status: Status) {
+        self.status.write(status);
+    }
+
+    #[external(v0)]
+    fn status_code(self: @ContractState) -> u32 {
+        match self.status.read() {
+            Status::Idle => 0,
+            Status::Running(steps) => steps + 1,
+            Status::Done => 0xffffffff,
+        }
+    }
+}
@@ -16,6 +16,7 @@ use crate::test_utils::{get_example_file_path, get_test_contract};
 #[test_case("libfuncs_coverage::libfuncs_coverage")]
 #[test_case("erc20::erc_20")]
 #[test_case("storage_accesses::storage_accesses")]
+#[test_case("stored_enum_match::stored_enum_match")]
 #[test_case("token_bridge::token_bridge")]
 #[test_case("with_erc20::erc20_contract")]
 #[test_case("with_ownable::ownable_balance")]
@@ -171,9 +171,15 @@ pub fn get_spec() -> Vec<Node> {
     .add_separated_list("MatchArms", "MatchArm", "TerminalComma")
     .add_struct(StructBuilder::new("MatchArm")
         .node("patterns", "PatternListOr")
+        .node("guard", "OptionMatchArmGuard")
         .node("arrow", "TerminalMatchArrow")
         .node("expression", "Expr")
     )
+    .add_struct(StructBuilder::new("MatchArmGuard")
+        .node("if_kw", "TerminalIf")
+        .node("condition", "Expr")
+    )
+    .add_option("MatchArmGuard")
     .add_struct(StructBuilder::new("ExprIf")
         .node("if_kw", "TerminalIf")
         .node("condition", "Condition")
@@ -307,6 +313,10 @@ pub fn get_spec() -> Vec<Node> {
         .node("Tuple")
         .node("Enum")
         .node("FixedSizeArray")
+        .node("Range")
+        .node("Or")
+        .node("Binding")
+        .node("Rest")
         .node_with_explicit_kind("Path", "ExprPath")
     )
     .add_struct(StructBuilder::new("PatternIdentifier")
@@ -330,6 +340,28 @@ pub fn get_spec() -> Vec<Node> {
         .node("lbrack", "TerminalLBrack")
         .node("patterns", "PatternList")
         .node("rbrack", "TerminalRBrack"))
+    .add_struct(StructBuilder::new("PatternRange")
+        .node("start", "TerminalLiteralNumber")
+        .node("op", "PatternRangeOperator")
+        .node("end", "TerminalLiteralNumber")
+    )
+    .add_enum(EnumBuilder::new("PatternRangeOperator")
+        .node_with_explicit_kind("DotDot", "TerminalDotDot")
+        .node_with_explicit_kind("DotDotEq", "TerminalDotDotEq")
+    )
+    .add_struct(StructBuilder::new("PatternOr")
+        .node("patterns", "PatternListOr")
+    )
+    .add_struct(StructBuilder::new("PatternBinding")
+        .node("binding", "PatternIdentifier")
+        .node("at", "TerminalAt")
+        .node("pattern", "Pattern")
+    )
+    .add_struct(StructBuilder::new("PatternRest")
+        .node("dotdot", "TerminalDotDot")
+        .node("binding", "OptionPatternIdentifier")
+    )
+    .add_option("PatternIdentifier")
     .add_separated_list("PatternList", "Pattern", "TerminalComma")
     .add_separated_list("PatternListOr", "Pattern", "TerminalOr")
     .add_enum(EnumBuilder::new("PatternStructParam")
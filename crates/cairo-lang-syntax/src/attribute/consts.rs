@@ -35,6 +35,22 @@ pub const FEATURE_ATTR: &str = "feature";
 /// An attribute to define the order of implicit arguments.
 pub const IMPLICIT_PRECEDENCE_ATTR: &str = "implicit_precedence";
 
+/// An attribute to choose how numeric matches in a function are lowered - `jump_table` or
+/// `if_chain` - regardless of the number of arms.
+pub const MATCH_STRATEGY_ATTR: &str = "match_strategy";
+
+/// An attribute to set the minimal number of arms of the numeric matches in a function lowered into
+/// a jump table - `#[match_jump_table(min_arms = N)]`, overriding the compiler's threshold.
+pub const MATCH_JUMP_TABLE_ATTR: &str = "match_jump_table";
+
+/// An attribute to forbid catch-all patterns (`_`) in matches on an enum, so that each variant must
+/// be handled explicitly.
+pub const NO_CATCH_ALL_ATTR: &str = "no_catch_all";
+
+/// An attribute linking the matches in a function to the matches on the same enums in another
+/// function of the same module, verifying that they handle the same variants explicitly.
+pub const CONSISTENT_MATCH_ATTR: &str = "consistent_match";
+
 /// An attribute for the declaration of a starknet interface.
 ///
 /// It is used in the starknet crate, however it is defined here because it is currently used in the
@@ -4370,15 +4370,17 @@ pub struct MatchArm {
 }
 impl MatchArm {
     pub const INDEX_PATTERNS: usize = 0;
-    pub const INDEX_ARROW: usize = 1;
-    pub const INDEX_EXPRESSION: usize = 2;
+    pub const INDEX_GUARD: usize = 1;
+    pub const INDEX_ARROW: usize = 2;
+    pub const INDEX_EXPRESSION: usize = 3;
     pub fn new_green(
         db: &dyn SyntaxGroup,
         patterns: PatternListOrGreen,
+        guard: OptionMatchArmGuardGreen,
         arrow: TerminalMatchArrowGreen,
         expression: ExprGreen,
     ) -> MatchArmGreen {
-        let children: Vec<GreenId> = vec![patterns.0, arrow.0, expression.0];
+        let children: Vec<GreenId> = vec![patterns.0, guard.0, arrow.0, expression.0];
         let width = children.iter().copied().map(|id| id.lookup_intern(db).width()).sum();
         MatchArmGreen(
             Arc::new(GreenNode {
@@ -4393,11 +4395,14 @@ impl MatchArm {
     pub fn patterns(&self, db: &dyn SyntaxGroup) -> PatternListOr {
         PatternListOr::from_syntax_node(db, self.children[0].clone())
     }
+    pub fn guard(&self, db: &dyn SyntaxGroup) -> OptionMatchArmGuard {
+        OptionMatchArmGuard::from_syntax_node(db, self.children[1].clone())
+    }
     pub fn arrow(&self, db: &dyn SyntaxGroup) -> TerminalMatchArrow {
-        TerminalMatchArrow::from_syntax_node(db, self.children[1].clone())
+        TerminalMatchArrow::from_syntax_node(db, self.children[2].clone())
     }
     pub fn expression(&self, db: &dyn SyntaxGroup) -> Expr {
-        Expr::from_syntax_node(db, self.children[2].clone())
+        Expr::from_syntax_node(db, self.children[3].clone())
     }
 }
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
@@ -4430,6 +4435,7 @@ impl TypedSyntaxNode for MatchArm {
                 details: GreenNodeDetails::Node {
                     children: vec![
                         PatternListOr::missing(db).0,
+                        OptionMatchArmGuard::missing(db).0,
                         TerminalMatchArrow::missing(db).0,
                         Expr::missing(db).0,
                     ],
@@ -4468,6 +4474,286 @@ impl From<&MatchArm> for SyntaxStablePtrId {
     }
 }
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MatchArmGuard {
+    node: SyntaxNode,
+    children: Arc<[SyntaxNode]>,
+}
+impl MatchArmGuard {
+    pub const INDEX_IF_KW: usize = 0;
+    pub const INDEX_CONDITION: usize = 1;
+    pub fn new_green(
+        db: &dyn SyntaxGroup,
+        if_kw: TerminalIfGreen,
+        condition: ExprGreen,
+    ) -> MatchArmGuardGreen {
+        let children: Vec<GreenId> = vec![if_kw.0, condition.0];
+        let width = children.iter().copied().map(|id| id.lookup_intern(db).width()).sum();
+        MatchArmGuardGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::MatchArmGuard,
+                details: GreenNodeDetails::Node { children, width },
+            })
+            .intern(db),
+        )
+    }
+}
+impl MatchArmGuard {
+    pub fn if_kw(&self, db: &dyn SyntaxGroup) -> TerminalIf {
+        TerminalIf::from_syntax_node(db, self.children[0].clone())
+    }
+    pub fn condition(&self, db: &dyn SyntaxGroup) -> Expr {
+        Expr::from_syntax_node(db, self.children[1].clone())
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct MatchArmGuardPtr(pub SyntaxStablePtrId);
+impl MatchArmGuardPtr {}
+impl TypedStablePtr for MatchArmGuardPtr {
+    type SyntaxNode = MatchArmGuard;
+    fn untyped(&self) -> SyntaxStablePtrId {
+        self.0
+    }
+    fn lookup(&self, db: &dyn SyntaxGroup) -> MatchArmGuard {
+        MatchArmGuard::from_syntax_node(db, self.0.lookup(db))
+    }
+}
+impl From<MatchArmGuardPtr> for SyntaxStablePtrId {
+    fn from(ptr: MatchArmGuardPtr) -> Self {
+        ptr.untyped()
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct MatchArmGuardGreen(pub GreenId);
+impl TypedSyntaxNode for MatchArmGuard {
+    const OPTIONAL_KIND: Option<SyntaxKind> = Some(SyntaxKind::MatchArmGuard);
+    type StablePtr = MatchArmGuardPtr;
+    type Green = MatchArmGuardGreen;
+    fn missing(db: &dyn SyntaxGroup) -> Self::Green {
+        MatchArmGuardGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::MatchArmGuard,
+                details: GreenNodeDetails::Node {
+                    children: vec![TerminalIf::missing(db).0, Expr::missing(db).0],
+                    width: TextWidth::default(),
+                },
+            })
+            .intern(db),
+        )
+    }
+    fn from_syntax_node(db: &dyn SyntaxGroup, node: SyntaxNode) -> Self {
+        let kind = node.kind(db);
+        assert_eq!(
+            kind,
+            SyntaxKind::MatchArmGuard,
+            "Unexpected SyntaxKind {:?}. Expected {:?}.",
+            kind,
+            SyntaxKind::MatchArmGuard
+        );
+        let children = db.get_children(node.clone());
+        Self { node, children }
+    }
+    fn cast(db: &dyn SyntaxGroup, node: SyntaxNode) -> Option<Self> {
+        let kind = node.kind(db);
+        if kind == SyntaxKind::MatchArmGuard {
+            Some(Self::from_syntax_node(db, node))
+        } else {
+            None
+        }
+    }
+    fn as_syntax_node(&self) -> SyntaxNode {
+        self.node.clone()
+    }
+    fn stable_ptr(&self) -> Self::StablePtr {
+        MatchArmGuardPtr(self.node.0.stable_ptr)
+    }
+}
+impl From<&MatchArmGuard> for SyntaxStablePtrId {
+    fn from(node: &MatchArmGuard) -> Self {
+        node.stable_ptr().untyped()
+    }
+}
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum OptionMatchArmGuard {
+    Empty(OptionMatchArmGuardEmpty),
+    MatchArmGuard(MatchArmGuard),
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct OptionMatchArmGuardPtr(pub SyntaxStablePtrId);
+impl TypedStablePtr for OptionMatchArmGuardPtr {
+    type SyntaxNode = OptionMatchArmGuard;
+    fn untyped(&self) -> SyntaxStablePtrId {
+        self.0
+    }
+    fn lookup(&self, db: &dyn SyntaxGroup) -> OptionMatchArmGuard {
+        OptionMatchArmGuard::from_syntax_node(db, self.0.lookup(db))
+    }
+}
+impl From<OptionMatchArmGuardPtr> for SyntaxStablePtrId {
+    fn from(ptr: OptionMatchArmGuardPtr) -> Self {
+        ptr.untyped()
+    }
+}
+impl From<OptionMatchArmGuardEmptyPtr> for OptionMatchArmGuardPtr {
+    fn from(value: OptionMatchArmGuardEmptyPtr) -> Self {
+        Self(value.0)
+    }
+}
+impl From<MatchArmGuardPtr> for OptionMatchArmGuardPtr {
+    fn from(value: MatchArmGuardPtr) -> Self {
+        Self(value.0)
+    }
+}
+impl From<OptionMatchArmGuardEmptyGreen> for OptionMatchArmGuardGreen {
+    fn from(value: OptionMatchArmGuardEmptyGreen) -> Self {
+        Self(value.0)
+    }
+}
+impl From<MatchArmGuardGreen> for OptionMatchArmGuardGreen {
+    fn from(value: MatchArmGuardGreen) -> Self {
+        Self(value.0)
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct OptionMatchArmGuardGreen(pub GreenId);
+impl TypedSyntaxNode for OptionMatchArmGuard {
+    const OPTIONAL_KIND: Option<SyntaxKind> = None;
+    type StablePtr = OptionMatchArmGuardPtr;
+    type Green = OptionMatchArmGuardGreen;
+    fn missing(db: &dyn SyntaxGroup) -> Self::Green {
+        panic!("No missing variant.");
+    }
+    fn from_syntax_node(db: &dyn SyntaxGroup, node: SyntaxNode) -> Self {
+        let kind = node.kind(db);
+        match kind {
+            SyntaxKind::OptionMatchArmGuardEmpty => {
+                OptionMatchArmGuard::Empty(OptionMatchArmGuardEmpty::from_syntax_node(db, node))
+            }
+            SyntaxKind::MatchArmGuard => {
+                OptionMatchArmGuard::MatchArmGuard(MatchArmGuard::from_syntax_node(db, node))
+            }
+            _ => panic!(
+                "Unexpected syntax kind {:?} when constructing {}.",
+                kind, "OptionMatchArmGuard"
+            ),
+        }
+    }
+    fn cast(db: &dyn SyntaxGroup, node: SyntaxNode) -> Option<Self> {
+        let kind = node.kind(db);
+        match kind {
+            SyntaxKind::OptionMatchArmGuardEmpty => Some(OptionMatchArmGuard::Empty(
+                OptionMatchArmGuardEmpty::from_syntax_node(db, node),
+            )),
+            SyntaxKind::MatchArmGuard => {
+                Some(OptionMatchArmGuard::MatchArmGuard(MatchArmGuard::from_syntax_node(db, node)))
+            }
+            _ => None,
+        }
+    }
+    fn as_syntax_node(&self) -> SyntaxNode {
+        match self {
+            OptionMatchArmGuard::Empty(x) => x.as_syntax_node(),
+            OptionMatchArmGuard::MatchArmGuard(x) => x.as_syntax_node(),
+        }
+    }
+    fn stable_ptr(&self) -> Self::StablePtr {
+        OptionMatchArmGuardPtr(self.as_syntax_node().0.stable_ptr)
+    }
+}
+impl From<&OptionMatchArmGuard> for SyntaxStablePtrId {
+    fn from(node: &OptionMatchArmGuard) -> Self {
+        node.stable_ptr().untyped()
+    }
+}
+impl OptionMatchArmGuard {
+    /// Checks if a kind of a variant of [OptionMatchArmGuard].
+    pub fn is_variant(kind: SyntaxKind) -> bool {
+        matches!(kind, SyntaxKind::OptionMatchArmGuardEmpty | SyntaxKind::MatchArmGuard)
+    }
+}
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct OptionMatchArmGuardEmpty {
+    node: SyntaxNode,
+    children: Arc<[SyntaxNode]>,
+}
+impl OptionMatchArmGuardEmpty {
+    pub fn new_green(db: &dyn SyntaxGroup) -> OptionMatchArmGuardEmptyGreen {
+        let children: Vec<GreenId> = vec![];
+        let width = children.iter().copied().map(|id| id.lookup_intern(db).width()).sum();
+        OptionMatchArmGuardEmptyGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::OptionMatchArmGuardEmpty,
+                details: GreenNodeDetails::Node { children, width },
+            })
+            .intern(db),
+        )
+    }
+}
+impl OptionMatchArmGuardEmpty {}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct OptionMatchArmGuardEmptyPtr(pub SyntaxStablePtrId);
+impl OptionMatchArmGuardEmptyPtr {}
+impl TypedStablePtr for OptionMatchArmGuardEmptyPtr {
+    type SyntaxNode = OptionMatchArmGuardEmpty;
+    fn untyped(&self) -> SyntaxStablePtrId {
+        self.0
+    }
+    fn lookup(&self, db: &dyn SyntaxGroup) -> OptionMatchArmGuardEmpty {
+        OptionMatchArmGuardEmpty::from_syntax_node(db, self.0.lookup(db))
+    }
+}
+impl From<OptionMatchArmGuardEmptyPtr> for SyntaxStablePtrId {
+    fn from(ptr: OptionMatchArmGuardEmptyPtr) -> Self {
+        ptr.untyped()
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct OptionMatchArmGuardEmptyGreen(pub GreenId);
+impl TypedSyntaxNode for OptionMatchArmGuardEmpty {
+    const OPTIONAL_KIND: Option<SyntaxKind> = Some(SyntaxKind::OptionMatchArmGuardEmpty);
+    type StablePtr = OptionMatchArmGuardEmptyPtr;
+    type Green = OptionMatchArmGuardEmptyGreen;
+    fn missing(db: &dyn SyntaxGroup) -> Self::Green {
+        OptionMatchArmGuardEmptyGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::OptionMatchArmGuardEmpty,
+                details: GreenNodeDetails::Node { children: vec![], width: TextWidth::default() },
+            })
+            .intern(db),
+        )
+    }
+    fn from_syntax_node(db: &dyn SyntaxGroup, node: SyntaxNode) -> Self {
+        let kind = node.kind(db);
+        assert_eq!(
+            kind,
+            SyntaxKind::OptionMatchArmGuardEmpty,
+            "Unexpected SyntaxKind {:?}. Expected {:?}.",
+            kind,
+            SyntaxKind::OptionMatchArmGuardEmpty
+        );
+        let children = db.get_children(node.clone());
+        Self { node, children }
+    }
+    fn cast(db: &dyn SyntaxGroup, node: SyntaxNode) -> Option<Self> {
+        let kind = node.kind(db);
+        if kind == SyntaxKind::OptionMatchArmGuardEmpty {
+            Some(Self::from_syntax_node(db, node))
+        } else {
+            None
+        }
+    }
+    fn as_syntax_node(&self) -> SyntaxNode {
+        self.node.clone()
+    }
+    fn stable_ptr(&self) -> Self::StablePtr {
+        OptionMatchArmGuardEmptyPtr(self.node.0.stable_ptr)
+    }
+}
+impl From<&OptionMatchArmGuardEmpty> for SyntaxStablePtrId {
+    fn from(node: &OptionMatchArmGuardEmpty) -> Self {
+        node.stable_ptr().untyped()
+    }
+}
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct ExprIf {
     node: SyntaxNode,
     children: Arc<[SyntaxNode]>,
@@ -7729,6 +8015,10 @@ pub enum Pattern {
     Tuple(PatternTuple),
     Enum(PatternEnum),
     FixedSizeArray(PatternFixedSizeArray),
+    Range(PatternRange),
+    Or(PatternOr),
+    Binding(PatternBinding),
+    Rest(PatternRest),
     Path(ExprPath),
 }
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
@@ -7802,6 +8092,26 @@ impl From<PatternFixedSizeArrayPtr> for PatternPtr {
         Self(value.0)
     }
 }
+impl From<PatternRangePtr> for PatternPtr {
+    fn from(value: PatternRangePtr) -> Self {
+        Self(value.0)
+    }
+}
+impl From<PatternOrPtr> for PatternPtr {
+    fn from(value: PatternOrPtr) -> Self {
+        Self(value.0)
+    }
+}
+impl From<PatternBindingPtr> for PatternPtr {
+    fn from(value: PatternBindingPtr) -> Self {
+        Self(value.0)
+    }
+}
+impl From<PatternRestPtr> for PatternPtr {
+    fn from(value: PatternRestPtr) -> Self {
+        Self(value.0)
+    }
+}
 impl From<ExprPathPtr> for PatternPtr {
     fn from(value: ExprPathPtr) -> Self {
         Self(value.0)
@@ -7862,6 +8172,26 @@ impl From<PatternFixedSizeArrayGreen> for PatternGreen {
         Self(value.0)
     }
 }
+impl From<PatternRangeGreen> for PatternGreen {
+    fn from(value: PatternRangeGreen) -> Self {
+        Self(value.0)
+    }
+}
+impl From<PatternOrGreen> for PatternGreen {
+    fn from(value: PatternOrGreen) -> Self {
+        Self(value.0)
+    }
+}
+impl From<PatternBindingGreen> for PatternGreen {
+    fn from(value: PatternBindingGreen) -> Self {
+        Self(value.0)
+    }
+}
+impl From<PatternRestGreen> for PatternGreen {
+    fn from(value: PatternRestGreen) -> Self {
+        Self(value.0)
+    }
+}
 impl From<ExprPathGreen> for PatternGreen {
     fn from(value: ExprPathGreen) -> Self {
         Self(value.0)
@@ -7902,6 +8232,12 @@ impl TypedSyntaxNode for Pattern {
             SyntaxKind::PatternFixedSizeArray => {
                 Pattern::FixedSizeArray(PatternFixedSizeArray::from_syntax_node(db, node))
             }
+            SyntaxKind::PatternRange => Pattern::Range(PatternRange::from_syntax_node(db, node)),
+            SyntaxKind::PatternOr => Pattern::Or(PatternOr::from_syntax_node(db, node)),
+            SyntaxKind::PatternBinding => {
+                Pattern::Binding(PatternBinding::from_syntax_node(db, node))
+            }
+            SyntaxKind::PatternRest => Pattern::Rest(PatternRest::from_syntax_node(db, node)),
             SyntaxKind::ExprPath => Pattern::Path(ExprPath::from_syntax_node(db, node)),
             _ => panic!("Unexpected syntax kind {:?} when constructing {}.", kind, "Pattern"),
         }
@@ -7940,6 +8276,14 @@ impl TypedSyntaxNode for Pattern {
             SyntaxKind::PatternFixedSizeArray => {
                 Some(Pattern::FixedSizeArray(PatternFixedSizeArray::from_syntax_node(db, node)))
             }
+            SyntaxKind::PatternRange => {
+                Some(Pattern::Range(PatternRange::from_syntax_node(db, node)))
+            }
+            SyntaxKind::PatternOr => Some(Pattern::Or(PatternOr::from_syntax_node(db, node))),
+            SyntaxKind::PatternBinding => {
+                Some(Pattern::Binding(PatternBinding::from_syntax_node(db, node)))
+            }
+            SyntaxKind::PatternRest => Some(Pattern::Rest(PatternRest::from_syntax_node(db, node))),
             SyntaxKind::ExprPath => Some(Pattern::Path(ExprPath::from_syntax_node(db, node))),
             _ => None,
         }
@@ -7957,6 +8301,10 @@ impl TypedSyntaxNode for Pattern {
             Pattern::Tuple(x) => x.as_syntax_node(),
             Pattern::Enum(x) => x.as_syntax_node(),
             Pattern::FixedSizeArray(x) => x.as_syntax_node(),
+            Pattern::Range(x) => x.as_syntax_node(),
+            Pattern::Or(x) => x.as_syntax_node(),
+            Pattern::Binding(x) => x.as_syntax_node(),
+            Pattern::Rest(x) => x.as_syntax_node(),
             Pattern::Path(x) => x.as_syntax_node(),
         }
     }
@@ -7985,6 +8333,10 @@ impl Pattern {
                 | SyntaxKind::PatternTuple
                 | SyntaxKind::PatternEnum
                 | SyntaxKind::PatternFixedSizeArray
+                | SyntaxKind::PatternRange
+                | SyntaxKind::PatternOr
+                | SyntaxKind::PatternBinding
+                | SyntaxKind::PatternRest
                 | SyntaxKind::ExprPath
         )
     }
@@ -8526,6 +8878,682 @@ impl From<&PatternFixedSizeArray> for SyntaxStablePtrId {
     }
 }
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PatternRange {
+    node: SyntaxNode,
+    children: Arc<[SyntaxNode]>,
+}
+impl PatternRange {
+    pub const INDEX_START: usize = 0;
+    pub const INDEX_OP: usize = 1;
+    pub const INDEX_END: usize = 2;
+    pub fn new_green(
+        db: &dyn SyntaxGroup,
+        start: TerminalLiteralNumberGreen,
+        op: PatternRangeOperatorGreen,
+        end: TerminalLiteralNumberGreen,
+    ) -> PatternRangeGreen {
+        let children: Vec<GreenId> = vec![start.0, op.0, end.0];
+        let width = children.iter().copied().map(|id| id.lookup_intern(db).width()).sum();
+        PatternRangeGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::PatternRange,
+                details: GreenNodeDetails::Node { children, width },
+            })
+            .intern(db),
+        )
+    }
+}
+impl PatternRange {
+    pub fn start(&self, db: &dyn SyntaxGroup) -> TerminalLiteralNumber {
+        TerminalLiteralNumber::from_syntax_node(db, self.children[0].clone())
+    }
+    pub fn op(&self, db: &dyn SyntaxGroup) -> PatternRangeOperator {
+        PatternRangeOperator::from_syntax_node(db, self.children[1].clone())
+    }
+    pub fn end(&self, db: &dyn SyntaxGroup) -> TerminalLiteralNumber {
+        TerminalLiteralNumber::from_syntax_node(db, self.children[2].clone())
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PatternRangePtr(pub SyntaxStablePtrId);
+impl PatternRangePtr {}
+impl TypedStablePtr for PatternRangePtr {
+    type SyntaxNode = PatternRange;
+    fn untyped(&self) -> SyntaxStablePtrId {
+        self.0
+    }
+    fn lookup(&self, db: &dyn SyntaxGroup) -> PatternRange {
+        PatternRange::from_syntax_node(db, self.0.lookup(db))
+    }
+}
+impl From<PatternRangePtr> for SyntaxStablePtrId {
+    fn from(ptr: PatternRangePtr) -> Self {
+        ptr.untyped()
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PatternRangeGreen(pub GreenId);
+impl TypedSyntaxNode for PatternRange {
+    const OPTIONAL_KIND: Option<SyntaxKind> = Some(SyntaxKind::PatternRange);
+    type StablePtr = PatternRangePtr;
+    type Green = PatternRangeGreen;
+    fn missing(db: &dyn SyntaxGroup) -> Self::Green {
+        PatternRangeGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::PatternRange,
+                details: GreenNodeDetails::Node {
+                    children: vec![
+                        TerminalLiteralNumber::missing(db).0,
+                        PatternRangeOperator::missing(db).0,
+                        TerminalLiteralNumber::missing(db).0,
+                    ],
+                    width: TextWidth::default(),
+                },
+            })
+            .intern(db),
+        )
+    }
+    fn from_syntax_node(db: &dyn SyntaxGroup, node: SyntaxNode) -> Self {
+        let kind = node.kind(db);
+        assert_eq!(
+            kind,
+            SyntaxKind::PatternRange,
+            "Unexpected SyntaxKind {:?}. Expected {:?}.",
+            kind,
+            SyntaxKind::PatternRange
+        );
+        let children = db.get_children(node.clone());
+        Self { node, children }
+    }
+    fn cast(db: &dyn SyntaxGroup, node: SyntaxNode) -> Option<Self> {
+        let kind = node.kind(db);
+        if kind == SyntaxKind::PatternRange { Some(Self::from_syntax_node(db, node)) } else { None }
+    }
+    fn as_syntax_node(&self) -> SyntaxNode {
+        self.node.clone()
+    }
+    fn stable_ptr(&self) -> Self::StablePtr {
+        PatternRangePtr(self.node.0.stable_ptr)
+    }
+}
+impl From<&PatternRange> for SyntaxStablePtrId {
+    fn from(node: &PatternRange) -> Self {
+        node.stable_ptr().untyped()
+    }
+}
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum PatternRangeOperator {
+    DotDot(TerminalDotDot),
+    DotDotEq(TerminalDotDotEq),
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PatternRangeOperatorPtr(pub SyntaxStablePtrId);
+impl TypedStablePtr for PatternRangeOperatorPtr {
+    type SyntaxNode = PatternRangeOperator;
+    fn untyped(&self) -> SyntaxStablePtrId {
+        self.0
+    }
+    fn lookup(&self, db: &dyn SyntaxGroup) -> PatternRangeOperator {
+        PatternRangeOperator::from_syntax_node(db, self.0.lookup(db))
+    }
+}
+impl From<PatternRangeOperatorPtr> for SyntaxStablePtrId {
+    fn from(ptr: PatternRangeOperatorPtr) -> Self {
+        ptr.untyped()
+    }
+}
+impl From<TerminalDotDotPtr> for PatternRangeOperatorPtr {
+    fn from(value: TerminalDotDotPtr) -> Self {
+        Self(value.0)
+    }
+}
+impl From<TerminalDotDotEqPtr> for PatternRangeOperatorPtr {
+    fn from(value: TerminalDotDotEqPtr) -> Self {
+        Self(value.0)
+    }
+}
+impl From<TerminalDotDotGreen> for PatternRangeOperatorGreen {
+    fn from(value: TerminalDotDotGreen) -> Self {
+        Self(value.0)
+    }
+}
+impl From<TerminalDotDotEqGreen> for PatternRangeOperatorGreen {
+    fn from(value: TerminalDotDotEqGreen) -> Self {
+        Self(value.0)
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PatternRangeOperatorGreen(pub GreenId);
+impl TypedSyntaxNode for PatternRangeOperator {
+    const OPTIONAL_KIND: Option<SyntaxKind> = None;
+    type StablePtr = PatternRangeOperatorPtr;
+    type Green = PatternRangeOperatorGreen;
+    fn missing(db: &dyn SyntaxGroup) -> Self::Green {
+        panic!("No missing variant.");
+    }
+    fn from_syntax_node(db: &dyn SyntaxGroup, node: SyntaxNode) -> Self {
+        let kind = node.kind(db);
+        match kind {
+            SyntaxKind::TerminalDotDot => {
+                PatternRangeOperator::DotDot(TerminalDotDot::from_syntax_node(db, node))
+            }
+            SyntaxKind::TerminalDotDotEq => {
+                PatternRangeOperator::DotDotEq(TerminalDotDotEq::from_syntax_node(db, node))
+            }
+            _ => panic!(
+                "Unexpected syntax kind {:?} when constructing {}.",
+                kind, "PatternRangeOperator"
+            ),
+        }
+    }
+    fn cast(db: &dyn SyntaxGroup, node: SyntaxNode) -> Option<Self> {
+        let kind = node.kind(db);
+        match kind {
+            SyntaxKind::TerminalDotDot => {
+                Some(PatternRangeOperator::DotDot(TerminalDotDot::from_syntax_node(db, node)))
+            }
+            SyntaxKind::TerminalDotDotEq => {
+                Some(PatternRangeOperator::DotDotEq(TerminalDotDotEq::from_syntax_node(db, node)))
+            }
+            _ => None,
+        }
+    }
+    fn as_syntax_node(&self) -> SyntaxNode {
+        match self {
+            PatternRangeOperator::DotDot(x) => x.as_syntax_node(),
+            PatternRangeOperator::DotDotEq(x) => x.as_syntax_node(),
+        }
+    }
+    fn stable_ptr(&self) -> Self::StablePtr {
+        PatternRangeOperatorPtr(self.as_syntax_node().0.stable_ptr)
+    }
+}
+impl From<&PatternRangeOperator> for SyntaxStablePtrId {
+    fn from(node: &PatternRangeOperator) -> Self {
+        node.stable_ptr().untyped()
+    }
+}
+impl PatternRangeOperator {
+    /// Checks if a kind of a variant of [PatternRangeOperator].
+    pub fn is_variant(kind: SyntaxKind) -> bool {
+        matches!(kind, SyntaxKind::TerminalDotDot | SyntaxKind::TerminalDotDotEq)
+    }
+}
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PatternOr {
+    node: SyntaxNode,
+    children: Arc<[SyntaxNode]>,
+}
+impl PatternOr {
+    pub const INDEX_PATTERNS: usize = 0;
+    pub fn new_green(db: &dyn SyntaxGroup, patterns: PatternListOrGreen) -> PatternOrGreen {
+        let children: Vec<GreenId> = vec![patterns.0];
+        let width = children.iter().copied().map(|id| id.lookup_intern(db).width()).sum();
+        PatternOrGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::PatternOr,
+                details: GreenNodeDetails::Node { children, width },
+            })
+            .intern(db),
+        )
+    }
+}
+impl PatternOr {
+    pub fn patterns(&self, db: &dyn SyntaxGroup) -> PatternListOr {
+        PatternListOr::from_syntax_node(db, self.children[0].clone())
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PatternOrPtr(pub SyntaxStablePtrId);
+impl PatternOrPtr {}
+impl TypedStablePtr for PatternOrPtr {
+    type SyntaxNode = PatternOr;
+    fn untyped(&self) -> SyntaxStablePtrId {
+        self.0
+    }
+    fn lookup(&self, db: &dyn SyntaxGroup) -> PatternOr {
+        PatternOr::from_syntax_node(db, self.0.lookup(db))
+    }
+}
+impl From<PatternOrPtr> for SyntaxStablePtrId {
+    fn from(ptr: PatternOrPtr) -> Self {
+        ptr.untyped()
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PatternOrGreen(pub GreenId);
+impl TypedSyntaxNode for PatternOr {
+    const OPTIONAL_KIND: Option<SyntaxKind> = Some(SyntaxKind::PatternOr);
+    type StablePtr = PatternOrPtr;
+    type Green = PatternOrGreen;
+    fn missing(db: &dyn SyntaxGroup) -> Self::Green {
+        PatternOrGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::PatternOr,
+                details: GreenNodeDetails::Node {
+                    children: vec![PatternListOr::missing(db).0],
+                    width: TextWidth::default(),
+                },
+            })
+            .intern(db),
+        )
+    }
+    fn from_syntax_node(db: &dyn SyntaxGroup, node: SyntaxNode) -> Self {
+        let kind = node.kind(db);
+        assert_eq!(
+            kind,
+            SyntaxKind::PatternOr,
+            "Unexpected SyntaxKind {:?}. Expected {:?}.",
+            kind,
+            SyntaxKind::PatternOr
+        );
+        let children = db.get_children(node.clone());
+        Self { node, children }
+    }
+    fn cast(db: &dyn SyntaxGroup, node: SyntaxNode) -> Option<Self> {
+        let kind = node.kind(db);
+        if kind == SyntaxKind::PatternOr { Some(Self::from_syntax_node(db, node)) } else { None }
+    }
+    fn as_syntax_node(&self) -> SyntaxNode {
+        self.node.clone()
+    }
+    fn stable_ptr(&self) -> Self::StablePtr {
+        PatternOrPtr(self.node.0.stable_ptr)
+    }
+}
+impl From<&PatternOr> for SyntaxStablePtrId {
+    fn from(node: &PatternOr) -> Self {
+        node.stable_ptr().untyped()
+    }
+}
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PatternBinding {
+    node: SyntaxNode,
+    children: Arc<[SyntaxNode]>,
+}
+impl PatternBinding {
+    pub const INDEX_BINDING: usize = 0;
+    pub const INDEX_AT: usize = 1;
+    pub const INDEX_PATTERN: usize = 2;
+    pub fn new_green(
+        db: &dyn SyntaxGroup,
+        binding: PatternIdentifierGreen,
+        at: TerminalAtGreen,
+        pattern: PatternGreen,
+    ) -> PatternBindingGreen {
+        let children: Vec<GreenId> = vec![binding.0, at.0, pattern.0];
+        let width = children.iter().copied().map(|id| id.lookup_intern(db).width()).sum();
+        PatternBindingGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::PatternBinding,
+                details: GreenNodeDetails::Node { children, width },
+            })
+            .intern(db),
+        )
+    }
+}
+impl PatternBinding {
+    pub fn binding(&self, db: &dyn SyntaxGroup) -> PatternIdentifier {
+        PatternIdentifier::from_syntax_node(db, self.children[0].clone())
+    }
+    pub fn at(&self, db: &dyn SyntaxGroup) -> TerminalAt {
+        TerminalAt::from_syntax_node(db, self.children[1].clone())
+    }
+    pub fn pattern(&self, db: &dyn SyntaxGroup) -> Pattern {
+        Pattern::from_syntax_node(db, self.children[2].clone())
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PatternBindingPtr(pub SyntaxStablePtrId);
+impl PatternBindingPtr {}
+impl TypedStablePtr for PatternBindingPtr {
+    type SyntaxNode = PatternBinding;
+    fn untyped(&self) -> SyntaxStablePtrId {
+        self.0
+    }
+    fn lookup(&self, db: &dyn SyntaxGroup) -> PatternBinding {
+        PatternBinding::from_syntax_node(db, self.0.lookup(db))
+    }
+}
+impl From<PatternBindingPtr> for SyntaxStablePtrId {
+    fn from(ptr: PatternBindingPtr) -> Self {
+        ptr.untyped()
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PatternBindingGreen(pub GreenId);
+impl TypedSyntaxNode for PatternBinding {
+    const OPTIONAL_KIND: Option<SyntaxKind> = Some(SyntaxKind::PatternBinding);
+    type StablePtr = PatternBindingPtr;
+    type Green = PatternBindingGreen;
+    fn missing(db: &dyn SyntaxGroup) -> Self::Green {
+        PatternBindingGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::PatternBinding,
+                details: GreenNodeDetails::Node {
+                    children: vec![
+                        PatternIdentifier::missing(db).0,
+                        TerminalAt::missing(db).0,
+                        Pattern::missing(db).0,
+                    ],
+                    width: TextWidth::default(),
+                },
+            })
+            .intern(db),
+        )
+    }
+    fn from_syntax_node(db: &dyn SyntaxGroup, node: SyntaxNode) -> Self {
+        let kind = node.kind(db);
+        assert_eq!(
+            kind,
+            SyntaxKind::PatternBinding,
+            "Unexpected SyntaxKind {:?}. Expected {:?}.",
+            kind,
+            SyntaxKind::PatternBinding
+        );
+        let children = db.get_children(node.clone());
+        Self { node, children }
+    }
+    fn cast(db: &dyn SyntaxGroup, node: SyntaxNode) -> Option<Self> {
+        let kind = node.kind(db);
+        if kind == SyntaxKind::PatternBinding {
+            Some(Self::from_syntax_node(db, node))
+        } else {
+            None
+        }
+    }
+    fn as_syntax_node(&self) -> SyntaxNode {
+        self.node.clone()
+    }
+    fn stable_ptr(&self) -> Self::StablePtr {
+        PatternBindingPtr(self.node.0.stable_ptr)
+    }
+}
+impl From<&PatternBinding> for SyntaxStablePtrId {
+    fn from(node: &PatternBinding) -> Self {
+        node.stable_ptr().untyped()
+    }
+}
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PatternRest {
+    node: SyntaxNode,
+    children: Arc<[SyntaxNode]>,
+}
+impl PatternRest {
+    pub const INDEX_DOTDOT: usize = 0;
+    pub const INDEX_BINDING: usize = 1;
+    pub fn new_green(
+        db: &dyn SyntaxGroup,
+        dotdot: TerminalDotDotGreen,
+        binding: OptionPatternIdentifierGreen,
+    ) -> PatternRestGreen {
+        let children: Vec<GreenId> = vec![dotdot.0, binding.0];
+        let width = children.iter().copied().map(|id| id.lookup_intern(db).width()).sum();
+        PatternRestGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::PatternRest,
+                details: GreenNodeDetails::Node { children, width },
+            })
+            .intern(db),
+        )
+    }
+}
+impl PatternRest {
+    pub fn dotdot(&self, db: &dyn SyntaxGroup) -> TerminalDotDot {
+        TerminalDotDot::from_syntax_node(db, self.children[0].clone())
+    }
+    pub fn binding(&self, db: &dyn SyntaxGroup) -> OptionPatternIdentifier {
+        OptionPatternIdentifier::from_syntax_node(db, self.children[1].clone())
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PatternRestPtr(pub SyntaxStablePtrId);
+impl PatternRestPtr {}
+impl TypedStablePtr for PatternRestPtr {
+    type SyntaxNode = PatternRest;
+    fn untyped(&self) -> SyntaxStablePtrId {
+        self.0
+    }
+    fn lookup(&self, db: &dyn SyntaxGroup) -> PatternRest {
+        PatternRest::from_syntax_node(db, self.0.lookup(db))
+    }
+}
+impl From<PatternRestPtr> for SyntaxStablePtrId {
+    fn from(ptr: PatternRestPtr) -> Self {
+        ptr.untyped()
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PatternRestGreen(pub GreenId);
+impl TypedSyntaxNode for PatternRest {
+    const OPTIONAL_KIND: Option<SyntaxKind> = Some(SyntaxKind::PatternRest);
+    type StablePtr = PatternRestPtr;
+    type Green = PatternRestGreen;
+    fn missing(db: &dyn SyntaxGroup) -> Self::Green {
+        PatternRestGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::PatternRest,
+                details: GreenNodeDetails::Node {
+                    children: vec![
+                        TerminalDotDot::missing(db).0,
+                        OptionPatternIdentifier::missing(db).0,
+                    ],
+                    width: TextWidth::default(),
+                },
+            })
+            .intern(db),
+        )
+    }
+    fn from_syntax_node(db: &dyn SyntaxGroup, node: SyntaxNode) -> Self {
+        let kind = node.kind(db);
+        assert_eq!(
+            kind,
+            SyntaxKind::PatternRest,
+            "Unexpected SyntaxKind {:?}. Expected {:?}.",
+            kind,
+            SyntaxKind::PatternRest
+        );
+        let children = db.get_children(node.clone());
+        Self { node, children }
+    }
+    fn cast(db: &dyn SyntaxGroup, node: SyntaxNode) -> Option<Self> {
+        let kind = node.kind(db);
+        if kind == SyntaxKind::PatternRest { Some(Self::from_syntax_node(db, node)) } else { None }
+    }
+    fn as_syntax_node(&self) -> SyntaxNode {
+        self.node.clone()
+    }
+    fn stable_ptr(&self) -> Self::StablePtr {
+        PatternRestPtr(self.node.0.stable_ptr)
+    }
+}
+impl From<&PatternRest> for SyntaxStablePtrId {
+    fn from(node: &PatternRest) -> Self {
+        node.stable_ptr().untyped()
+    }
+}
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum OptionPatternIdentifier {
+    Empty(OptionPatternIdentifierEmpty),
+    PatternIdentifier(PatternIdentifier),
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct OptionPatternIdentifierPtr(pub SyntaxStablePtrId);
+impl TypedStablePtr for OptionPatternIdentifierPtr {
+    type SyntaxNode = OptionPatternIdentifier;
+    fn untyped(&self) -> SyntaxStablePtrId {
+        self.0
+    }
+    fn lookup(&self, db: &dyn SyntaxGroup) -> OptionPatternIdentifier {
+        OptionPatternIdentifier::from_syntax_node(db, self.0.lookup(db))
+    }
+}
+impl From<OptionPatternIdentifierPtr> for SyntaxStablePtrId {
+    fn from(ptr: OptionPatternIdentifierPtr) -> Self {
+        ptr.untyped()
+    }
+}
+impl From<OptionPatternIdentifierEmptyPtr> for OptionPatternIdentifierPtr {
+    fn from(value: OptionPatternIdentifierEmptyPtr) -> Self {
+        Self(value.0)
+    }
+}
+impl From<PatternIdentifierPtr> for OptionPatternIdentifierPtr {
+    fn from(value: PatternIdentifierPtr) -> Self {
+        Self(value.0)
+    }
+}
+impl From<OptionPatternIdentifierEmptyGreen> for OptionPatternIdentifierGreen {
+    fn from(value: OptionPatternIdentifierEmptyGreen) -> Self {
+        Self(value.0)
+    }
+}
+impl From<PatternIdentifierGreen> for OptionPatternIdentifierGreen {
+    fn from(value: PatternIdentifierGreen) -> Self {
+        Self(value.0)
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct OptionPatternIdentifierGreen(pub GreenId);
+impl TypedSyntaxNode for OptionPatternIdentifier {
+    const OPTIONAL_KIND: Option<SyntaxKind> = None;
+    type StablePtr = OptionPatternIdentifierPtr;
+    type Green = OptionPatternIdentifierGreen;
+    fn missing(db: &dyn SyntaxGroup) -> Self::Green {
+        panic!("No missing variant.");
+    }
+    fn from_syntax_node(db: &dyn SyntaxGroup, node: SyntaxNode) -> Self {
+        let kind = node.kind(db);
+        match kind {
+            SyntaxKind::OptionPatternIdentifierEmpty => OptionPatternIdentifier::Empty(
+                OptionPatternIdentifierEmpty::from_syntax_node(db, node),
+            ),
+            SyntaxKind::PatternIdentifier => OptionPatternIdentifier::PatternIdentifier(
+                PatternIdentifier::from_syntax_node(db, node),
+            ),
+            _ => panic!(
+                "Unexpected syntax kind {:?} when constructing {}.",
+                kind, "OptionPatternIdentifier"
+            ),
+        }
+    }
+    fn cast(db: &dyn SyntaxGroup, node: SyntaxNode) -> Option<Self> {
+        let kind = node.kind(db);
+        match kind {
+            SyntaxKind::OptionPatternIdentifierEmpty => Some(OptionPatternIdentifier::Empty(
+                OptionPatternIdentifierEmpty::from_syntax_node(db, node),
+            )),
+            SyntaxKind::PatternIdentifier => Some(OptionPatternIdentifier::PatternIdentifier(
+                PatternIdentifier::from_syntax_node(db, node),
+            )),
+            _ => None,
+        }
+    }
+    fn as_syntax_node(&self) -> SyntaxNode {
+        match self {
+            OptionPatternIdentifier::Empty(x) => x.as_syntax_node(),
+            OptionPatternIdentifier::PatternIdentifier(x) => x.as_syntax_node(),
+        }
+    }
+    fn stable_ptr(&self) -> Self::StablePtr {
+        OptionPatternIdentifierPtr(self.as_syntax_node().0.stable_ptr)
+    }
+}
+impl From<&OptionPatternIdentifier> for SyntaxStablePtrId {
+    fn from(node: &OptionPatternIdentifier) -> Self {
+        node.stable_ptr().untyped()
+    }
+}
+impl OptionPatternIdentifier {
+    /// Checks if a kind of a variant of [OptionPatternIdentifier].
+    pub fn is_variant(kind: SyntaxKind) -> bool {
+        matches!(kind, SyntaxKind::OptionPatternIdentifierEmpty | SyntaxKind::PatternIdentifier)
+    }
+}
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct OptionPatternIdentifierEmpty {
+    node: SyntaxNode,
+    children: Arc<[SyntaxNode]>,
+}
+impl OptionPatternIdentifierEmpty {
+    pub fn new_green(db: &dyn SyntaxGroup) -> OptionPatternIdentifierEmptyGreen {
+        let children: Vec<GreenId> = vec![];
+        let width = children.iter().copied().map(|id| id.lookup_intern(db).width()).sum();
+        OptionPatternIdentifierEmptyGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::OptionPatternIdentifierEmpty,
+                details: GreenNodeDetails::Node { children, width },
+            })
+            .intern(db),
+        )
+    }
+}
+impl OptionPatternIdentifierEmpty {}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct OptionPatternIdentifierEmptyPtr(pub SyntaxStablePtrId);
+impl OptionPatternIdentifierEmptyPtr {}
+impl TypedStablePtr for OptionPatternIdentifierEmptyPtr {
+    type SyntaxNode = OptionPatternIdentifierEmpty;
+    fn untyped(&self) -> SyntaxStablePtrId {
+        self.0
+    }
+    fn lookup(&self, db: &dyn SyntaxGroup) -> OptionPatternIdentifierEmpty {
+        OptionPatternIdentifierEmpty::from_syntax_node(db, self.0.lookup(db))
+    }
+}
+impl From<OptionPatternIdentifierEmptyPtr> for SyntaxStablePtrId {
+    fn from(ptr: OptionPatternIdentifierEmptyPtr) -> Self {
+        ptr.untyped()
+    }
+}
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct OptionPatternIdentifierEmptyGreen(pub GreenId);
+impl TypedSyntaxNode for OptionPatternIdentifierEmpty {
+    const OPTIONAL_KIND: Option<SyntaxKind> = Some(SyntaxKind::OptionPatternIdentifierEmpty);
+    type StablePtr = OptionPatternIdentifierEmptyPtr;
+    type Green = OptionPatternIdentifierEmptyGreen;
+    fn missing(db: &dyn SyntaxGroup) -> Self::Green {
+        OptionPatternIdentifierEmptyGreen(
+            Arc::new(GreenNode {
+                kind: SyntaxKind::OptionPatternIdentifierEmpty,
+                details: GreenNodeDetails::Node { children: vec![], width: TextWidth::default() },
+            })
+            .intern(db),
+        )
+    }
+    fn from_syntax_node(db: &dyn SyntaxGroup, node: SyntaxNode) -> Self {
+        let kind = node.kind(db);
+        assert_eq!(
+            kind,
+            SyntaxKind::OptionPatternIdentifierEmpty,
+            "Unexpected SyntaxKind {:?}. Expected {:?}.",
+            kind,
+            SyntaxKind::OptionPatternIdentifierEmpty
+        );
+        let children = db.get_children(node.clone());
+        Self { node, children }
+    }
+    fn cast(db: &dyn SyntaxGroup, node: SyntaxNode) -> Option<Self> {
+        let kind = node.kind(db);
+        if kind == SyntaxKind::OptionPatternIdentifierEmpty {
+            Some(Self::from_syntax_node(db, node))
+        } else {
+            None
+        }
+    }
+    fn as_syntax_node(&self) -> SyntaxNode {
+        self.node.clone()
+    }
+    fn stable_ptr(&self) -> Self::StablePtr {
+        OptionPatternIdentifierEmptyPtr(self.node.0.stable_ptr)
+    }
+}
+impl From<&OptionPatternIdentifierEmpty> for SyntaxStablePtrId {
+    fn from(node: &OptionPatternIdentifierEmpty) -> Self {
+        node.stable_ptr().untyped()
+    }
+}
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct PatternList(ElementList<Pattern, 2>);
 impl Deref for PatternList {
     type Target = ElementList<Pattern, 2>;
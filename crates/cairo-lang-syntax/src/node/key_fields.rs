@@ -78,6 +78,12 @@ pub fn get_key_fields(kind: SyntaxKind, children: &[GreenId]) -> Vec<GreenId> {
         SyntaxKind::MatchArm => {
             vec![]
         }
+        SyntaxKind::MatchArmGuard => {
+            vec![]
+        }
+        SyntaxKind::OptionMatchArmGuardEmpty => {
+            vec![]
+        }
         SyntaxKind::ExprIf => {
             vec![]
         }
@@ -161,6 +167,21 @@ pub fn get_key_fields(kind: SyntaxKind, children: &[GreenId]) -> Vec<GreenId> {
         SyntaxKind::PatternFixedSizeArray => {
             vec![]
         }
+        SyntaxKind::PatternRange => {
+            vec![]
+        }
+        SyntaxKind::PatternOr => {
+            vec![]
+        }
+        SyntaxKind::PatternBinding => {
+            vec![]
+        }
+        SyntaxKind::PatternRest => {
+            vec![]
+        }
+        SyntaxKind::OptionPatternIdentifierEmpty => {
+            vec![]
+        }
         SyntaxKind::PatternList => vec![],
         SyntaxKind::PatternListOr => vec![],
         SyntaxKind::PatternStructParamWithExpr => {
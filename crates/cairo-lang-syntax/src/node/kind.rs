@@ -31,6 +31,8 @@ pub enum SyntaxKind {
     ExprMatch,
     MatchArms,
     MatchArm,
+    MatchArmGuard,
+    OptionMatchArmGuardEmpty,
     ExprIf,
     ConditionLet,
     ConditionExpr,
@@ -60,6 +62,11 @@ pub enum SyntaxKind {
     PatternStructParamList,
     PatternTuple,
     PatternFixedSizeArray,
+    PatternRange,
+    PatternOr,
+    PatternBinding,
+    PatternRest,
+    OptionPatternIdentifierEmpty,
     PatternList,
     PatternListOr,
     PatternStructParamWithExpr,
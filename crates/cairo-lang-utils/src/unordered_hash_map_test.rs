@@ -161,3 +161,13 @@ fn test_merge() {
     });
     assert_eq!(value, UnorderedHashMap::from_iter([(1, 21), (2, 22), (3, 13), (4, 24)]));
 }
+
+#[test]
+fn test_with_capacity() {
+    let mut map = UnorderedHashMap::<_, _>::with_capacity(4);
+    assert!(map.is_empty());
+    map.insert(1, 2);
+    map.insert(3, 4);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map[&3], 4);
+}